@@ -41,7 +41,7 @@ class PravegaReaderTest(unittest.TestCase):
         w1.write_event("test event")
         w1.write_event("test event")
 
-        r1 = stream_manager.create_reader(scope, "testStream")
+        r1 = stream_manager.create_reader(scope, "testStream", "rg")
         segment_slice = _run(self.get_segment_slice(r1))
         print(segment_slice)
         # consume the segment slice for events.
@@ -22,6 +22,7 @@ cfg_if! {
         use pyo3::PyResult;
         use pyo3::{exceptions, PyObjectProtocol};
         use log::info;
+        use uuid::Uuid;
     }
 }
 
@@ -248,23 +249,40 @@ impl StreamManager {
     }
 
     ///
-    /// Create a Reader for a given Stream.
+    /// Create a Reader for a given Stream. The reader is added to the given reader group, the
+    /// reader group is created if it does not exist.
     ///
     /// ```
     /// import pravega_client;
     /// manager=pravega_client.StreamManager("127.0.0.1:9090")
     /// // Create a reader against an already created Pravega scope and Stream.
-    /// reader=manager.create_reader("scope", "stream")
+    /// reader=manager.create_reader("scope", "stream", "rg")
     /// ```
     ///
-    #[text_signature = "($self, scope_name, stream_name)"]
-    pub fn create_reader(&self, scope_name: &str, stream_name: &str) -> PyResult<StreamReader> {
+    #[text_signature = "($self, scope_name, stream_name, reader_group_name)"]
+    pub fn create_reader(
+        &self,
+        scope_name: &str,
+        stream_name: &str,
+        reader_group_name: &str,
+    ) -> PyResult<StreamReader> {
         let scoped_stream = ScopedStream {
             scope: Scope::from(scope_name.to_string()),
             stream: Stream::from(stream_name.to_string()),
         };
         let handle = self.cf.get_runtime_handle();
-        let reader = handle.block_on(self.cf.create_event_stream_reader(scoped_stream.clone()));
+        let reader_group = match handle.block_on(self.cf.create_reader_group(
+            scoped_stream.scope.clone(),
+            reader_group_name.to_string(),
+            scoped_stream.clone(),
+        )) {
+            Ok(reader_group) => reader_group,
+            Err(e) => return Err(exceptions::ValueError::py_err(format!("{:?}", e))),
+        };
+        let reader = match handle.block_on(reader_group.create_reader(Uuid::new_v4().to_string())) {
+            Ok(reader) => reader,
+            Err(e) => return Err(exceptions::ValueError::py_err(format!("{:?}", e))),
+        };
         let stream_reader = StreamReader::new(
             Arc::new(Mutex::new(reader)),
             self.cf.get_runtime_handle(),
@@ -12,6 +12,7 @@ cfg_if! {
     if #[cfg(feature = "python_binding")] {
         use pravega_client_rust::event_reader::EventReader;
         use pravega_rust_client_shared::ScopedStream;
+        use pyo3::exceptions;
         use pyo3::prelude::*;
         use pyo3::PyResult;
         use pyo3::PyObjectProtocol;
@@ -70,14 +71,23 @@ impl StreamReader {
         self.handle.spawn(async move {
             let slice_result = read.lock().await.acquire_segment().await;
             let slice_py: Slice = match slice_result {
-                Some(slice) => Slice {
+                Ok(Some(slice)) => Slice {
                     seg_slice: slice,
                     is_empty: false,
                 },
-                None => Slice {
+                Ok(None) => Slice {
                     seg_slice: SegmentSlice::default(),
                     is_empty: true,
                 },
+                Err(e) => {
+                    let gil = Python::acquire_gil();
+                    let py = gil.python();
+                    let exception = exceptions::ValueError::py_err(format!("{:?}", e)).to_object(py);
+                    if let Err(e) = StreamReader::set_fut_exception(event_loop, py_future, exception) {
+                        e.print(py);
+                    }
+                    return;
+                }
             };
             let gil = Python::acquire_gil();
             let py = gil.python();
@@ -117,6 +127,19 @@ impl StreamReader {
         Ok(())
     }
 
+    //
+    // This is used to mark the Python future as failed with the given exception.
+    // ref: https://docs.python.org/3/library/asyncio-future.html#asyncio.Future.set_exception
+    //
+    fn set_fut_exception(event_loop: PyObject, fut: PyObject, exception: PyObject) -> PyResult<()> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let se = fut.getattr(py, "set_exception")?;
+        event_loop.call_method1(py, "call_soon_threadsafe", (se, exception))?;
+
+        Ok(())
+    }
+
     //
     // Return the running event loop in the current OS thread.
     // https://docs.python.org/3/library/asyncio-eventloop.html#asyncio.get_running_loop
@@ -26,8 +26,9 @@
 
 use futures_intrusive::sync::{GenericSemaphoreReleaser, Semaphore};
 use std::cmp::min;
+use std::fmt;
 use std::sync::Arc;
-use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::error::{SendError, TryRecvError};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 pub struct ChannelSender<T> {
@@ -76,6 +77,43 @@ impl<T> ChannelReceiver<T> {
             message
         }
     }
+
+    /// Receives the next message but keeps its capacity taken until the returned guard is dropped,
+    /// so that the capacity also covers the time it takes to process the message.
+    pub async fn recv_with_guard(&mut self) -> Option<(T, CapacityGuard)> {
+        let (message, size) = self.receiver.recv().await?;
+        let guard = CapacityGuard {
+            semaphore: self.semaphore.clone(),
+            n_permits: min(size, self.capacity),
+        };
+        Some((message, guard))
+    }
+
+    pub fn try_recv(&mut self) -> Result<(T, usize), TryRecvError> {
+        let message = self.receiver.try_recv()?;
+        self.semaphore.release(min(message.1, self.capacity));
+        Ok(message)
+    }
+}
+
+/// Returns the capacity taken by a received message to the channel when dropped.
+pub struct CapacityGuard {
+    semaphore: Arc<Semaphore>,
+    n_permits: usize,
+}
+
+impl Drop for CapacityGuard {
+    fn drop(&mut self) {
+        self.semaphore.release(self.n_permits);
+    }
+}
+
+impl fmt::Debug for CapacityGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapacityGuard")
+            .field("n_permits", &self.n_permits)
+            .finish()
+    }
 }
 
 pub fn create_channel<U>(capacity: usize) -> (ChannelSender<U>, ChannelReceiver<U>) {
@@ -109,6 +147,7 @@ mod tests {
         runtime.block_on(test_sender_block());
         runtime.block_on(test_sender_close_first());
         runtime.block_on(test_receiver_close_first());
+        runtime.block_on(test_capacity_guard());
     }
 
     async fn test_simple_test() {
@@ -240,4 +279,27 @@ mod tests {
         let result = tx.send((2, 4)).await;
         assert!(result.is_err());
     }
+
+    async fn test_capacity_guard() {
+        // can only hold 4 bytes
+        let (tx, mut rx) = create_channel(4);
+        tx.send((1, 4)).await.expect("send message to channel");
+
+        let (message, guard) = rx.recv_with_guard().await.expect("get message");
+        assert_eq!(message, 1);
+
+        // the capacity is still taken by the received message
+        let tx1 = tx.clone();
+        let handle = tokio::spawn(async move { tx1.send((2, 4)).await });
+        tokio::time::delay_for(tokio::time::Duration::from_millis(100)).await;
+        assert!(rx.try_recv().is_err());
+
+        // messages without size never block
+        tx.send((3, 0)).await.expect("send message to channel");
+        assert_eq!(rx.try_recv().expect("get message"), (3, 0));
+
+        drop(guard);
+        handle.await.expect("join").expect("send message to channel");
+        assert_eq!(rx.recv().await, Some((2, 4)));
+    }
 }
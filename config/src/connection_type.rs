@@ -22,6 +22,9 @@ pub enum MockType {
     SegmentIsSealed,
     SegmentIsTruncated,
     WrongHost,
+    // behaves like Happy but the connection is lost once a conditional append is applied,
+    // before its reply is received.
+    ConnectionLostAfterAppend,
 }
 
 impl Default for ConnectionType {
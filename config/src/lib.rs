@@ -85,6 +85,60 @@ pub struct ClientConfig {
     #[get_copy = "pub"]
     #[builder(default = "false")]
     pub is_auth_enabled: bool,
+
+    /// Maximum size in bytes of the events sent in one append block.
+    #[get_copy = "pub"]
+    #[builder(default = "8 * 1024 * 1024 + 8")]
+    pub max_batch_bytes: usize,
+
+    /// Maximum number of events sent in one append block.
+    #[get_copy = "pub"]
+    #[builder(default = "500")]
+    pub max_batch_events: usize,
+
+    /// Time in milliseconds a writer waits for more events before sending a batch
+    /// that has not reached the size thresholds. Zero sends the pending events right away.
+    #[get_copy = "pub"]
+    #[builder(default = "0")]
+    pub batch_linger_time: u64,
+
+    /// Maximum size in bytes of the events a writer holds before they are acknowledged by the
+    /// segment store. Writing an event waits once it is reached.
+    #[get_copy = "pub"]
+    #[builder(default = "16 * 1024 * 1024")]
+    pub max_outstanding_bytes: usize,
+
+    /// Whether an event stream writer accepts events larger than the maximum size of a single
+    /// append. Such an event is written in chunks to a transient segment which is then merged
+    /// into the segment of the event, so that the readers see the whole event at once.
+    #[get_copy = "pub"]
+    #[builder(default = "false")]
+    pub enable_large_events: bool,
+
+    /// Number of reads a byte stream reader keeps outstanding ahead of its current offset, the
+    /// reads are served from a local buffer. Zero disables read-ahead, every read is then sent
+    /// to the segment store for the requested number of bytes.
+    #[get_copy = "pub"]
+    #[builder(default = "0")]
+    pub byte_stream_prefetch_reads: usize,
+
+    /// Size in bytes requested by each read-ahead read of a byte stream reader.
+    #[get_copy = "pub"]
+    #[builder(default = "1024 * 1024")]
+    pub byte_stream_read_size: usize,
+
+    /// Time in milliseconds a byte stream reader waits before reading again when it finds no
+    /// data at the tail of a segment. The wait is doubled for every further read that finds no
+    /// data, up to `byte_stream_max_tail_poll_interval`.
+    #[get_copy = "pub"]
+    #[builder(default = "10")]
+    pub byte_stream_tail_poll_interval: u64,
+
+    /// Maximum time in milliseconds a byte stream reader waits between reads at the tail of a
+    /// segment.
+    #[get_copy = "pub"]
+    #[builder(default = "1000")]
+    pub byte_stream_max_tail_poll_interval: u64,
 }
 
 impl ClientConfigBuilder {
@@ -148,6 +202,30 @@ mod tests {
         assert_eq!(config.max_controller_connections(), 3u32);
         assert_eq!(config.connection_type(), ConnectionType::Tokio);
         assert_eq!(config.retry_policy(), RetryWithBackoff::default());
+        assert_eq!(config.max_batch_bytes(), 8 * 1024 * 1024 + 8);
+        assert_eq!(config.max_batch_events(), 500);
+        assert_eq!(config.batch_linger_time(), 0);
+        assert_eq!(config.max_outstanding_bytes(), 16 * 1024 * 1024);
+        assert!(!config.enable_large_events());
+        assert_eq!(config.byte_stream_prefetch_reads(), 0);
+        assert_eq!(config.byte_stream_read_size(), 1024 * 1024);
+        assert_eq!(config.byte_stream_tail_poll_interval(), 10);
+        assert_eq!(config.byte_stream_max_tail_poll_interval(), 1000);
+    }
+
+    #[test]
+    fn test_batch_config() {
+        let config = ClientConfigBuilder::default()
+            .controller_uri(MOCK_CONTROLLER_URI)
+            .max_batch_bytes(64 * 1024_usize)
+            .max_batch_events(1000_usize)
+            .batch_linger_time(5_u64)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.max_batch_bytes(), 64 * 1024);
+        assert_eq!(config.max_batch_events(), 1000);
+        assert_eq!(config.batch_linger_time(), 5);
     }
 
     #[test]
@@ -33,13 +33,14 @@ use std::time::{Duration, Instant};
 use async_trait::async_trait;
 use controller::{
     controller_service_client::ControllerServiceClient, create_scope_status, create_stream_status,
-    delete_scope_status, delete_stream_status, ping_txn_status, scale_request, scale_response,
-    scale_status_response, txn_state, txn_status, update_stream_status, CreateScopeStatus,
-    CreateStreamStatus, CreateTxnRequest, CreateTxnResponse, DelegationToken, DeleteScopeStatus,
-    DeleteStreamStatus, GetEpochSegmentsRequest, GetSegmentsRequest, NodeUri, PingTxnRequest, PingTxnStatus,
-    ScaleRequest, ScaleResponse, ScaleStatusRequest, ScaleStatusResponse, ScopeInfo, SegmentId,
-    SegmentRanges, SegmentsAtTime, StreamConfig, StreamInfo, SuccessorResponse, TxnId, TxnRequest, TxnState,
-    TxnStatus, UpdateStreamStatus,
+    delete_scope_status, delete_stream_status, ping_txn_status, remove_writer_response, scale_request,
+    scale_response, scale_status_response, timestamp_response, txn_state, txn_status, update_stream_status,
+    CreateScopeStatus, CreateStreamStatus, CreateTxnRequest, CreateTxnResponse, DelegationToken,
+    DeleteScopeStatus, DeleteStreamStatus, GetEpochSegmentsRequest, GetSegmentsRequest, NodeUri,
+    PingTxnRequest, PingTxnStatus, RemoveWriterRequest, RemoveWriterResponse, ScaleRequest, ScaleResponse,
+    ScaleStatusRequest, ScaleStatusResponse, ScopeInfo, SegmentId, SegmentRanges, SegmentsAtTime,
+    StreamConfig, StreamInfo, SuccessorResponse, TimestampFromWriter, TimestampResponse, TxnId, TxnRequest,
+    TxnState, TxnStatus, UpdateStreamStatus,
 };
 use im::HashMap as ImHashMap;
 use pravega_rust_client_config::credentials::AUTHORIZATION;
@@ -264,6 +265,23 @@ pub trait ControllerClient: Send + Sync {
     ///in  progress.
     ///
     async fn check_scale(&self, stream: &ScopedStream, scale_epoch: i32) -> ResultRetry<bool>;
+
+    ///
+    /// Notes the time of a writer together with its position in the stream. The controller uses
+    /// the times noted by all the writers of a stream to generate the watermarks of the stream.
+    ///
+    async fn note_timestamp_from_writer(
+        &self,
+        writer_id: WriterId,
+        timestamp: Timestamp,
+        position: &StreamCut,
+    ) -> ResultRetry<()>;
+
+    ///
+    /// Removes a writer from the watermark computation of a stream, so that the watermarks
+    /// are no longer held back by it.
+    ///
+    async fn remove_writer(&self, stream: &ScopedStream, writer_id: WriterId) -> ResultRetry<()>;
 }
 
 pub struct ControllerClientImpl {
@@ -479,6 +497,25 @@ impl ControllerClient for ControllerClientImpl {
             self.call_check_scale(stream, scale_epoch)
         )
     }
+
+    async fn note_timestamp_from_writer(
+        &self,
+        writer_id: WriterId,
+        timestamp: Timestamp,
+        position: &StreamCut,
+    ) -> ResultRetry<()> {
+        wrap_with_async_retry!(
+            self.config.retry_policy.max_tries(MAX_RETRIES),
+            self.call_note_timestamp_from_writer(writer_id, timestamp.clone(), position)
+        )
+    }
+
+    async fn remove_writer(&self, stream: &ScopedStream, writer_id: WriterId) -> ResultRetry<()> {
+        wrap_with_async_retry!(
+            self.config.retry_policy.max_tries(MAX_RETRIES),
+            self.call_remove_writer(stream, writer_id)
+        )
+    }
 }
 
 impl ControllerClientImpl {
@@ -912,10 +949,10 @@ impl ControllerClientImpl {
                     operation: operation_name.into(),
                     error_msg: "Ping transaction failed, Reason:ScaleGraceTimeExceeded".into(),
                 }),
-                _ => Err(ControllerError::OperationError {
-                    can_retry: true, // retry for all other errors
+                Status::Unknown => Err(ControllerError::OperationError {
+                    can_retry: false, // do not retry, the transaction is not found.
                     operation: operation_name.into(),
-                    error_msg: "Operation failed".into(),
+                    error_msg: "Ping transaction failed, Reason:Unknown transaction".into(),
                 }),
             },
             Err(status) => Err(self.map_grpc_error(operation_name, status).await),
@@ -1150,6 +1187,80 @@ impl ControllerClientImpl {
         }
     }
 
+    async fn call_note_timestamp_from_writer(
+        &self,
+        writer_id: WriterId,
+        timestamp: Timestamp,
+        position: &StreamCut,
+    ) -> Result<()> {
+        use timestamp_response::Status;
+        let request = TimestampFromWriter {
+            writer: writer_id.0.to_string(),
+            position: Some(controller::StreamCut::from(position)),
+            timestamp: timestamp.0 as i64,
+        };
+        let op_status: StdResult<tonic::Response<TimestampResponse>, tonic::Status> = self
+            .get_controller_client()
+            .note_timestamp_from_writer(tonic::Request::new(request))
+            .await;
+        let operation_name = "noteTimestampFromWriter";
+        match op_status {
+            Ok(code) => match code.into_inner().result() {
+                Status::Success => Ok(()),
+                Status::InvalidTime => Err(ControllerError::OperationError {
+                    can_retry: false, // do not retry.
+                    operation: operation_name.into(),
+                    error_msg: "Note time failed, Reason:InvalidTime".into(),
+                }),
+                Status::InvalidPosition => Err(ControllerError::OperationError {
+                    can_retry: false, // do not retry.
+                    operation: operation_name.into(),
+                    error_msg: "Note time failed, Reason:InvalidPosition".into(),
+                }),
+                _ => Err(ControllerError::OperationError {
+                    can_retry: true, // retry for all other errors
+                    operation: operation_name.into(),
+                    error_msg: "Operation failed".into(),
+                }),
+            },
+            Err(status) => Err(self.map_grpc_error(operation_name, status).await),
+        }
+    }
+
+    async fn call_remove_writer(&self, stream: &ScopedStream, writer_id: WriterId) -> Result<()> {
+        use remove_writer_response::Status;
+        let request = RemoveWriterRequest {
+            writer: writer_id.0.to_string(),
+            stream: Some(StreamInfo::from(stream)),
+        };
+        let op_status: StdResult<tonic::Response<RemoveWriterResponse>, tonic::Status> = self
+            .get_controller_client()
+            .remove_writer(tonic::Request::new(request))
+            .await;
+        let operation_name = "removeWriter";
+        match op_status {
+            Ok(code) => match code.into_inner().result() {
+                Status::Success => Ok(()),
+                Status::UnknownWriter => Err(ControllerError::OperationError {
+                    can_retry: false, // do not retry.
+                    operation: operation_name.into(),
+                    error_msg: "Remove writer failed, Reason:UnknownWriter".into(),
+                }),
+                Status::StreamDoesNotExist => Err(ControllerError::OperationError {
+                    can_retry: false, // do not retry.
+                    operation: operation_name.into(),
+                    error_msg: "Remove writer failed, Reason:StreamDoesNotExist".into(),
+                }),
+                _ => Err(ControllerError::OperationError {
+                    can_retry: true, // retry for all other errors
+                    operation: operation_name.into(),
+                    error_msg: "Operation failed".into(),
+                }),
+            },
+            Err(status) => Err(self.map_grpc_error(operation_name, status).await),
+        }
+    }
+
     async fn call_get_delegation_token(&self, stream: &ScopedStream) -> Result<String> {
         let op_status: StdResult<tonic::Response<DelegationToken>, tonic::Status> = self
             .get_controller_client()
@@ -1356,9 +1467,21 @@ mod test {
 
         // test get delegation token
         let res = rt
-            .block_on(controller.get_or_refresh_delegation_token_for(scoped_stream))
+            .block_on(controller.get_or_refresh_delegation_token_for(scoped_stream.clone()))
             .expect("get delegation token");
         assert_eq!(res, "123".to_string());
+
+        // test note timestamp from writer
+        let position = StreamCut {
+            scoped_stream: scoped_stream.clone(),
+            segment_offset_map: HashMap::new(),
+        };
+        rt.block_on(controller.note_timestamp_from_writer(WriterId(0), Timestamp(0), &position))
+            .expect("note timestamp from writer");
+
+        // test remove writer
+        rt.block_on(controller.remove_writer(&scoped_stream, WriterId(0)))
+            .expect("remove writer");
     }
 
     #[derive(Default)]
@@ -30,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::SystemTime;
 use tokio::sync::{RwLock, RwLockReadGuard};
@@ -43,6 +44,12 @@ pub struct MockController {
     created_scopes: RwLock<HashMap<String, HashSet<ScopedStream>>>,
     created_streams: RwLock<HashMap<ScopedStream, StreamConfiguration>>,
     transactions: RwLock<HashMap<TxId, TransactionStatus>>,
+    // the current segments of the streams that were scaled
+    scaled_streams: RwLock<HashMap<ScopedStream, Vec<SegmentWithRange>>>,
+    // maps from a sealed segment to its successors and their predecessors
+    successors: RwLock<HashMap<ScopedSegment, ImHashMap<SegmentWithRange, Vec<Segment>>>>,
+    // the latest time and position noted by each writer that is not removed from the watermarking
+    noted_times: Arc<RwLock<HashMap<WriterId, (Timestamp, StreamCut)>>>,
 }
 
 impl MockController {
@@ -57,8 +64,37 @@ impl MockController {
             created_scopes: RwLock::new(HashMap::new()),
             created_streams: RwLock::new(HashMap::new()),
             transactions: RwLock::new(HashMap::new()),
+            scaled_streams: RwLock::new(HashMap::new()),
+            successors: RwLock::new(HashMap::new()),
+            noted_times: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// Returns the latest time and position noted by each writer which has not been removed from
+    /// the watermarking. The returned map is shared with this controller and reflects later changes.
+    pub fn get_noted_times(&self) -> Arc<RwLock<HashMap<WriterId, (Timestamp, StreamCut)>>> {
+        self.noted_times.clone()
+    }
+
+    async fn current_segments(
+        &self,
+        stream: &ScopedStream,
+    ) -> Result<Vec<SegmentWithRange>, RetryError<ControllerError>> {
+        if let Some(segments) = self.scaled_streams.read().await.get(stream) {
+            return Ok(segments.clone());
+        }
+        let segments_in_stream = get_segments_for_stream(stream, &self.created_streams.read().await)?;
+        let increment = 1.0 / segments_in_stream.len() as f64;
+        Ok(segments_in_stream
+            .into_iter()
+            .enumerate()
+            .map(|(number, segment)| SegmentWithRange {
+                scoped_segment: segment,
+                min_key: OrderedFloat(number as f64 * increment),
+                max_key: OrderedFloat((number + 1) as f64 * increment),
+            })
+            .collect())
+    }
 }
 #[async_trait]
 impl ControllerClient for MockController {
@@ -223,17 +259,12 @@ impl ControllerClient for MockController {
         &self,
         stream: &ScopedStream,
     ) -> Result<StreamSegments, RetryError<ControllerError>> {
-        let segments_in_stream = get_segments_for_stream(stream, &self.created_streams.read().await)?;
-        let mut segments = BTreeMap::new();
-        let increment = 1.0 / segments_in_stream.len() as f64;
-        for (number, segment) in segments_in_stream.into_iter().enumerate() {
-            let segment_with_range = SegmentWithRange {
-                scoped_segment: segment,
-                min_key: OrderedFloat(number as f64 * increment),
-                max_key: OrderedFloat((number + 1) as f64 * increment),
-            };
-            segments.insert(segment_with_range.max_key, segment_with_range);
-        }
+        let segments: BTreeMap<_, _> = self
+            .current_segments(stream)
+            .await?
+            .into_iter()
+            .map(|segment| (segment.max_key, segment))
+            .collect();
 
         Ok(StreamSegments {
             key_segment_map: segments.into(),
@@ -262,16 +293,13 @@ impl ControllerClient for MockController {
         })
     }
 
-    async fn get_head_segments(&self, _stream: &ScopedStream) -> ResultRetry<ImHashMap<Segment, i64>> {
-        Err(RetryError {
-            error: ControllerError::OperationError {
-                can_retry: false, // do not retry.
-                operation: "get head segments".into(),
-                error_msg: "unsupported operation.".into(),
-            },
-            total_delay: Duration::from_millis(1),
-            tries: 0,
-        })
+    async fn get_head_segments(&self, stream: &ScopedStream) -> ResultRetry<ImHashMap<Segment, i64>> {
+        // streams are never truncated with a mock controller, all segments start at offset 0.
+        let segments_in_stream = get_segments_for_stream(stream, &self.created_streams.read().await)?;
+        Ok(segments_in_stream
+            .into_iter()
+            .map(|scoped_segment| (scoped_segment.segment, 0))
+            .collect())
     }
 
     async fn create_transaction(
@@ -371,8 +399,11 @@ impl ControllerClient for MockController {
 
     async fn get_successors(
         &self,
-        _segment: &ScopedSegment,
+        segment: &ScopedSegment,
     ) -> Result<StreamSegmentsWithPredecessors, RetryError<ControllerError>> {
+        if let Some(successors) = self.successors.read().await.get(segment) {
+            return Ok(StreamSegmentsWithPredecessors::new(successors.clone()));
+        }
         // empty hash map means the stream is sealed
         Ok(StreamSegmentsWithPredecessors {
             segment_with_predecessors: ImHashMap::new(),
@@ -380,16 +411,81 @@ impl ControllerClient for MockController {
         })
     }
 
+    // Only updates the segments known to the controller, the sealed segments have to be sealed
+    // on the segment store by the caller.
     async fn scale_stream(
         &self,
-        _stream: &ScopedStream,
-        _sealed_segments: &[Segment],
-        _new_key_ranges: &[(f64, f64)],
+        stream: &ScopedStream,
+        sealed_segments: &[Segment],
+        new_key_ranges: &[(f64, f64)],
     ) -> Result<(), RetryError<ControllerError>> {
+        let current = self.current_segments(stream).await?;
+        let (sealed, mut remaining): (Vec<SegmentWithRange>, Vec<SegmentWithRange>) = current
+            .iter()
+            .cloned()
+            .partition(|segment| sealed_segments.contains(&segment.scoped_segment.segment));
+        if sealed.len() != sealed_segments.len() {
+            return Err(RetryError {
+                error: ControllerError::OperationError {
+                    can_retry: false, // do not retry.
+                    operation: "scale stream".into(),
+                    error_msg: "sealed segment is not an active segment.".into(),
+                },
+                total_delay: Duration::from_millis(1),
+                tries: 0,
+            });
+        }
+        let next_number = current
+            .iter()
+            .map(|segment| segment.scoped_segment.segment.number)
+            .max()
+            .unwrap_or(-1)
+            + 1;
+        let mut successors = self.successors.write().await;
+        for (i, (min_key, max_key)) in new_key_ranges.iter().enumerate() {
+            let new_segment = SegmentWithRange {
+                scoped_segment: ScopedSegment {
+                    scope: stream.scope.clone(),
+                    stream: stream.stream.clone(),
+                    segment: Segment::from(next_number + i as i64),
+                },
+                min_key: OrderedFloat(*min_key),
+                max_key: OrderedFloat(*max_key),
+            };
+            let predecessors: Vec<&SegmentWithRange> = sealed
+                .iter()
+                .filter(|segment| segment.min_key.0 < *max_key && segment.max_key.0 > *min_key)
+                .collect();
+            for predecessor in &predecessors {
+                successors
+                    .entry(predecessor.scoped_segment.clone())
+                    .or_insert_with(ImHashMap::new)
+                    .insert(
+                        new_segment.clone(),
+                        predecessors
+                            .iter()
+                            .map(|p| p.scoped_segment.segment.clone())
+                            .collect(),
+                    );
+            }
+            remaining.push(new_segment);
+        }
+        self.scaled_streams
+            .write()
+            .await
+            .insert(stream.clone(), remaining);
+        Ok(())
+    }
+
+    async fn check_scale(
+        &self,
+        _stream: &ScopedStream,
+        _scale_epoch: i32,
+    ) -> Result<bool, RetryError<ControllerError>> {
         Err(RetryError {
             error: ControllerError::OperationError {
                 can_retry: false, // do not retry.
-                operation: "scale stream".into(),
+                operation: "check stream scale".into(),
                 error_msg: "unsupported operation.".into(),
             },
             total_delay: Duration::from_millis(1),
@@ -397,16 +493,48 @@ impl ControllerClient for MockController {
         })
     }
 
-    async fn check_scale(
+    async fn note_timestamp_from_writer(
         &self,
-        _stream: &ScopedStream,
-        _scale_epoch: i32,
-    ) -> Result<bool, RetryError<ControllerError>> {
+        writer_id: WriterId,
+        timestamp: Timestamp,
+        position: &StreamCut,
+    ) -> Result<(), RetryError<ControllerError>> {
+        check_stream_exists(
+            &position.scoped_stream,
+            &self.created_streams.read().await,
+            "note time",
+        )?;
+        self.noted_times
+            .write()
+            .await
+            .insert(writer_id, (timestamp, position.clone()));
+        Ok(())
+    }
+
+    async fn remove_writer(
+        &self,
+        stream: &ScopedStream,
+        writer_id: WriterId,
+    ) -> Result<(), RetryError<ControllerError>> {
+        check_stream_exists(stream, &self.created_streams.read().await, "remove writer")?;
+        self.noted_times.write().await.remove(&writer_id);
+        Ok(())
+    }
+}
+
+fn check_stream_exists(
+    stream: &ScopedStream,
+    created_streams: &RwLockReadGuard<HashMap<ScopedStream, StreamConfiguration>>,
+    operation: &str,
+) -> Result<(), RetryError<ControllerError>> {
+    if created_streams.contains_key(stream) {
+        Ok(())
+    } else {
         Err(RetryError {
             error: ControllerError::OperationError {
                 can_retry: false, // do not retry.
-                operation: "check stream scale".into(),
-                error_msg: "unsupported operation.".into(),
+                operation: operation.into(),
+                error_msg: "stream does not exist.".into(),
             },
             total_delay: Duration::from_millis(1),
             tries: 0,
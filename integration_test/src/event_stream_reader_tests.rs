@@ -60,14 +60,21 @@ async fn test_stream_scaling(client_factory: &ClientFactory) {
         stream: stream_name.clone(),
     };
 
-    let mut reader = client_factory.create_event_stream_reader(str).await;
+    let reader_group = client_factory
+        .create_reader_group(scope_name.clone(), "rg_scaling".to_string(), str)
+        .await
+        .expect("create reader group");
+    let mut reader = reader_group
+        .create_reader("r1".to_string())
+        .await
+        .expect("create reader");
     let mut event_count = 0;
     loop {
         if event_count == NUM_EVENTS + NUM_EVENTS {
             // all events have been read. Exit test.
             break;
         }
-        if let Some(mut slice) = reader.acquire_segment().await {
+        if let Some(mut slice) = reader.acquire_segment().await.expect("acquire segment") {
             loop {
                 if let Some(event) = slice.next() {
                     assert_eq!(b"aaa", event.value.as_slice(), "Corrupted event read");
@@ -115,9 +122,16 @@ async fn test_read_api(client_factory: &ClientFactory) {
         stream: stream_name.clone(),
     };
 
-    let mut reader = client_factory.create_event_stream_reader(str).await;
+    let reader_group = client_factory
+        .create_reader_group(scope_name.clone(), "rg_read_api".to_string(), str)
+        .await
+        .expect("create reader group");
+    let mut reader = reader_group
+        .create_reader("r1".to_string())
+        .await
+        .expect("create reader");
     let mut event_count = 0;
-    while let Some(mut slice) = reader.acquire_segment().await {
+    while let Some(mut slice) = reader.acquire_segment().await.expect("acquire segment") {
         loop {
             if let Some(event) = slice.next() {
                 assert_eq!(b"aaa", event.value.as_slice(), "Corrupted event read");
@@ -207,6 +207,8 @@ impl From<&str> for ScopedSegment {
         if NameUtils::is_transaction_segment(qualified_name) {
             let original_segment_name = NameUtils::get_parent_stream_segment_name(qualified_name);
             ScopedSegment::from(original_segment_name)
+        } else if NameUtils::is_transient_segment(qualified_name) {
+            ScopedSegment::from(NameUtils::get_parent_of_transient_segment(qualified_name))
         } else {
             let mut tokens = NameUtils::extract_segment_tokens(qualified_name.to_owned());
             if tokens.len() == 2 {
@@ -334,6 +336,8 @@ pub enum PingStatus {
     Ok = 0,
     Committed = 1,
     Aborted = 2,
+    /// The transaction could not be pinged, its lease is no longer extended.
+    Failed = 3,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
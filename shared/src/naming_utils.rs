@@ -13,6 +13,8 @@ const EPOCH_DELIMITER: &str = ".#epoch.";
 const TRANSACTION_DELIMITER: &str = "#transaction.";
 const TRANSACTION_PART_LENGTH: i32 = 16;
 const TRANSACTION_ID_LENGTH: i32 = 2 * TRANSACTION_PART_LENGTH;
+const TRANSIENT_DELIMITER: &str = "#transient.";
+const TRANSIENT_ID_LENGTH: usize = 32;
 
 pub struct NameUtils {}
 
@@ -80,10 +82,32 @@ impl NameUtils {
         }
     }
 
+    /// Returns the name of a transient segment that is merged into the given segment, the id
+    /// tells apart the transient segments of the same segment.
+    pub fn get_transient_name_from_id(parent_segment_name: &str, id: u128) -> String {
+        format!("{}{}{:032X}", parent_segment_name, TRANSIENT_DELIMITER, id)
+    }
+
+    pub fn is_transient_segment(stream_segment_name: &str) -> bool {
+        match stream_segment_name.rfind(TRANSIENT_DELIMITER) {
+            Some(pos) => pos + TRANSIENT_DELIMITER.len() + TRANSIENT_ID_LENGTH == stream_segment_name.len(),
+            None => false,
+        }
+    }
+
+    pub fn get_parent_of_transient_segment(transient_name: &str) -> &str {
+        if !NameUtils::is_transient_segment(transient_name) {
+            panic!("name is not legal");
+        }
+        &transient_name[..transient_name.len() - TRANSIENT_DELIMITER.len() - TRANSIENT_ID_LENGTH]
+    }
+
     pub fn extract_segment_tokens(qualified_name: String) -> Vec<String> {
         assert!(!qualified_name.is_empty());
         let original_segment_name = if NameUtils::is_transaction_segment(&qualified_name) {
             String::from(NameUtils::get_parent_stream_segment_name(&qualified_name))
+        } else if NameUtils::is_transient_segment(&qualified_name) {
+            String::from(NameUtils::get_parent_of_transient_segment(&qualified_name))
         } else {
             qualified_name
         };
@@ -187,6 +211,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_transient_segment_name() {
+        let segment_id = NameUtils::compute_segment_id(10, 100);
+        let parent_name =
+            NameUtils::get_qualified_stream_segment_name("testScope", "testStream", segment_id, None);
+        let transient_name = NameUtils::get_transient_name_from_id(&parent_name, 1);
+        assert!(NameUtils::is_transient_segment(&transient_name));
+        assert!(!NameUtils::is_transient_segment(&parent_name));
+        assert_eq!(
+            NameUtils::get_parent_of_transient_segment(&transient_name),
+            parent_name
+        );
+        assert_eq!(
+            NameUtils::extract_segment_tokens(transient_name),
+            NameUtils::extract_segment_tokens(parent_name)
+        );
+    }
+
     #[test]
     fn test_extract_segment_tokens() {
         let segment_id = NameUtils::compute_segment_id(10, 100);
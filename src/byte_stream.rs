@@ -15,22 +15,33 @@ use crate::get_random_u128;
 use crate::reactor::event::{Incoming, PendingEvent};
 use crate::reactor::reactors::Reactor;
 use crate::segment_metadata::SegmentMetadataClient;
-use crate::segment_reader::{AsyncSegmentReader, AsyncSegmentReaderImpl};
+use crate::segment_reader::{AsyncSegmentReader, AsyncSegmentReaderImpl, ReaderError};
+use bytes::{Buf, Bytes};
+use futures::ready;
+use pravega_rust_client_channel::{create_channel, ChannelSender};
+use pravega_rust_client_config::ClientConfig;
 use pravega_rust_client_shared::{ScopedSegment, ScopedStream, WriterId};
+use pravega_wire_protocol::commands::SegmentReadCommand;
 use std::cmp;
+use std::collections::VecDeque;
 use std::convert::TryInto;
+use std::future::Future;
 use std::io::{Error, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
 use tokio::runtime::Handle;
-use tokio::sync::mpsc::{channel, Sender};
 use tokio::sync::oneshot;
-use tokio::sync::oneshot::error::TryRecvError;
-use tokio::time::{timeout, Duration};
-use tracing::info_span;
+use tokio::sync::oneshot::error::{RecvError, TryRecvError};
+use tokio::task::JoinHandle;
+use tokio::time::{delay_for, timeout, Duration, Elapsed};
+use tracing::{debug, info_span};
 use tracing_futures::Instrument;
 use uuid::Uuid;
 
 const BUFFER_SIZE: usize = 4096;
-const CHANNEL_CAPACITY: usize = 100;
 
 type EventHandle = oneshot::Receiver<Result<(), SegmentWriterError>>;
 
@@ -41,12 +52,19 @@ type EventHandle = oneshot::Receiver<Result<(), SegmentWriterError>>;
 /// As such, any bytes written by this API can ONLY be read using ByteStreamReader.
 /// Similarly, unless some sort of framing is added it is probably an error to have multiple
 /// ByteStreamWriters write to the same segment as this will result in interleaved data.
+///
+/// A writer created for a stream writes to the single active segment of the stream and moves on
+/// to its successor when the stream scales, so the stream must have one segment at a time.
 pub struct ByteStreamWriter {
     writer_id: WriterId,
-    sender: Sender<Incoming>,
+    sender: ChannelSender<Incoming>,
+    segment: ScopedSegment,
     metadata_client: SegmentMetadataClient,
     runtime_handle: Handle,
     event_handle: Option<EventHandle>,
+    // set if the writer follows the active segment of the stream.
+    stream: Option<ScopedStream>,
+    factory: ClientFactory,
 }
 
 /// ByteStreamWriter implements Write trait in standard library.
@@ -59,19 +77,8 @@ impl Write for ByteStreamWriter {
         let bytes_to_write = std::cmp::min(buf.len(), EventStreamWriter::MAX_EVENT_SIZE);
         let oneshot_receiver = self.runtime_handle.block_on(async {
             let payload = buf[0..bytes_to_write].to_vec();
-            let mut oneshot_receiver = ByteStreamWriter::write_internal(self.sender.clone(), payload).await;
-            match oneshot_receiver.try_recv() {
-                // The channel is currently empty
-                Err(TryRecvError::Empty) => Ok(Some(oneshot_receiver)),
-                Err(e) => Err(Error::new(ErrorKind::Other, format!("oneshot error {:?}", e))),
-                Ok(res) => {
-                    if let Err(e) = res {
-                        Err(Error::new(ErrorKind::Other, format!("{:?}", e)))
-                    } else {
-                        Ok(None)
-                    }
-                }
-            }
+            let oneshot_receiver = ByteStreamWriter::write_internal(self.sender.clone(), payload).await;
+            ByteStreamWriter::check_event_handle(oneshot_receiver)
         })?;
 
         self.event_handle = oneshot_receiver;
@@ -81,7 +88,8 @@ impl Write for ByteStreamWriter {
     /// This is a blocking call that will wait for data to be persisted on the server side.
     fn flush(&mut self) -> Result<(), Error> {
         if let Some(event_handle) = self.event_handle.take() {
-            self.runtime_handle.block_on(self.flush_internal(event_handle))
+            self.runtime_handle
+                .block_on(ByteStreamWriter::flush_internal(event_handle))
         } else {
             Ok(())
         }
@@ -90,7 +98,18 @@ impl Write for ByteStreamWriter {
 
 impl ByteStreamWriter {
     pub(crate) fn new(segment: ScopedSegment, factory: ClientFactory) -> Self {
-        let (sender, receiver) = channel(CHANNEL_CAPACITY);
+        ByteStreamWriter::create(segment, None, factory)
+    }
+
+    pub(crate) fn new_for_stream(stream: ScopedStream, factory: ClientFactory) -> Result<Self, Error> {
+        let segment = factory
+            .get_runtime_handle()
+            .block_on(active_segment(&stream, &factory))?;
+        Ok(ByteStreamWriter::create(segment, Some(stream), factory))
+    }
+
+    fn create(segment: ScopedSegment, stream: Option<ScopedStream>, factory: ClientFactory) -> Self {
+        let (sender, receiver) = create_channel(factory.get_config().max_outstanding_bytes());
         let handle = factory.get_runtime_handle();
         let metadata_client = handle.block_on(factory.create_segment_metadata_client(segment.clone()));
         let writer_id = WriterId(get_random_u128());
@@ -100,6 +119,7 @@ impl ByteStreamWriter {
             tokio::spawn(
                 Reactor::run(
                     ScopedStream::from(&segment),
+                    writer_id,
                     sender.clone(),
                     receiver,
                     factory.clone(),
@@ -110,17 +130,28 @@ impl ByteStreamWriter {
         ByteStreamWriter {
             writer_id,
             sender,
+            segment,
             metadata_client,
             runtime_handle: handle,
             event_handle: None,
+            stream,
+            factory,
         }
     }
 
-    /// Seals the segment and no further writes are allowed.
+    /// Returns the segment that is written to, for a writer of a stream this is the active
+    /// segment of the stream when it was last sealed or truncated.
+    pub fn current_segment(&self) -> &ScopedSegment {
+        &self.segment
+    }
+
+    /// Seals the segment and no further writes are allowed. For a writer of a stream the
+    /// active segment of the stream is sealed, which ends the stream for its readers.
     pub async fn seal(&mut self) -> Result<(), Error> {
         if let Some(event_handle) = self.event_handle.take() {
-            self.flush_internal(event_handle).await?;
+            ByteStreamWriter::flush_internal(event_handle).await?;
         }
+        self.refresh_segment().await?;
         self.metadata_client
             .seal_segment()
             .await
@@ -128,22 +159,40 @@ impl ByteStreamWriter {
     }
 
     /// Truncates data before a given offset for the segment. No reads are allowed before
-    /// truncation point after calling this method.
-    pub async fn truncate_data_before(&self, offset: i64) -> Result<(), Error> {
+    /// truncation point after calling this method. For a writer of a stream the offset is
+    /// an offset in the active segment of the stream.
+    pub async fn truncate_data_before(&mut self, offset: i64) -> Result<(), Error> {
+        self.refresh_segment().await?;
         self.metadata_client
             .truncate_segment(offset)
             .await
             .map_err(|e| Error::new(ErrorKind::Other, format!("segment truncation error: {:?}", e)))
     }
 
+    // The reactor moves on to the successor of a sealed segment by itself, the metadata client
+    // has to be moved to the active segment of the stream.
+    async fn refresh_segment(&mut self) -> Result<(), Error> {
+        let stream = match &self.stream {
+            Some(stream) => stream,
+            None => return Ok(()),
+        };
+        let segment = active_segment(stream, &self.factory).await?;
+        if segment != self.segment {
+            self.metadata_client = self.factory.create_segment_metadata_client(segment.clone()).await;
+            self.segment = segment;
+        }
+        Ok(())
+    }
+
     async fn write_internal(
-        mut sender: Sender<Incoming>,
+        sender: ChannelSender<Incoming>,
         event: Vec<u8>,
     ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
         let (tx, rx) = oneshot::channel();
         if let Some(pending_event) = PendingEvent::without_header(None, event, tx) {
+            let size = pending_event.data.len();
             let append_event = Incoming::AppendEvent(pending_event);
-            if let Err(_e) = sender.send(append_event).await {
+            if let Err(_e) = sender.send((append_event, size)).await {
                 let (tx_error, rx_error) = oneshot::channel();
                 tx_error
                     .send(Err(SegmentWriterError::SendToProcessor {}))
@@ -154,10 +203,40 @@ impl ByteStreamWriter {
         rx
     }
 
-    async fn flush_internal(&self, event_handle: EventHandle) -> Result<(), Error> {
-        let result = event_handle
-            .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("oneshot error {:?}", e)))?;
+    // Returns the handle if the event is not acknowledged yet, or the error if it failed.
+    fn check_event_handle(mut event_handle: EventHandle) -> Result<Option<EventHandle>, Error> {
+        match event_handle.try_recv() {
+            // The channel is currently empty
+            Err(TryRecvError::Empty) => Ok(Some(event_handle)),
+            Err(e) => Err(Error::new(ErrorKind::Other, format!("oneshot error {:?}", e))),
+            Ok(res) => {
+                if let Err(e) = res {
+                    Err(Error::new(ErrorKind::Other, format!("{:?}", e)))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    async fn flush_internal(event_handle: EventHandle) -> Result<(), Error> {
+        ByteStreamWriter::ack_result(event_handle.await)
+    }
+
+    // Shuts down the reactor once the data written so far is acknowledged.
+    fn close_reactor(sender: ChannelSender<Incoming>, handle: &Handle) {
+        let (tx, _rx) = oneshot::channel();
+        handle.enter(|| {
+            tokio::spawn(async move {
+                if sender.send((Incoming::Close(tx), 0)).await.is_err() {
+                    debug!("reactor is already closed");
+                }
+            })
+        });
+    }
+
+    fn ack_result(ack: Result<Result<(), SegmentWriterError>, RecvError>) -> Result<(), Error> {
+        let result = ack.map_err(|e| Error::new(ErrorKind::Other, format!("oneshot error {:?}", e)))?;
 
         if let Err(e) = result {
             Err(Error::new(ErrorKind::Other, format!("{:?}", e)))
@@ -167,41 +246,80 @@ impl ByteStreamWriter {
     }
 }
 
+impl Drop for ByteStreamWriter {
+    fn drop(&mut self) {
+        ByteStreamWriter::close_reactor(self.sender.clone(), &self.runtime_handle);
+    }
+}
+
+// Returns the active segment of a stream that is used as a byte stream.
+async fn active_segment(stream: &ScopedStream, factory: &ClientFactory) -> Result<ScopedSegment, Error> {
+    let mut segments = factory
+        .get_controller_client()
+        .get_current_segments(stream)
+        .await
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to get current segments: {:?}", e),
+            )
+        })?
+        .get_segments();
+    if segments.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "byte stream {} must have a single active segment, found {}",
+                stream,
+                segments.len()
+            ),
+        ));
+    }
+    Ok(segments.pop().expect("must have a segment"))
+}
+
 /// Allows for reading raw bytes from a segment.
+/// If read-ahead is enabled in the client config, the reader keeps a number of reads outstanding
+/// ahead of its current offset and serves the reads from a local buffer.
+///
+/// A reader created for a stream moves on to the successor at the end of a sealed segment, so it
+/// reads the concatenation of the segments of the stream. Its offset is the offset in the current
+/// segment and a seek stays within that segment.
 pub struct ByteStreamReader {
     reader_id: Uuid,
-    reader: AsyncSegmentReaderImpl,
+    segment: ScopedSegment,
+    reader: Arc<AsyncSegmentReaderImpl>,
     metadata_client: SegmentMetadataClient,
     offset: i64,
     runtime_handle: Handle,
     timeout: Duration,
+    prefetch_reads: usize,
+    read_size: usize,
+    // the reads ahead of the buffer, each one starts where the previous one ends.
+    outstanding: VecDeque<OutstandingRead>,
+    // the data read from the current offset onwards.
+    buffer: Bytes,
+    follow_mode: bool,
+    // set if the reader moves on to the successor of a sealed segment.
+    follow_successors: bool,
+    factory: ClientFactory,
+}
+
+struct OutstandingRead {
+    offset: i64,
+    length: i32,
+    handle: JoinHandle<Result<SegmentReadCommand, ReaderError>>,
 }
 
 impl Read for ByteStreamReader {
+    /// Reads the data at the current offset. An error is returned at the end of a sealed segment
+    /// unless the reader follows the tail of the segment, see `set_follow_mode`.
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
-        let read_future = self.reader.read(self.offset, buf.len() as i32);
-        let timeout_fut = self.runtime_handle.enter(|| timeout(self.timeout, read_future));
-        let result = self.runtime_handle.block_on(timeout_fut);
-        match result {
-            Ok(result) => match result {
-                Ok(cmd) => {
-                    if cmd.end_of_segment {
-                        Err(Error::new(ErrorKind::Other, "segment is sealed"))
-                    } else {
-                        // Read may have returned more or less than the requested number of bytes.
-                        let size_to_return = cmp::min(buf.len(), cmd.data.len());
-                        self.offset += size_to_return as i64;
-                        buf[..size_to_return].copy_from_slice(&cmd.data[..size_to_return]);
-                        Ok(size_to_return)
-                    }
-                }
-                Err(e) => Err(Error::new(ErrorKind::Other, format!("Error: {:?}", e))),
-            },
-            Err(e) => Err(Error::new(
-                ErrorKind::TimedOut,
-                format!("Reader timed out after {:?}: {:?}", self.timeout, e),
-            )),
+        if self.follow_mode {
+            return self.read_following(buf);
         }
+        self.read_next(buf)?
+            .ok_or_else(|| Error::new(ErrorKind::Other, "segment is sealed"))
     }
 }
 
@@ -209,15 +327,55 @@ impl ByteStreamReader {
     pub(crate) fn new(segment: ScopedSegment, factory: &ClientFactory) -> Self {
         let handle = factory.get_runtime_handle();
         let async_reader = handle.block_on(factory.create_async_event_reader(segment.clone()));
-        let metadata_client = handle.block_on(factory.create_segment_metadata_client(segment));
+        let metadata_client = handle.block_on(factory.create_segment_metadata_client(segment.clone()));
         ByteStreamReader {
             reader_id: Uuid::new_v4(),
-            reader: async_reader,
+            segment,
+            reader: Arc::new(async_reader),
             metadata_client,
             offset: 0,
             runtime_handle: handle,
             timeout: Duration::from_secs(3600),
+            prefetch_reads: factory.get_config().byte_stream_prefetch_reads(),
+            read_size: factory.get_config().byte_stream_read_size(),
+            outstanding: VecDeque::new(),
+            buffer: Bytes::new(),
+            follow_mode: false,
+            follow_successors: false,
+            factory: factory.clone(),
+        }
+    }
+
+    pub(crate) fn new_for_stream(stream: ScopedStream, factory: &ClientFactory) -> Result<Self, Error> {
+        let head = factory
+            .get_runtime_handle()
+            .block_on(factory.get_controller_client().get_head_segments(&stream))
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed to get head segments: {:?}", e)))?;
+        if head.len() != 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "byte stream {} must have a single segment at its head, found {}",
+                    stream,
+                    head.len()
+                ),
+            ));
         }
+        let (segment, offset) = head.into_iter().next().expect("must have a segment");
+        let segment = ScopedSegment {
+            scope: stream.scope,
+            stream: stream.stream,
+            segment,
+        };
+        let mut reader = ByteStreamReader::new(segment, factory);
+        reader.offset = offset;
+        reader.follow_successors = true;
+        Ok(reader)
+    }
+
+    /// Returns the segment that is read from.
+    pub fn current_segment(&self) -> &ScopedSegment {
+        &self.segment
     }
 
     pub fn current_head(&self) -> std::io::Result<u64> {
@@ -238,13 +396,212 @@ impl ByteStreamReader {
             self.timeout = Duration::from_secs(3600);
         }
     }
-}
 
-/// The Seek implementation for ByteStreamReader allows seeking to a byte offset from the beginning
-/// of the stream or a byte offset relative to the current position in the stream.
-/// If the stream has been truncated, the byte offset will be relative to the original beginning of the stream.
-impl Seek for ByteStreamReader {
-    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+    /// In follow mode a read at the tail of the segment waits until data is appended, up to the
+    /// reader timeout, and a read at the end of a sealed segment returns 0 bytes. So the segment
+    /// can be followed like a log file that is being written. The reads at the tail back off
+    /// as configured by `byte_stream_tail_poll_interval` and `byte_stream_max_tail_poll_interval`.
+    pub fn set_follow_mode(&mut self, follow: bool) {
+        self.follow_mode = follow;
+    }
+
+    // Reads until some data is returned, the end of the sealed segment is reached or the
+    // reader timeout is exceeded.
+    fn read_following(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let deadline = Instant::now() + self.timeout;
+        let mut tail_poll = TailPoll::new(self.factory.get_config());
+        loop {
+            match self.read_next(buf)? {
+                None => return Ok(0),
+                Some(0) if !buf.is_empty() => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(Error::new(
+                            ErrorKind::TimedOut,
+                            format!("No data appended after {:?}", self.timeout),
+                        ));
+                    }
+                    let wait = cmp::min(tail_poll.next_wait(), deadline - now);
+                    self.runtime_handle.block_on(async { delay_for(wait).await });
+                }
+                Some(size) => return Ok(size),
+            }
+        }
+    }
+
+    // Returns the number of bytes read, or None at the end of a sealed segment that has no
+    // successor to move on to.
+    fn read_next(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        loop {
+            match self.read_once(buf)? {
+                None if self.follow_successors => {
+                    if !self.move_to_successor()? {
+                        return Ok(None);
+                    }
+                }
+                result => return Ok(result),
+            }
+        }
+    }
+
+    // Moves the reader to the head of the successor of the current segment, returns false if
+    // the stream is sealed.
+    fn move_to_successor(&mut self) -> Result<bool, Error> {
+        let successors = self
+            .runtime_handle
+            .block_on(self.factory.get_controller_client().get_successors(&self.segment))
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed to get successors: {:?}", e)))?;
+        if successors.is_stream_sealed() {
+            return Ok(false);
+        }
+        let mut segments: Vec<ScopedSegment> = successors
+            .segment_with_predecessors
+            .keys()
+            .map(|segment| segment.scoped_segment.clone())
+            .collect();
+        if segments.len() != 1 {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "segment {} of a byte stream is succeeded by {} segments",
+                    self.segment,
+                    segments.len()
+                ),
+            ));
+        }
+        let segment = segments.pop().expect("must have a successor");
+        let reader = self
+            .runtime_handle
+            .block_on(self.factory.create_async_event_reader(segment.clone()));
+        let metadata_client = self
+            .runtime_handle
+            .block_on(self.factory.create_segment_metadata_client(segment.clone()));
+        let head = self
+            .runtime_handle
+            .block_on(metadata_client.fetch_current_starting_head())
+            .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))?;
+        self.invalidate_prefetch();
+        self.reader = Arc::new(reader);
+        self.metadata_client = metadata_client;
+        self.segment = segment;
+        self.offset = head;
+        Ok(true)
+    }
+
+    // Returns the number of bytes read, or None at the end of a sealed segment.
+    fn read_once(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        if self.prefetch_reads > 0 {
+            return self.read_from_buffer(buf);
+        }
+        let read_future = self.reader.read(self.offset, buf.len() as i32);
+        let timeout_fut = self.runtime_handle.enter(|| timeout(self.timeout, read_future));
+        let result = self.runtime_handle.block_on(timeout_fut);
+        match result {
+            Ok(result) => match result {
+                Ok(cmd) => {
+                    if cmd.end_of_segment {
+                        Ok(None)
+                    } else {
+                        // Read may have returned more or less than the requested number of bytes,
+                        // the data beyond the buffer is dropped and read again by the next read.
+                        let size_to_return = cmp::min(buf.len(), cmd.data.len());
+                        self.offset += size_to_return as i64;
+                        buf[..size_to_return].copy_from_slice(&cmd.data[..size_to_return]);
+                        Ok(Some(size_to_return))
+                    }
+                }
+                Err(e) => Err(Error::new(ErrorKind::Other, format!("Error: {:?}", e))),
+            },
+            Err(e) => Err(Error::new(
+                ErrorKind::TimedOut,
+                format!("Reader timed out after {:?}: {:?}", self.timeout, e),
+            )),
+        }
+    }
+
+    // Serves the read from the buffer, the buffer is filled by the oldest outstanding read if
+    // it is empty.
+    fn read_from_buffer(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        if buf.is_empty() {
+            return Ok(Some(0));
+        }
+        self.prefetch(buf.len());
+        if self.buffer.is_empty() {
+            let read = self.outstanding.pop_front().expect("must have outstanding read");
+            let (read_timeout, read_length) = (self.timeout, read.length);
+            let result = self
+                .runtime_handle
+                .block_on(async { timeout(read_timeout, read.handle).await });
+            match result {
+                Ok(Ok(Ok(cmd))) => {
+                    if cmd.end_of_segment {
+                        self.invalidate_prefetch();
+                        return Ok(None);
+                    }
+                    // the reads ahead of a short read do not start where it ends, and the
+                    // data beyond the requested length is read again by the next read.
+                    let mut data = cmd.data;
+                    if data.len() < read_length as usize {
+                        self.outstanding.clear();
+                    } else {
+                        data.truncate(read_length as usize);
+                    }
+                    self.buffer = Bytes::from(data);
+                    self.prefetch(buf.len());
+                }
+                Ok(Ok(Err(e))) => {
+                    self.invalidate_prefetch();
+                    return Err(Error::new(ErrorKind::Other, format!("Error: {:?}", e)));
+                }
+                Ok(Err(e)) => {
+                    self.invalidate_prefetch();
+                    return Err(Error::new(ErrorKind::Other, format!("Read task failed: {:?}", e)));
+                }
+                Err(e) => {
+                    self.invalidate_prefetch();
+                    return Err(Error::new(
+                        ErrorKind::TimedOut,
+                        format!("Reader timed out after {:?}: {:?}", self.timeout, e),
+                    ));
+                }
+            }
+        }
+        let size_to_return = cmp::min(buf.len(), self.buffer.len());
+        buf[..size_to_return].copy_from_slice(&self.buffer[..size_to_return]);
+        self.buffer.advance(size_to_return);
+        self.offset += size_to_return as i64;
+        Ok(Some(size_to_return))
+    }
+
+    // Issues reads until the configured number of reads is outstanding.
+    fn prefetch(&mut self, min_length: usize) {
+        let length = cmp::max(self.read_size, min_length) as i32;
+        let mut next_offset = match self.outstanding.back() {
+            Some(read) => read.offset + read.length as i64,
+            None => self.offset + self.buffer.len() as i64,
+        };
+        while self.outstanding.len() < self.prefetch_reads {
+            let reader = self.reader.clone();
+            let offset = next_offset;
+            let handle = self
+                .runtime_handle
+                .spawn(async move { reader.read(offset, length).await });
+            self.outstanding.push_back(OutstandingRead {
+                offset,
+                length,
+                handle,
+            });
+            next_offset += length as i64;
+        }
+    }
+
+    // Drops the buffered data and the outstanding reads, the reads complete in the background.
+    fn invalidate_prefetch(&mut self) {
+        self.buffer.clear();
+        self.outstanding.clear();
+    }
+
+    fn seek_offset(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
         match pos {
             SeekFrom::Start(offset) => {
                 self.offset = offset.try_into().map_err(|e| {
@@ -286,13 +643,351 @@ impl Seek for ByteStreamReader {
     }
 }
 
+/// The Seek implementation for ByteStreamReader allows seeking to a byte offset from the beginning
+/// of the stream or a byte offset relative to the current position in the stream.
+/// If the stream has been truncated, the byte offset will be relative to the original beginning of the stream.
+impl Seek for ByteStreamReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let offset = self.offset;
+        let result = self.seek_offset(pos);
+        // the data read ahead belongs to the previous offset.
+        if self.offset != offset {
+            self.invalidate_prefetch();
+        }
+        result
+    }
+}
+
+// The wait between the reads at the tail of a segment, it is doubled after every read that finds
+// no data until it reaches the configured maximum.
+struct TailPoll {
+    initial: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl TailPoll {
+    fn new(config: &ClientConfig) -> Self {
+        let initial = Duration::from_millis(config.byte_stream_tail_poll_interval());
+        TailPoll {
+            initial,
+            max: Duration::from_millis(config.byte_stream_max_tail_poll_interval()),
+            next: initial,
+        }
+    }
+
+    fn next_wait(&mut self) -> Duration {
+        let wait = self.next;
+        self.next = cmp::min(self.next * 2, self.max);
+        wait
+    }
+
+    fn reset(&mut self) {
+        self.next = self.initial;
+    }
+}
+
+/// The asynchronous counterpart of ByteStreamWriter, it implements AsyncWrite so that it can be
+/// used inside an async context, e.g. as the destination of `tokio::io::copy`.
+pub struct AsyncByteStreamWriter {
+    writer_id: WriterId,
+    sender: ChannelSender<Incoming>,
+    metadata_client: SegmentMetadataClient,
+    // the write waiting for capacity in the channel and the number of bytes it writes.
+    write_future: Option<(WriteFuture, usize)>,
+    event_handle: Option<EventHandle>,
+    runtime_handle: Handle,
+    closed: bool,
+}
+
+type WriteFuture = Pin<Box<dyn Future<Output = EventHandle> + Send>>;
+
+impl AsyncByteStreamWriter {
+    pub(crate) async fn new(segment: ScopedSegment, factory: ClientFactory) -> Self {
+        let (sender, receiver) = create_channel(factory.get_config().max_outstanding_bytes());
+        let metadata_client = factory.create_segment_metadata_client(segment.clone()).await;
+        let writer_id = WriterId(get_random_u128());
+        let span = info_span!("SegmentReactor", event_stream_writer = %writer_id);
+        // tokio::spawn is tied to the factory runtime.
+        factory.get_runtime_handle().enter(|| {
+            tokio::spawn(
+                Reactor::run(
+                    ScopedStream::from(&segment),
+                    writer_id,
+                    sender.clone(),
+                    receiver,
+                    factory.clone(),
+                )
+                .instrument(span),
+            )
+        });
+        AsyncByteStreamWriter {
+            writer_id,
+            sender,
+            metadata_client,
+            write_future: None,
+            event_handle: None,
+            runtime_handle: factory.get_runtime_handle(),
+            closed: false,
+        }
+    }
+
+    /// Waits until the data written so far is persisted on the server side and shuts down the
+    /// writer. The error of the first write that failed is returned.
+    pub async fn close(mut self) -> Result<(), Error> {
+        self.closed = true;
+        let (tx, rx) = oneshot::channel();
+        if self.sender.send((Incoming::Close(tx), 0)).await.is_err() {
+            return Err(Error::new(ErrorKind::Other, "reactor is already closed"));
+        }
+        ByteStreamWriter::ack_result(rx.await)
+    }
+
+    /// Seals the segment and no further writes are allowed.
+    pub async fn seal(&mut self) -> Result<(), Error> {
+        if let Some(event_handle) = self.event_handle.take() {
+            ByteStreamWriter::flush_internal(event_handle).await?;
+        }
+        self.metadata_client
+            .seal_segment()
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("segment seal error: {:?}", e)))
+    }
+
+    /// Truncates data before a given offset for the segment. No reads are allowed before
+    /// truncation point after calling this method.
+    pub async fn truncate_data_before(&self, offset: i64) -> Result<(), Error> {
+        self.metadata_client
+            .truncate_segment(offset)
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("segment truncation error: {:?}", e)))
+    }
+}
+
+impl AsyncWrite for AsyncByteStreamWriter {
+    /// Writes the given data to the server. Like ByteStreamWriter the data is not necessarily
+    /// persisted when this returns, flush ensures all data has been acknowledged by the server.
+    /// If Pending is returned the same data must be written again once the writer is woken up.
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Error>> {
+        let writer = self.get_mut();
+        if writer.write_future.is_none() {
+            let bytes_to_write = cmp::min(buf.len(), EventStreamWriter::MAX_EVENT_SIZE);
+            let payload = buf[0..bytes_to_write].to_vec();
+            let write = ByteStreamWriter::write_internal(writer.sender.clone(), payload);
+            writer.write_future = Some((Box::pin(write), bytes_to_write));
+        }
+        let (write, bytes_to_write) = writer.write_future.as_mut().expect("must have write future");
+        let event_handle = ready!(write.as_mut().poll(cx));
+        let bytes_to_write = *bytes_to_write;
+        writer.write_future = None;
+        if let Some(event_handle) = ByteStreamWriter::check_event_handle(event_handle)? {
+            writer.event_handle = Some(event_handle);
+        }
+        Poll::Ready(Ok(bytes_to_write))
+    }
+
+    /// Waits for the data written so far to be persisted on the server side.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let writer = self.get_mut();
+        if let Some(event_handle) = writer.event_handle.as_mut() {
+            // events are acknowledged in order, so the last event is acknowledged last.
+            let ack = ready!(Pin::new(event_handle).poll(cx));
+            writer.event_handle = None;
+            return Poll::Ready(ByteStreamWriter::ack_result(ack));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl Drop for AsyncByteStreamWriter {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        ByteStreamWriter::close_reactor(self.sender.clone(), &self.runtime_handle);
+    }
+}
+
+/// The asynchronous counterpart of ByteStreamReader, it implements AsyncRead and AsyncSeek so
+/// that it can be used inside an async context, e.g. as the source of `tokio::io::copy`.
+/// Unlike ByteStreamReader, reaching the end of a sealed segment is reported as the end of the
+/// stream, that is a read of 0 bytes.
+pub struct AsyncByteStreamReader {
+    reader: Arc<AsyncSegmentReaderImpl>,
+    metadata_client: Arc<SegmentMetadataClient>,
+    offset: i64,
+    timeout: Duration,
+    read_future: Option<ReadFuture>,
+    seek_future: Option<SeekFuture>,
+    tail_poll: TailPoll,
+}
+
+type ReadFuture =
+    Pin<Box<dyn Future<Output = Result<Result<SegmentReadCommand, ReaderError>, Elapsed>> + Send>>;
+type SeekFuture = Pin<Box<dyn Future<Output = Result<i64, Error>> + Send>>;
+
+impl AsyncByteStreamReader {
+    pub(crate) async fn new(segment: ScopedSegment, factory: &ClientFactory) -> Self {
+        let async_reader = factory.create_async_event_reader(segment.clone()).await;
+        let metadata_client = factory.create_segment_metadata_client(segment).await;
+        AsyncByteStreamReader {
+            reader: Arc::new(async_reader),
+            metadata_client: Arc::new(metadata_client),
+            offset: 0,
+            timeout: Duration::from_secs(3600),
+            read_future: None,
+            seek_future: None,
+            tail_poll: TailPoll::new(factory.get_config()),
+        }
+    }
+
+    pub async fn current_head(&self) -> std::io::Result<u64> {
+        self.metadata_client
+            .fetch_current_starting_head()
+            .await
+            .map(|i| i as u64)
+            .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))
+    }
+
+    pub fn current_offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn set_reader_timeout(&mut self, timeout: Option<Duration>) {
+        if let Some(time) = timeout {
+            self.timeout = time;
+        } else {
+            self.timeout = Duration::from_secs(3600);
+        }
+    }
+
+    // Reads at the current offset, after waiting for the given time if any.
+    fn read_after(&self, wait: Option<Duration>, length: i32) -> ReadFuture {
+        let segment_reader = self.reader.clone();
+        let (offset, read_timeout) = (self.offset, self.timeout);
+        Box::pin(async move {
+            if let Some(wait) = wait {
+                delay_for(wait).await;
+            }
+            timeout(read_timeout, segment_reader.read(offset, length)).await
+        })
+    }
+}
+
+impl AsyncRead for AsyncByteStreamReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<Result<usize, Error>> {
+        let reader = self.get_mut();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if reader.read_future.is_none() {
+            reader.read_future = Some(reader.read_after(None, buf.len() as i32));
+        }
+        loop {
+            let result = ready!(reader
+                .read_future
+                .as_mut()
+                .expect("must have read future")
+                .as_mut()
+                .poll(cx));
+            reader.read_future = None;
+            match result {
+                Ok(Ok(cmd)) => {
+                    if cmd.end_of_segment {
+                        return Poll::Ready(Ok(0));
+                    }
+                    if !cmd.data.is_empty() {
+                        // Read may have returned more or less than the requested number of bytes.
+                        let size_to_return = cmp::min(buf.len(), cmd.data.len());
+                        reader.offset += size_to_return as i64;
+                        buf[..size_to_return].copy_from_slice(&cmd.data[..size_to_return]);
+                        reader.tail_poll.reset();
+                        return Poll::Ready(Ok(size_to_return));
+                    }
+                    // nothing to read at the tail yet, reading 0 bytes would end the stream, so
+                    // the read is retried after a while instead.
+                    let (wait, length) = (reader.tail_poll.next_wait(), buf.len() as i32);
+                    reader.read_future = Some(reader.read_after(Some(wait), length));
+                }
+                Ok(Err(e)) => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::Other, format!("Error: {:?}", e))));
+                }
+                Err(e) => {
+                    return Poll::Ready(Err(Error::new(
+                        ErrorKind::TimedOut,
+                        format!("Reader timed out after {:?}: {:?}", reader.timeout, e),
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Seeking works like the Seek implementation of ByteStreamReader. A read that is in progress
+/// is abandoned by a seek.
+impl AsyncSeek for AsyncByteStreamReader {
+    fn start_seek(self: Pin<&mut Self>, _cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<(), Error>> {
+        let reader = self.get_mut();
+        reader.read_future = None;
+        reader.tail_poll.reset();
+        let current = reader.offset;
+        let metadata_client = reader.metadata_client.clone();
+        reader.seek_future = Some(Box::pin(async move {
+            let new_offset = match pos {
+                SeekFrom::Start(offset) => offset.try_into().map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Overflowed when converting offset to i64: {:?}", e),
+                    )
+                })?,
+                SeekFrom::Current(offset) => current + offset,
+                SeekFrom::End(offset) => {
+                    let tail = metadata_client
+                        .fetch_current_segment_length()
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::Other, format!("{:?}", e)))?;
+                    tail + offset
+                }
+            };
+            if new_offset < 0 {
+                Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Cannot seek to a negative offset",
+                ))
+            } else {
+                Ok(new_offset)
+            }
+        }));
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<u64, Error>> {
+        let reader = self.get_mut();
+        let seek = match reader.seek_future.as_mut() {
+            Some(seek) => seek,
+            None => return Poll::Ready(Ok(reader.offset as u64)),
+        };
+        let result = ready!(seek.as_mut().poll(cx));
+        reader.seek_future = None;
+        reader.offset = result?;
+        Poll::Ready(Ok(reader.offset as u64))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::create_stream;
-    use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
+    use crate::{create_mock_factory, create_stream, mock_config_builder, read_segment};
     use pravega_rust_client_config::ClientConfigBuilder;
-    use pravega_rust_client_shared::PravegaNodeUri;
+    use pravega_rust_client_shared::{
+        PravegaNodeUri, Retention, RetentionType, ScaleType, Scaling, Segment, StreamConfiguration,
+    };
+    use std::thread;
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
     use tokio::runtime::Runtime;
 
     #[test]
@@ -396,14 +1091,438 @@ mod test {
         assert!(result.is_err());
     }
 
-    fn create_reader_and_writer(runtime: &mut Runtime) -> (ByteStreamWriter, ByteStreamReader) {
+    #[test]
+    fn test_byte_stream_read_ahead() {
+        let config = mock_config_builder()
+            .byte_stream_prefetch_reads(3usize)
+            .byte_stream_read_size(64usize)
+            .build()
+            .unwrap();
+        let factory = ClientFactory::new(config);
+        factory
+            .get_runtime_handle()
+            .block_on(create_stream(&factory, "scope", "readahead"));
+        let segment = ScopedSegment::from("scope/readahead/0");
+        let mut writer = factory.create_byte_stream_writer(segment.clone());
+        let mut reader = factory.create_byte_stream_reader(segment);
+
+        writer.write_all(&[1; 200]).expect("write");
+        writer.flush().expect("flush");
+
+        // small reads are served from the data read ahead
+        let mut buf = [0; 10];
+        let mut read = 0;
+        while read < 200 {
+            let size = reader.read(&mut buf).expect("read");
+            assert!(size > 0);
+            read += size;
+        }
+        assert_eq!(read, 200);
+        assert_eq!(reader.current_offset(), 200);
+
+        // the reading continues with the data written later
+        writer.write_all(&[1; 100]).expect("write");
+        writer.flush().expect("flush");
+        let mut buf = [0; 100];
+        let mut read = 0;
+        while read < 100 {
+            read += reader.read(&mut buf[read..]).expect("read");
+        }
+        assert_eq!(reader.current_offset(), 300);
+
+        // a seek drops the data read ahead
+        reader.seek(SeekFrom::Start(250)).expect("seek");
+        let mut buf = [0; 100];
+        let mut read = 0;
+        while read < 50 {
+            read += reader.read(&mut buf[read..]).expect("read");
+        }
+        assert_eq!(read, 50);
+        assert_eq!(reader.current_offset(), 300);
+
+        // the end of a sealed segment is reported once the data is read
+        factory
+            .get_runtime_handle()
+            .block_on(writer.seal())
+            .expect("seal");
+        reader.seek(SeekFrom::Start(280)).expect("seek");
+        let mut buf = [0; 100];
+        assert_eq!(reader.read(&mut buf).expect("read"), 20);
+        assert!(reader.read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_byte_stream_read_ahead_over_long_reply() {
+        let config = mock_config_builder()
+            .byte_stream_prefetch_reads(2usize)
+            .byte_stream_read_size(4usize)
+            .build()
+            .unwrap();
+        let factory = ClientFactory::new(config);
+        factory
+            .get_runtime_handle()
+            .block_on(create_stream(&factory, "scope", "overlong"));
+        let segment = ScopedSegment::from("scope/overlong/0");
+        let mut writer = factory.create_byte_stream_writer(segment.clone());
+        let mut reader = factory.create_byte_stream_reader(segment.clone());
+
+        let payload: Vec<u8> = (0..16).collect();
+        writer.write_all(&payload).expect("write");
+        writer.flush().expect("flush");
+
+        // the first read ahead returns more than the requested 4 bytes
+        let data = payload[..8].to_vec();
+        let handle = factory.get_runtime_handle().spawn(async move {
+            Ok(SegmentReadCommand {
+                segment: segment.to_string(),
+                offset: 0,
+                at_tail: false,
+                end_of_segment: false,
+                data,
+                request_id: 0,
+            })
+        });
+        reader.outstanding.push_back(OutstandingRead {
+            offset: 0,
+            length: 4,
+            handle,
+        });
+
+        // no byte is read twice
+        let mut buf = [0; 4];
+        let mut read = vec![];
+        while read.len() < 12 {
+            let size = reader.read(&mut buf).expect("read");
+            read.extend_from_slice(&buf[..size]);
+        }
+        assert_eq!(read, payload[..12].to_vec());
+        assert_eq!(reader.current_offset(), 12);
+    }
+
+    #[test]
+    fn test_byte_stream_follow_mode() {
+        let mut rt = Runtime::new().unwrap();
+        let (mut writer, mut reader) = create_reader_and_writer(&mut rt);
+        reader.set_follow_mode(true);
+
+        writer.write_all(&[1; 100]).expect("write");
+        writer.flush().expect("flush");
+        let mut buf = [0; 200];
+        assert_eq!(reader.read(&mut buf).expect("read"), 100);
+
+        // a read at the tail waits for the data appended later
+        let appender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            writer.write_all(&[1; 50]).expect("write");
+            writer.flush().expect("flush");
+            writer
+        });
+        assert_eq!(reader.read(&mut buf).expect("read"), 50);
+        let mut writer = appender.join().expect("append data");
+
+        // a read at the tail fails once the reader timeout is exceeded
+        reader.set_reader_timeout(Some(Duration::from_millis(100)));
+        let error = reader.read(&mut buf).expect_err("no data is appended");
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+
+        // the end of the sealed segment is the end of the stream
+        rt.block_on(writer.seal()).expect("seal");
+        assert_eq!(reader.read(&mut buf).expect("read"), 0);
+        assert_eq!(reader.current_offset(), 150);
+    }
+
+    #[test]
+    fn test_byte_stream_for_stream() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "bytestream"));
+        let stream = ScopedStream::from("scope/bytestream");
+        let mut writer = factory
+            .create_byte_stream_writer_for_stream(stream.clone())
+            .expect("create writer");
+        let mut reader = factory
+            .create_byte_stream_reader_for_stream(stream)
+            .expect("create reader");
+        assert_eq!(
+            writer.current_segment(),
+            &ScopedSegment::from("scope/bytestream/0")
+        );
+        assert_eq!(
+            reader.current_segment(),
+            &ScopedSegment::from("scope/bytestream/0")
+        );
+
+        writer.write_all(&[1; 200]).expect("write");
+        writer.flush().expect("flush");
+        let mut buf = [0; 200];
+        reader.read_exact(&mut buf).expect("read");
+        assert_eq!(buf, [1; 200]);
+
+        // a sealed segment without successors is the end of the stream
+        handle.block_on(writer.seal()).expect("seal");
+        assert!(reader.read(&mut buf).is_err());
+        reader.set_follow_mode(true);
+        assert_eq!(reader.read(&mut buf).expect("read"), 0);
+        assert_eq!(
+            reader.current_segment(),
+            &ScopedSegment::from("scope/bytestream/0")
+        );
+
+        // a stream with more than one segment cannot be used as a byte stream
+        let stream = ScopedStream::from("scope/multisegment");
+        handle.block_on(async {
+            factory
+                .get_controller_client()
+                .create_stream(&StreamConfiguration {
+                    scoped_stream: stream.clone(),
+                    scaling: Scaling {
+                        scale_type: ScaleType::FixedNumSegments,
+                        target_rate: 0,
+                        scale_factor: 0,
+                        min_num_segments: 2,
+                    },
+                    retention: Retention {
+                        retention_type: RetentionType::None,
+                        retention_param: 0,
+                    },
+                })
+                .await
+                .expect("create stream")
+        });
+        let error = factory
+            .create_byte_stream_writer_for_stream(stream.clone())
+            .err()
+            .expect("writer of a stream with two segments");
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        let error = factory
+            .create_byte_stream_reader_for_stream(stream)
+            .err()
+            .expect("reader of a stream with two segments");
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_byte_stream_writer_seal_mid_stream() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "writerscale"));
+        let stream = ScopedStream::from("scope/writerscale");
+        let mut writer = factory
+            .create_byte_stream_writer_for_stream(stream.clone())
+            .expect("create writer");
+
+        // the segment is sealed while the data is being appended to it
+        let payload = test_payload(64 * 1024);
+        writer.write_all(&payload[..1024]).expect("write");
+        writer.flush().expect("flush");
+        for chunk in payload[1024..32 * 1024].chunks(1024) {
+            writer.write_all(chunk).expect("write");
+        }
+        scale_byte_stream(&factory, &stream, 0);
+        for chunk in payload[32 * 1024..].chunks(1024) {
+            writer.write_all(chunk).expect("write");
+        }
+        writer.flush().expect("flush");
+
+        // no bytes are lost or written twice
+        let mut data = handle.block_on(read_segment(
+            &factory,
+            &ScopedSegment::from("scope/writerscale/0"),
+        ));
+        data.extend(handle.block_on(read_segment(
+            &factory,
+            &ScopedSegment::from("scope/writerscale/1"),
+        )));
+        assert_eq!(data, payload);
+
+        handle.block_on(writer.seal()).expect("seal");
+        assert_eq!(
+            writer.current_segment(),
+            &ScopedSegment::from("scope/writerscale/1")
+        );
+    }
+
+    #[test]
+    fn test_byte_stream_reader_seal_mid_stream() {
+        let config = mock_config_builder()
+            .byte_stream_prefetch_reads(3usize)
+            .byte_stream_read_size(1000usize)
+            .build()
+            .unwrap();
+        let factory = ClientFactory::new(config);
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "readerscale"));
+        let stream = ScopedStream::from("scope/readerscale");
+        let mut writer = factory
+            .create_byte_stream_writer_for_stream(stream.clone())
+            .expect("create writer");
+        let mut reader = factory
+            .create_byte_stream_reader_for_stream(stream.clone())
+            .expect("create reader");
+
+        let payload = test_payload(20 * 1024);
+        writer.write_all(&payload[..10 * 1024]).expect("write");
+        writer.flush().expect("flush");
+
+        // the reader is in the middle of the segment, with reads ahead of it, when it is sealed
+        let mut read = vec![0; 4 * 1024];
+        reader.read_exact(&mut read).expect("read");
+        scale_byte_stream(&factory, &stream, 0);
+        writer.write_all(&payload[10 * 1024..]).expect("write");
+        writer.flush().expect("flush");
+
+        // no bytes are lost or read twice
+        let mut buf = [0; 1024];
+        while read.len() < payload.len() {
+            let size = reader.read(&mut buf).expect("read");
+            read.extend_from_slice(&buf[..size]);
+        }
+        assert_eq!(read, payload);
+        assert_eq!(
+            reader.current_segment(),
+            &ScopedSegment::from("scope/readerscale/1")
+        );
+    }
+
+    #[test]
+    fn test_async_byte_stream_copy() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(async {
+            create_stream(&factory, "scope", "source").await;
+            create_stream(&factory, "scope", "destination").await;
+            let source = ScopedSegment::from("scope/source/0");
+            let destination = ScopedSegment::from("scope/destination/0");
+
+            let mut writer = factory.create_async_byte_stream_writer(source.clone()).await;
+            writer.write_all(&[1; 200]).await.expect("write");
+            writer.flush().await.expect("flush");
+            writer.seal().await.expect("seal");
+
+            // the end of the sealed segment ends the copy
+            let mut reader = factory.create_async_byte_stream_reader(source).await;
+            let mut copy_writer = factory.create_async_byte_stream_writer(destination.clone()).await;
+            let copied = tokio::io::copy(&mut reader, &mut copy_writer)
+                .await
+                .expect("copy");
+            assert_eq!(copied, 200);
+            copy_writer.flush().await.expect("flush");
+            copy_writer.seal().await.expect("seal");
+
+            let mut reader = factory.create_async_byte_stream_reader(destination).await;
+            let mut buf = vec![];
+            reader.read_to_end(&mut buf).await.expect("read to end");
+            assert_eq!(buf, vec![1; 200]);
+        });
+    }
+
+    #[test]
+    fn test_async_byte_stream_read_at_tail() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(async {
+            create_stream(&factory, "scope", "tail").await;
+            let segment = ScopedSegment::from("scope/tail/0");
+            let mut writer = factory.create_async_byte_stream_writer(segment.clone()).await;
+            writer.write_all(&[1; 100]).await.expect("write");
+            writer.flush().await.expect("flush");
+            let mut reader = factory.create_async_byte_stream_reader(segment).await;
+            let mut buf = vec![0; 100];
+            reader.read_exact(&mut buf).await.expect("read");
+
+            // the read waits at the tail until the data is written, without spinning.
+            let mut polls = 0;
+            let read = futures::future::poll_fn(|cx| {
+                polls += 1;
+                Pin::new(&mut reader).poll_read(cx, &mut buf)
+            });
+            let write = async {
+                delay_for(Duration::from_millis(100)).await;
+                writer.write_all(&[2; 100]).await.expect("write");
+                writer.close().await.expect("close");
+            };
+            let (read, _) = tokio::join!(read, write);
+            assert_eq!(read.expect("read"), 100);
+            assert_eq!(buf, vec![2; 100]);
+            assert!(polls < 50, "polled {} times", polls);
+        });
+    }
+
+    #[test]
+    fn test_tail_poll_backoff() {
         let config = ClientConfigBuilder::default()
-            .connection_type(ConnectionType::Mock(MockType::Happy))
-            .mock(true)
             .controller_uri(PravegaNodeUri::from("127.0.0.2:9091".to_string()))
+            .byte_stream_tail_poll_interval(10u64)
+            .byte_stream_max_tail_poll_interval(50u64)
             .build()
             .unwrap();
-        let factory = ClientFactory::new(config);
+        let mut tail_poll = TailPoll::new(&config);
+        let waits: Vec<u64> = (0..5).map(|_| tail_poll.next_wait().as_millis() as u64).collect();
+        assert_eq!(waits, vec![10, 20, 40, 50, 50]);
+        tail_poll.reset();
+        assert_eq!(tail_poll.next_wait(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_async_byte_stream_seek() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(async {
+            create_stream(&factory, "scope", "stream").await;
+            let segment = ScopedSegment::from("scope/stream/0");
+            let mut writer = factory.create_async_byte_stream_writer(segment.clone()).await;
+            writer.write_all(&[1; 200]).await.expect("write");
+            writer.flush().await.expect("flush");
+
+            let mut reader = factory.create_async_byte_stream_reader(segment).await;
+            let mut buf = vec![0; 100];
+            reader.read_exact(&mut buf).await.expect("read");
+            assert_eq!(reader.current_offset(), 100);
+
+            assert_eq!(
+                reader.seek(SeekFrom::Current(50)).await.expect("seek to current"),
+                150
+            );
+            assert_eq!(reader.seek(SeekFrom::End(-100)).await.expect("seek to end"), 100);
+            assert_eq!(reader.seek(SeekFrom::Start(0)).await.expect("seek to head"), 0);
+            assert!(reader.seek(SeekFrom::Current(-1)).await.is_err());
+            assert!(reader.seek(SeekFrom::End(-300)).await.is_err());
+            assert_eq!(reader.current_offset(), 0);
+
+            let mut buf = vec![0; 200];
+            reader.read_exact(&mut buf).await.expect("read");
+            assert_eq!(buf, vec![1; 200]);
+        });
+    }
+
+    // Distinct consecutive bytes, so that lost or duplicated bytes are detected.
+    fn test_payload(length: usize) -> Vec<u8> {
+        (0..length).map(|i| (i % 251) as u8).collect()
+    }
+
+    // Replaces the single segment of the stream by its successor and seals it.
+    fn scale_byte_stream(factory: &ClientFactory, stream: &ScopedStream, segment: i64) {
+        let handle = factory.get_runtime_handle();
+        handle
+            .block_on(factory.get_controller_client().scale_stream(
+                stream,
+                &[Segment::from(segment)],
+                &[(0.0, 1.0)],
+            ))
+            .expect("scale stream");
+        let sealed = ScopedSegment {
+            scope: stream.scope.clone(),
+            stream: stream.stream.clone(),
+            segment: Segment::from(segment),
+        };
+        let metadata_client = handle.block_on(factory.create_segment_metadata_client(sealed));
+        handle
+            .block_on(metadata_client.seal_segment())
+            .expect("seal segment");
+    }
+
+    fn create_reader_and_writer(runtime: &mut Runtime) -> (ByteStreamWriter, ByteStreamReader) {
+        let factory = create_mock_factory();
         runtime.block_on(create_stream(&factory, "scope", "stream"));
         let segment = ScopedSegment::from("scope/stream/0");
         let writer = factory.create_byte_stream_writer(segment.clone());
@@ -12,21 +12,34 @@ use pravega_connection_pool::connection_pool::ConnectionPool;
 use pravega_controller_client::mock_controller::MockController;
 use pravega_controller_client::{ControllerClient, ControllerClientImpl};
 use pravega_rust_client_config::ClientConfig;
-use pravega_rust_client_shared::{DelegationToken, PravegaNodeUri, ScopedSegment, ScopedStream, WriterId};
+use pravega_rust_client_shared::{
+    DelegationToken, PravegaNodeUri, Scope, ScopedSegment, ScopedStream, WriterId,
+};
 use pravega_wire_protocol::connection_factory::{
     ConnectionFactory, ConnectionFactoryConfig, SegmentConnectionManager,
 };
 
-use crate::byte_stream::{ByteStreamReader, ByteStreamWriter};
+use crate::byte_stream::{AsyncByteStreamReader, AsyncByteStreamWriter, ByteStreamReader, ByteStreamWriter};
+use crate::conditional_segment_writer::ConditionalSegmentWriter;
+use crate::error::*;
 use crate::event_reader::EventReader;
-use crate::event_stream_writer::EventStreamWriter;
+use crate::event_stream_writer::{EventStreamWriter, TypedEventStreamWriter};
+use crate::get_random_u128;
 use crate::raw_client::RawClientImpl;
+use crate::reader_group::reader_group_config::{ReaderGroupConfigV1, ReaderGroupConfigVersioned};
+use crate::reader_group::{ReaderGroup, ReaderGroupStateError};
 use crate::segment_metadata::SegmentMetadataClient;
-use crate::segment_reader::AsyncSegmentReaderImpl;
+use crate::segment_reader::{AsyncSegmentReaderImpl, ReaderError};
+use crate::segment_slice::Event;
+use crate::serializer::{Serializer, TableCodec};
+use crate::stream::event_pointer::EventPointerVersioned;
+use crate::stream::stream_cut::{StreamCutV1, StreamCutVersioned};
 use crate::table_synchronizer::TableSynchronizer;
 use crate::tablemap::TableMap;
 use crate::transaction::transactional_event_stream_writer::TransactionalEventStreamWriter;
 use pravega_rust_client_auth::DelegationTokenProvider;
+use snafu::ResultExt;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use tokio::runtime::{Handle, Runtime};
@@ -44,14 +57,33 @@ struct ClientFactoryInternal {
 impl ClientFactory {
     pub fn new(config: ClientConfig) -> ClientFactory {
         let rt = tokio::runtime::Runtime::new().expect("create runtime");
-        let cf = ConnectionFactory::create(ConnectionFactoryConfig::from(&config));
-        let pool = ConnectionPool::new(SegmentConnectionManager::new(cf, config.max_connections_in_pool));
         let controller = if config.mock {
             Box::new(MockController::new(config.controller_uri.clone())) as Box<dyn ControllerClient>
         } else {
             Box::new(ControllerClientImpl::new(config.clone(), rt.handle().clone()))
                 as Box<dyn ControllerClient>
         };
+        ClientFactory::with_runtime(config, controller, rt)
+    }
+
+    // Creates a client factory that uses the given controller client, the tests use it to inspect
+    // the state of a mock controller.
+    #[cfg(test)]
+    pub(crate) fn with_controller(
+        config: ClientConfig,
+        controller: Box<dyn ControllerClient>,
+    ) -> ClientFactory {
+        let rt = tokio::runtime::Runtime::new().expect("create runtime");
+        ClientFactory::with_runtime(config, controller, rt)
+    }
+
+    fn with_runtime(
+        config: ClientConfig,
+        controller: Box<dyn ControllerClient>,
+        rt: Runtime,
+    ) -> ClientFactory {
+        let cf = ConnectionFactory::create(ConnectionFactoryConfig::from(&config));
+        let pool = ConnectionPool::new(SegmentConnectionManager::new(cf, config.max_connections_in_pool));
         ClientFactory(Arc::new(ClientFactoryInternal {
             connection_pool: pool,
             controller_client: controller,
@@ -84,6 +116,12 @@ impl ClientFactory {
             .expect("Failed to create Table map")
     }
 
+    pub async fn create_table_map_with_codec<C: TableCodec>(&self, name: String, codec: C) -> TableMap<C> {
+        TableMap::new_with_codec(name, self.clone(), codec)
+            .await
+            .expect("Failed to create Table map")
+    }
+
     pub async fn create_table_synchronizer(&self, name: String) -> TableSynchronizer {
         TableSynchronizer::new(name, self.clone()).await
     }
@@ -106,8 +144,62 @@ impl ClientFactory {
         EventStreamWriter::new(stream, self.clone())
     }
 
+    ///
+    /// Create a writer that appends to the stream using the given writer id. A producer that
+    /// restarts with the same writer id and writes its events again with the same event numbers,
+    /// see `EventStreamWriter::write_event_with_number`, and routing keys has the events that the
+    /// segmentstore already persisted acknowledged without writing them twice. Events without a
+    /// routing key go to a random segment, so only single segment streams can be resumed with
+    /// them. Events written without an event number are always written.
+    ///
+    pub fn create_event_stream_writer_with_id(
+        &self,
+        stream: ScopedStream,
+        writer_id: WriterId,
+    ) -> EventStreamWriter {
+        EventStreamWriter::with_writer_id(stream, writer_id, self.clone())
+    }
+
+    pub fn create_typed_event_stream_writer<T>(
+        &self,
+        stream: ScopedStream,
+        serializer: Arc<dyn Serializer<T>>,
+    ) -> TypedEventStreamWriter<T> {
+        TypedEventStreamWriter::new(EventStreamWriter::new(stream, self.clone()), serializer)
+    }
+
+    ///
+    /// Create a reader group which reads the given stream from its head. The reader group state
+    /// is created if a reader group with the same name does not exist in the scope.
+    ///
+    pub async fn create_reader_group(
+        &self,
+        scope: Scope,
+        reader_group_name: String,
+        stream: ScopedStream,
+    ) -> Result<ReaderGroup, ReaderGroupStateError> {
+        let config = ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new().stream(stream, None, None));
+        ReaderGroup::create(scope, reader_group_name, config, self.clone()).await
+    }
+
+    ///
+    /// Create a reader which reads all the segments of the given stream from its head. The reader
+    /// is the only member of a reader group created for it.
+    ///
+    #[deprecated(
+        since = "0.1.0",
+        note = "use create_reader_group and ReaderGroup::create_reader instead"
+    )]
     pub async fn create_event_stream_reader(&self, stream: ScopedStream) -> EventReader {
-        EventReader::init(stream, self.clone()).await
+        let reader_group_name = format!("reader{}", get_random_u128());
+        let reader_group = self
+            .create_reader_group(stream.scope.clone(), reader_group_name, stream)
+            .await
+            .expect("create reader group");
+        reader_group
+            .create_reader("reader".to_owned())
+            .await
+            .expect("create reader")
     }
 
     pub async fn create_transactional_event_stream_writer(
@@ -122,10 +214,43 @@ impl ClientFactory {
         ByteStreamWriter::new(segment, self.clone())
     }
 
+    pub async fn create_conditional_segment_writer(
+        &self,
+        segment: ScopedSegment,
+    ) -> ConditionalSegmentWriter {
+        ConditionalSegmentWriter::new(segment, self.clone()).await
+    }
+
     pub fn create_byte_stream_reader(&self, segment: ScopedSegment) -> ByteStreamReader {
         ByteStreamReader::new(segment, self)
     }
 
+    /// Creates a byte stream writer for a stream that has a single segment at a time, the writes
+    /// go to the active segment of the stream and move on to its successor after a scale event.
+    pub fn create_byte_stream_writer_for_stream(
+        &self,
+        stream: ScopedStream,
+    ) -> std::io::Result<ByteStreamWriter> {
+        ByteStreamWriter::new_for_stream(stream, self.clone())
+    }
+
+    /// Creates a byte stream reader for a stream that has a single segment at a time, the reader
+    /// starts at the head of the stream and reads the segments of the stream one after another.
+    pub fn create_byte_stream_reader_for_stream(
+        &self,
+        stream: ScopedStream,
+    ) -> std::io::Result<ByteStreamReader> {
+        ByteStreamReader::new_for_stream(stream, self)
+    }
+
+    pub async fn create_async_byte_stream_writer(&self, segment: ScopedSegment) -> AsyncByteStreamWriter {
+        AsyncByteStreamWriter::new(segment, self.clone()).await
+    }
+
+    pub async fn create_async_byte_stream_reader(&self, segment: ScopedSegment) -> AsyncByteStreamReader {
+        AsyncByteStreamReader::new(segment, self).await
+    }
+
     pub async fn create_delegation_token_provider(&self, stream: ScopedStream) -> DelegationTokenProvider {
         self.0.create_delegation_token_provider(stream).await
     }
@@ -134,6 +259,73 @@ impl ClientFactory {
         SegmentMetadataClient::new(segment.clone(), self.clone()).await
     }
 
+    ///
+    /// Fetch the stream cut at the current head of the stream, i.e. the position of the first
+    /// event which has not been truncated.
+    ///
+    pub async fn fetch_head_stream_cut(
+        &self,
+        stream: &ScopedStream,
+    ) -> Result<StreamCutVersioned, StreamCutError> {
+        let head_segments = self
+            .0
+            .controller_client
+            .get_head_segments(stream)
+            .await
+            .map_err(|e| StreamCutError::StreamCutControllerError { err: e })?;
+        let positions = head_segments
+            .into_iter()
+            .map(|(segment, offset)| {
+                (
+                    ScopedSegment::new(stream.scope.clone(), stream.stream.clone(), segment),
+                    offset,
+                )
+            })
+            .collect::<HashMap<ScopedSegment, i64>>();
+        Ok(StreamCutVersioned::V1(StreamCutV1::new(
+            stream.clone(),
+            positions,
+        )))
+    }
+
+    ///
+    /// Fetch the stream cut at the current tail of the stream, i.e. the position right after the
+    /// last event written to the stream.
+    ///
+    pub async fn fetch_tail_stream_cut(
+        &self,
+        stream: &ScopedStream,
+    ) -> Result<StreamCutVersioned, StreamCutError> {
+        let current_segments = self
+            .0
+            .controller_client
+            .get_current_segments(stream)
+            .await
+            .map_err(|e| StreamCutError::StreamCutControllerError { err: e })?;
+        let mut positions = HashMap::new();
+        for segment in current_segments.get_segments() {
+            let offset = self
+                .create_segment_metadata_client(segment.clone())
+                .await
+                .fetch_current_segment_length()
+                .await
+                .context(StreamCutSegmentError {})?;
+            positions.insert(segment, offset);
+        }
+        Ok(StreamCutVersioned::V1(StreamCutV1::new(
+            stream.clone(),
+            positions,
+        )))
+    }
+
+    ///
+    /// Fetch the event the given event pointer points to, without reading the segment from
+    /// the beginning.
+    ///
+    pub async fn fetch_event(&self, pointer: &EventPointerVersioned) -> Result<Event, ReaderError> {
+        pointer.fetch_event(self).await
+    }
+
     pub fn get_controller_client(&self) -> &dyn ControllerClient {
         self.0.get_controller_client()
     }
@@ -186,3 +378,59 @@ impl fmt::Debug for ClientFactoryInternal {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{create_mock_factory, create_stream};
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_create_event_stream_reader() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "stream"));
+
+        let mut reader =
+            handle.block_on(factory.create_event_stream_reader(ScopedStream::from("scope/stream")));
+        let offsets = reader.get_position().get_segments_with_offsets();
+        assert_eq!(
+            offsets.len(),
+            1,
+            "the reader should own all the segments of the stream"
+        );
+        assert_eq!(offsets.values().cloned().collect::<Vec<i64>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_fetch_head_and_tail_stream_cut() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "cut"));
+        let stream = ScopedStream::from("scope/cut");
+        let segment = ScopedSegment::from("scope/cut/0");
+
+        let mut writer = factory.create_event_stream_writer(stream.clone());
+        for _ in 0..2 {
+            let _ack = handle.block_on(writer.write_event(vec![1; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let head = handle
+            .block_on(factory.fetch_head_stream_cut(&stream))
+            .expect("fetch head stream cut");
+        assert_eq!(head, stream_cut(&stream, &segment, 0));
+
+        // each event is written with a header of 8 bytes.
+        let tail = handle
+            .block_on(factory.fetch_tail_stream_cut(&stream))
+            .expect("fetch tail stream cut");
+        assert_eq!(tail, stream_cut(&stream, &segment, 2 * (8 + 10)));
+    }
+
+    fn stream_cut(stream: &ScopedStream, segment: &ScopedSegment, offset: i64) -> StreamCutVersioned {
+        let mut positions = HashMap::new();
+        positions.insert(segment.clone(), offset);
+        StreamCutVersioned::V1(StreamCutV1::new(stream.clone(), positions))
+    }
+}
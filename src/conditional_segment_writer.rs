@@ -0,0 +1,273 @@
+//
+// Copyright (c) Dell Inc., or its subsidiaries. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+
+use crate::client_factory::ClientFactory;
+use crate::error::*;
+use crate::event_stream_writer::EventStreamWriter;
+use crate::raw_client::RawClient;
+use crate::{get_random_u128, get_request_id};
+use pravega_rust_client_auth::DelegationTokenProvider;
+use pravega_rust_client_retry::retry_async::retry_async;
+use pravega_rust_client_retry::retry_result::RetryResult;
+use pravega_rust_client_shared::{ScopedSegment, ScopedStream, WriterId};
+use pravega_wire_protocol::client_connection::{ClientConnectionReadHalf, ClientConnectionWriteHalf};
+use pravega_wire_protocol::commands::{ConditionalAppendCommand, EventCommand, SetupAppendCommand};
+use pravega_wire_protocol::wire_commands::{Replies, Requests};
+use std::cmp;
+use tracing::{debug, warn};
+
+/// A writer that appends an event to a segment only if the segment length equals the offset
+/// expected by the caller, which allows a single writer log or an optimistic state machine to be
+/// built on a plain segment. The event is written with its header, so that it can be read back
+/// like any event written by an EventStreamWriter.
+pub struct ConditionalSegmentWriter {
+    segment: ScopedSegment,
+    writer_id: WriterId,
+    event_number: i64,
+    factory: ClientFactory,
+    delegation_token_provider: DelegationTokenProvider,
+    connection: Option<(ClientConnectionReadHalf, ClientConnectionWriteHalf)>,
+}
+
+impl ConditionalSegmentWriter {
+    pub(crate) async fn new(segment: ScopedSegment, factory: ClientFactory) -> Self {
+        let delegation_token_provider = factory
+            .create_delegation_token_provider(ScopedStream::from(&segment))
+            .await;
+        ConditionalSegmentWriter {
+            segment,
+            writer_id: WriterId::from(get_random_u128()),
+            event_number: 0,
+            factory,
+            delegation_token_provider,
+            connection: None,
+        }
+    }
+
+    /// Appends the event if the length of the segment equals the expected offset and returns
+    /// the length of the segment after the append. A ConditionalCheckFailed error is returned
+    /// if the segment length differs, in which case nothing is written.
+    ///
+    /// If the connection fails after the event was sent, the connection is set up again and the
+    /// event is sent again with the same event number unless the segment store has already
+    /// appended it, so the event is appended at most once.
+    pub async fn write_at_offset(
+        &mut self,
+        event: Vec<u8>,
+        expected_offset: i64,
+    ) -> Result<i64, ConditionalAppendError> {
+        if event.len() > EventStreamWriter::MAX_EVENT_SIZE {
+            return Err(ConditionalAppendError::ConditionalEventSizeTooLarge {
+                limit: EventStreamWriter::MAX_EVENT_SIZE,
+                size: event.len(),
+            });
+        }
+        if self.connection.is_none() {
+            self.setup_append().await?;
+        }
+
+        self.event_number += 1;
+        let event_number = self.event_number;
+        // the event is appended together with its header of 8 bytes
+        let appended_offset = expected_offset + event.len() as i64 + 8;
+        let request = Requests::ConditionalAppend(ConditionalAppendCommand {
+            writer_id: self.writer_id.0,
+            event_number,
+            expected_offset,
+            event: EventCommand { data: event },
+            request_id: get_request_id(),
+        });
+        let mut resent = false;
+        let reply = loop {
+            let (reader, writer) = self.connection.as_mut().expect("must have connection");
+            let result = match writer.write(&request).await {
+                Ok(()) => reader.read().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(reply) => break reply,
+                Err(e) => {
+                    // the connection is set up again on the next write
+                    self.connection = None;
+                    if resent {
+                        return Err(ConditionalAppendError::ConditionalConnectionFailed {
+                            segment: self.segment.to_string(),
+                            error_msg: format!("{:?}", e),
+                        });
+                    }
+                    warn!(
+                        "connection failed while appending event {} to segment {:?}: {:?}",
+                        event_number, self.segment, e
+                    );
+                }
+            }
+            // the event may have been appended before the connection failed.
+            let last_event_number = self.setup_append().await?;
+            if last_event_number >= event_number {
+                return Ok(appended_offset);
+            }
+            resent = true;
+        };
+
+        match reply {
+            Replies::DataAppended(cmd) => Ok(cmd.current_segment_write_offset),
+            Replies::ConditionalCheckFailed(_cmd) => Err(ConditionalAppendError::ConditionalCheckFailed {
+                segment: self.segment.to_string(),
+                expected_offset,
+            }),
+            reply => Err(self.reply_error(reply)),
+        }
+    }
+
+    // Sets up a connection that appends to the segment using the writer id of this writer and
+    // returns the number of the last event of this writer appended to the segment.
+    async fn setup_append(&mut self) -> Result<i64, ConditionalAppendError> {
+        let controller = self.factory.get_controller_client();
+        let endpoint = controller
+            .get_endpoint_for_segment(&self.segment)
+            .await
+            .map_err(|e| ConditionalAppendError::ConditionalConnectionFailed {
+                segment: self.segment.to_string(),
+                error_msg: format!("failed to get endpoint: {:?}", e),
+            })?;
+        let request = Requests::SetupAppend(SetupAppendCommand {
+            request_id: get_request_id(),
+            writer_id: self.writer_id.0,
+            segment: self.segment.to_string(),
+            delegation_token: self.delegation_token_provider.retrieve_token(controller).await,
+        });
+
+        let raw_client = self.factory.create_raw_client_for_endpoint(endpoint);
+        let result = retry_async(self.factory.get_config().retry_policy, || async {
+            match raw_client.send_setup_request(&request).await {
+                Ok((reply, connection)) => RetryResult::Success((reply, connection)),
+                Err(e) => {
+                    warn!(
+                        "failed to setup conditional append using rawclient due to {:?}",
+                        e
+                    );
+                    if e.is_token_expired() {
+                        self.delegation_token_provider.signal_token_expiry();
+                    }
+                    RetryResult::Retry(e)
+                }
+            }
+        })
+        .await;
+
+        let (reply, mut connection) =
+            result.map_err(|e| ConditionalAppendError::ConditionalConnectionFailed {
+                segment: self.segment.to_string(),
+                error_msg: format!("failed to setup append: {:?}", e),
+            })?;
+        match reply {
+            Replies::AppendSetup(cmd) => {
+                debug!(
+                    "conditional append setup completed for writer:{:?}/segment:{:?}",
+                    self.writer_id, self.segment
+                );
+                // event numbers must keep increasing for the writer id
+                self.event_number = cmp::max(self.event_number, cmd.last_event_number);
+                self.connection = Some(connection.split());
+                Ok(cmd.last_event_number)
+            }
+            reply => {
+                drop(connection);
+                Err(self.reply_error(reply))
+            }
+        }
+    }
+
+    fn reply_error(&mut self, reply: Replies) -> ConditionalAppendError {
+        match reply {
+            Replies::SegmentIsSealed(_cmd) => ConditionalAppendError::ConditionalSegmentSealed {
+                segment: self.segment.to_string(),
+            },
+            Replies::NoSuchSegment(_cmd) => ConditionalAppendError::ConditionalNoSuchSegment {
+                segment: self.segment.to_string(),
+            },
+            reply => {
+                // the connection may be in an unknown state, set it up again on the next write
+                self.connection = None;
+                ConditionalAppendError::ConditionalUnexpectedReply { reply }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{create_mock_factory, create_stream, mock_config_builder, read_segment};
+    use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
+    use pravega_wire_protocol::commands::Command;
+
+    #[test]
+    fn test_write_at_offset() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "conditional"));
+        let mut writer = handle
+            .block_on(factory.create_conditional_segment_writer(ScopedSegment::from("scope/conditional/0")));
+
+        // each event is appended with an 8 byte header
+        let offset = handle
+            .block_on(writer.write_at_offset(vec![1; 100], 0))
+            .expect("append at head");
+        assert_eq!(offset, 108);
+        let offset = handle
+            .block_on(writer.write_at_offset(vec![1; 100], offset))
+            .expect("append at tail");
+        assert_eq!(offset, 216);
+
+        // a stale offset is rejected
+        let result = handle.block_on(writer.write_at_offset(vec![1; 100], 108));
+        assert!(matches!(
+            result,
+            Err(ConditionalAppendError::ConditionalCheckFailed {
+                expected_offset: 108,
+                ..
+            })
+        ));
+        let offset = handle
+            .block_on(writer.write_at_offset(vec![1; 100], 216))
+            .expect("append at tail");
+        assert_eq!(offset, 324);
+    }
+
+    #[test]
+    fn test_write_at_offset_connection_lost_after_append() {
+        let config = mock_config_builder()
+            .connection_type(ConnectionType::Mock(MockType::ConnectionLostAfterAppend))
+            .build()
+            .unwrap();
+        let factory = ClientFactory::new(config);
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "conditional"));
+        let segment = ScopedSegment::from("scope/conditional/0");
+        let mut writer = handle.block_on(factory.create_conditional_segment_writer(segment.clone()));
+
+        // the reply of each append is lost, the writer finds out the event was appended when it
+        // sets up the connection again.
+        let offset = handle
+            .block_on(writer.write_at_offset(vec![1; 100], 0))
+            .expect("append at head");
+        assert_eq!(offset, 108);
+        let offset = handle
+            .block_on(writer.write_at_offset(vec![2; 100], offset))
+            .expect("append at tail");
+        assert_eq!(offset, 216);
+
+        // each event is appended once
+        let mut expected = EventCommand { data: vec![1; 100] }.write_fields().unwrap();
+        expected.extend(EventCommand { data: vec![2; 100] }.write_fields().unwrap());
+        assert_eq!(handle.block_on(read_segment(&factory, &segment)), expected);
+    }
+}
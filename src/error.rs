@@ -8,7 +8,11 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 
+use crate::reader_group::ReaderGroupStateError;
+use crate::segment_metadata::SegmentMetadataClientError;
+use crate::segment_reader::ReaderError;
 use crate::tablemap::TableError;
+use base64::DecodeError;
 use pravega_connection_pool::connection_pool::ConnectionPoolError;
 use pravega_controller_client::ControllerError;
 use pravega_rust_client_retry::retry_result::RetryError;
@@ -16,6 +20,7 @@ use pravega_rust_client_shared::{TransactionStatus, TxId};
 use pravega_wire_protocol::error::*;
 use pravega_wire_protocol::wire_commands::Replies;
 use serde_cbor::Error as CborError;
+use serde_json::Error as JsonError;
 use snafu::Snafu;
 use std::fmt::Debug;
 use tokio::sync::mpsc::error::TryRecvError;
@@ -78,6 +83,19 @@ pub enum SegmentWriterError {
 
     #[snafu(display("Reactor is closed due to: {:?}", msg))]
     ReactorClosed { msg: String },
+
+    #[snafu(display(
+        "Event number {} is not greater than the last event number {} written to the segment",
+        event_number,
+        last_event_number
+    ))]
+    EventNumberOutOfOrder {
+        event_number: i64,
+        last_event_number: i64,
+    },
+
+    #[snafu(display("Failed to write an event due to: {}", error_msg))]
+    EventWriteFailed { error_msg: String },
 }
 
 #[derive(Debug, Snafu)]
@@ -140,6 +158,25 @@ pub enum TransactionError {
 pub enum SerdeError {
     #[snafu(display("Failed to {:?} due to {:?}", msg, source))]
     Cbor { msg: String, source: CborError },
+
+    #[snafu(display("Failed to {:?} due to {:?}", msg, source))]
+    Base64 { msg: String, source: DecodeError },
+
+    #[snafu(display("Failed to {:?} due to {:?}", msg, source))]
+    Json { msg: String, source: JsonError },
+
+    #[snafu(display("Failed to {:?} due to {:?}", msg, error_msg))]
+    Raw { msg: String, error_msg: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility = "pub")]
+pub enum StreamCutError {
+    #[snafu(display("Failed to fetch the segments of the stream from the controller: {:?}", err))]
+    StreamCutControllerError { err: RetryError<ControllerError> },
+
+    #[snafu(display("Failed to fetch the offset of the segment: {}", source))]
+    StreamCutSegmentError { source: SegmentMetadataClientError },
 }
 
 #[derive(Debug, Snafu)]
@@ -158,3 +195,73 @@ pub enum SynchronizerError {
     #[snafu(display("Failed insert tombestone in table synchronizer due to: {:?}", error_msg))]
     SyncTombstoneError { error_msg: String },
 }
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility = "pub")]
+pub enum EventReaderError {
+    #[snafu(display("Reader group state failed while performing {}: {}", operation, source))]
+    ReaderStateError {
+        operation: String,
+        source: ReaderGroupStateError,
+    },
+
+    #[snafu(display("Reader group state failed while performing {}: {}", operation, source))]
+    ReaderSyncError {
+        operation: String,
+        source: SynchronizerError,
+    },
+
+    #[snafu(display(
+        "Failed to fetch the successors of segment {} from the controller: {:?}",
+        segment,
+        err
+    ))]
+    ReaderSuccessorsError {
+        segment: String,
+        err: RetryError<ControllerError>,
+    },
+
+    #[snafu(display(
+        "Segment slices of segments {:?} were dropped without being released",
+        segments
+    ))]
+    ReaderSliceDropped { segments: Vec<String> },
+
+    #[snafu(display("Failed to read from the segment: {}", source))]
+    ReaderSegmentError { source: ReaderError },
+
+    #[snafu(display("Failed to read the watermarks: {}", error_msg))]
+    ReaderWatermarkError { error_msg: String },
+
+    #[snafu(display("Reader state of segment {} is inconsistent: {}", segment, error_msg))]
+    ReaderMetaError { segment: String, error_msg: String },
+
+    #[snafu(display("Reader {} is no longer online in the reader group", reader))]
+    ReaderNotOnline { reader: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility = "pub")]
+pub enum ConditionalAppendError {
+    #[snafu(display(
+        "Conditional append to segment {} failed because its length is not {}",
+        segment,
+        expected_offset
+    ))]
+    ConditionalCheckFailed { segment: String, expected_offset: i64 },
+
+    #[snafu(display("Segment {} is sealed", segment))]
+    ConditionalSegmentSealed { segment: String },
+
+    #[snafu(display("Segment {} does not exist", segment))]
+    ConditionalNoSuchSegment { segment: String },
+
+    #[snafu(display("The size limit is {} while actual size is {}", limit, size))]
+    ConditionalEventSizeTooLarge { limit: usize, size: usize },
+
+    #[snafu(display("Failed to append to segment {} due to: {}", segment, error_msg))]
+    ConditionalConnectionFailed { segment: String, error_msg: String },
+
+    #[snafu(display("Unexpected reply from segmentstore {:?}", reply))]
+    ConditionalUnexpectedReply { reply: Replies },
+}
@@ -9,24 +9,41 @@
 //
 
 use crate::client_factory::ClientFactory;
+use crate::error::*;
+use crate::reader_group::reader_group_state::{Offset, ReaderGroupState, ReaderGroupStateError};
+use crate::segment_metadata::SegmentMetadataClient;
 use crate::segment_reader::ReaderError;
-use crate::segment_slice::{SegmentDataBuffer, SegmentSlice, SliceMetadata};
+use crate::segment_slice::{SegmentDataBuffer, SegmentSlice, SliceMetadata, TypedSegmentSlice};
+use crate::serializer::Serializer;
+use crate::stream::position::{PositionV1, PositionVersioned};
+use crate::stream::watermark::{TimeWindow, WatermarkReader};
 use bytes::BufMut;
 use im::HashMap as ImHashMap;
-use pravega_rust_client_shared::{ScopedSegment, ScopedStream, Segment, SegmentWithRange};
+use pravega_controller_client::ControllerError;
+use pravega_rust_client_retry::retry_result::RetryError;
+use pravega_rust_client_shared::{Reader, ScopedSegment, ScopedStream, Segment, SegmentWithRange};
+use snafu::ResultExt;
+use std::cmp;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
-use std::iter::FromIterator;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::oneshot;
+use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::{oneshot, Mutex};
+use tokio::time::{delay_for, timeout, Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 pub type SegmentReadResult = Result<SegmentDataBuffer, ReaderError>;
 
+// A reader is considered offline if it has not sent a heartbeat for this many refresh intervals.
+const MAX_MISSED_HEARTBEATS: u32 = 10;
+
 ///
-/// This represents an event reader. An event readers fetches its assigned segments and provides the
-/// following APIs.
-/// 1. A method to initialize the event reader [EventReader#init](EventReader#init)
+/// This represents an event reader. An event reader is a member of a reader group, it fetches the
+/// segments assigned to it by the reader group state and provides the following APIs.
+/// 1. A method to create the event reader from a reader group [ReaderGroup#create_reader](ReaderGroup#create_reader)
 /// 2. A method to obtain the a SegmentSlice to read data from.  A SegmentSlice has data for a given
 ///Segment. The user can use the SegmentSlice's iterator API to fetch individual events from a given Segment Slice.
 /// [EventReader#acquire_segment](EventReader#acquire_segment).
@@ -51,16 +68,23 @@ pub type SegmentReadResult = Result<SegmentDataBuffer, ReaderError>;
 ///         scope: Scope::from("scope".to_string()),
 ///         stream: Stream::from("stream".to_string()),
 ///     };
-///     // Create a reader obtain a segment slice and read events from it.
-///     let mut reader = client_factory.create_event_stream_reader(stream).await;
+///     // Create a reader group and a reader, obtain a segment slice and read events from it.
+///     let reader_group = client_factory
+///         .create_reader_group(Scope::from("scope".to_string()), "rg".to_string(), stream)
+///         .await
+///         .expect("creating reader group");
+///     let mut reader = reader_group
+///         .create_reader("reader".to_string())
+///         .await
+///         .expect("creating reader");
 ///     // read all events from a given segment slice.
-///     if let Some(mut segment_slice) =  reader.acquire_segment().await {
+///     if let Some(mut segment_slice) = reader.acquire_segment().await.expect("acquire segment slice") {
 ///         while let Some(event) = segment_slice.next() {
 ///             println!("Event read is {:?}", event);
 ///         }
 ///     }
 ///     // read one event from the a given  segment slice and return it back.
-///     if let Some(mut segment_slice) = reader.acquire_segment().await {
+///     if let Some(mut segment_slice) = reader.acquire_segment().await.expect("acquire segment slice") {
 ///         if let Some(event) = segment_slice.next() {
 ///             println!("Event read is {:?}", event);
 ///             // release the segment slice back to the reader.
@@ -70,19 +94,30 @@ pub type SegmentReadResult = Result<SegmentDataBuffer, ReaderError>;
 /// }
 ///```
 ///
-#[derive(new)]
 pub struct EventReader {
-    stream: ScopedStream,
+    id: Reader,
     factory: ClientFactory,
     rx: Receiver<SegmentReadResult>,
     tx: Sender<SegmentReadResult>,
     meta: ReaderMeta,
+    rg_state: Arc<Mutex<ReaderGroupState>>,
+    refresh_interval: Duration,
+    last_refresh: Instant,
+    heartbeat_stop: Option<oneshot::Sender<()>>,
+    online: bool,
+    checkpoint: Option<String>,
+    watermark_readers: HashMap<ScopedStream, WatermarkReader>,
+    // The metadata clients of the segments read by this reader, they are used to compute the
+    // distance to tail of the reader on every refresh.
+    metadata_clients: HashMap<String, SegmentMetadataClient>,
 }
 
 /// Reader meta data.
 pub struct ReaderMeta {
     slices: HashMap<String, SliceMetadata>,
-    future_segments: HashMap<ScopedSegment, HashSet<Segment>>,
+    segment_ranges: HashMap<String, SegmentWithRange>,
+    read_offsets: HashMap<String, i64>,
+    dished_out_offsets: HashMap<String, i64>,
     slice_release_receiver: HashMap<String, oneshot::Receiver<SliceMetadata>>,
     slice_stop_reading: HashMap<String, oneshot::Sender<()>>,
 }
@@ -101,41 +136,45 @@ impl ReaderMeta {
     }
 
     //
-    // Wait until the user application returns the Segment Slice.
+    // Wait until the user application returns the Segment Slice. None is returned if the Segment
+    // Slice was dropped without being returned.
     //
-    async fn wait_for_segment_slice_return(&mut self, segment: &str) -> SliceMetadata {
-        if let Some(receiver) = self.slice_release_receiver.remove(segment) {
-            match receiver.await {
-                Ok(returned_meta) => {
-                    debug!("SegmentSLice returned {:?}", returned_meta);
-                    if let Some(meta) = self.slices.remove(segment) {
-                        info!("Meta removed for segment {:?}", meta);
-                    }
-                    returned_meta
-                }
-                Err(e) => {
-                    error!("Error Segment slice was not returned {:?}", e);
-                    panic!("A Segment slice was not returned to the Reader.");
+    async fn wait_for_segment_slice_return(&mut self, segment: &str) -> Option<SliceMetadata> {
+        let receiver = match self.slice_release_receiver.remove(segment) {
+            Some(receiver) => receiver,
+            None => {
+                warn!("No receiver for the segment slice of segment {:?}", segment);
+                return None;
+            }
+        };
+        match receiver.await {
+            Ok(returned_meta) => {
+                debug!("SegmentSLice returned {:?}", returned_meta);
+                if let Some(meta) = self.slices.remove(segment) {
+                    info!("Meta removed for segment {:?}", meta);
                 }
+                Some(returned_meta)
+            }
+            Err(e) => {
+                error!("Error Segment slice was not returned {:?}", e);
+                None
             }
-        } else {
-            panic!("This is unexpected, No receiver for SegmentSlice present.");
         }
     }
 
     //
     // Remove segment slice from reader meta and return it.
     // If the reader does not have the segment slice it waits for the segment slice which is out
-    // for consumption.
+    // for consumption, None is returned if that segment slice was dropped.
     //
-    async fn remove_segment(&mut self, segment: String) -> SliceMetadata {
+    async fn remove_segment(&mut self, segment: String) -> Option<SliceMetadata> {
         match self.slices.remove(&segment) {
             Some(meta) => {
                 debug!(
                     "Segment slice {:?} has not been dished out for consumption",
                     &segment
                 );
-                meta
+                Some(meta)
             }
             None => {
                 debug!(
@@ -149,19 +188,41 @@ impl ReaderMeta {
 
     //
     // Add Segment Slices to Reader meta data.
+    // An error is returned if a segment slice of the segment is already present.
     //
-    fn add_slices(&mut self, meta: SliceMetadata) {
-        if self.slices.insert(meta.scoped_segment.clone(), meta).is_some() {
-            panic!("Pre-condition check failure. Segment slice already present");
+    fn add_slices(&mut self, meta: SliceMetadata) -> Result<(), EventReaderError> {
+        match self.slices.entry(meta.scoped_segment.clone()) {
+            Entry::Occupied(_) => ReaderMetaError {
+                segment: meta.scoped_segment,
+                error_msg: "segment slice is already present",
+            }
+            .fail(),
+            Entry::Vacant(entry) => {
+                entry.insert(meta);
+                Ok(())
+            }
         }
     }
 
     //
     // Store a Sender which is used to stop the read task for a given Segment.
+    // An error is returned if the segment is already being read.
     //
-    fn add_stop_reading_tx(&mut self, segment: String, tx: oneshot::Sender<()>) {
-        if self.slice_stop_reading.insert(segment, tx).is_some() {
-            panic!("Pre-condition check failure. Sender used to stop fetching data is already present");
+    fn add_stop_reading_tx(
+        &mut self,
+        segment: String,
+        tx: oneshot::Sender<()>,
+    ) -> Result<(), EventReaderError> {
+        match self.slice_stop_reading.entry(segment) {
+            Entry::Occupied(entry) => ReaderMetaError {
+                segment: entry.key().to_owned(),
+                error_msg: "sender used to stop reading the segment is already present",
+            }
+            .fail(),
+            Entry::Vacant(entry) => {
+                entry.insert(tx);
+                Ok(())
+            }
         }
     }
 
@@ -177,44 +238,69 @@ impl ReaderMeta {
     }
 
     //
-    // Fetch the next segments that can be read by the Event Reader.
+    // Reclaim the segment slices which have already been returned by the user application.
+    // Returns the segments whose segment slices were dropped without being returned.
     //
-    fn next_segments_to_read(
-        &mut self,
-        completed_segment: String,
-        successors_mapped_to_their_predecessors: ImHashMap<SegmentWithRange, Vec<Segment>>,
-    ) -> Vec<ScopedSegment> {
-        info!(
-            "Completed segments {:?}, successor_to_predecessor: {:?} ",
-            completed_segment, successors_mapped_to_their_predecessors
-        );
-        info!("FutureSegments {:?}", self.future_segments);
-        // add missing successors to future_segments
-        for (segment, list) in successors_mapped_to_their_predecessors {
-            if !self.future_segments.contains_key(&segment.scoped_segment) {
-                let required_to_complete = HashSet::from_iter(list.clone().into_iter());
-                // update future segments.
-                self.future_segments
-                    .insert(segment.scoped_segment.to_owned(), required_to_complete);
-            }
-        }
-        debug!("Future Segments after update {:?}", self.future_segments);
-        // remove the completed segment from the dependency list
-        for required_to_complete in self.future_segments.values_mut() {
-            // the hash set needs update
-            required_to_complete.remove(&ScopedSegment::from(completed_segment.as_str()).segment);
-        }
-        debug!(
-            "Future Segments after removing the segment which completed. {:?}",
-            self.future_segments
-        );
-        // find successors that are ready to read. A successor is ready to read
-        // once all its predecessors are completed.
-        self.future_segments
-            .iter()
-            .filter(|&(_segment, set)| set.is_empty())
-            .map(|(segment, _set)| segment.to_owned())
-            .collect::<Vec<ScopedSegment>>()
+    fn reclaim_returned_slices(&mut self) -> Vec<String> {
+        let mut returned = vec![];
+        let mut dropped = vec![];
+        for (segment, rx) in self.slice_release_receiver.iter_mut() {
+            match rx.try_recv() {
+                Ok(meta) => returned.push((segment.clone(), meta)),
+                Err(TryRecvError::Closed) => dropped.push(segment.clone()),
+                Err(TryRecvError::Empty) => {}
+            }
+        }
+        for (segment, meta) in returned {
+            self.slice_release_receiver.remove(&segment);
+            self.slices.entry(segment).or_insert(meta);
+        }
+        dropped
+    }
+
+    //
+    // Stop reading the segments which are no longer assigned to the reader in the reader group
+    // state, e.g. after the reader group has been reset.
+    //
+    fn stop_revoked_segments(&mut self, assigned: &HashSet<ScopedSegment>) {
+        let revoked = self
+            .segment_ranges
+            .keys()
+            .filter(|segment| !assigned.contains(&ScopedSegment::from(segment.as_str())))
+            .cloned()
+            .collect::<Vec<String>>();
+        for segment in revoked {
+            info!("Segment {:?} is no longer assigned to the reader", segment);
+            self.stop_reading(&segment);
+            self.read_offsets.remove(&segment);
+            self.segment_ranges.remove(&segment);
+            self.slices.remove(&segment);
+            self.slice_release_receiver.remove(&segment);
+        }
+    }
+
+    //
+    // Stop reading all the segments and drop the segment slices of the reader, the segment slices
+    // which have been handed out are ignored when they are released.
+    //
+    fn stop_all(&mut self) {
+        let segments = self.slice_stop_reading.keys().cloned().collect::<Vec<String>>();
+        for segment in segments {
+            self.stop_reading(&segment);
+        }
+        self.slices.clear();
+        self.segment_ranges.clear();
+        self.read_offsets.clear();
+        self.dished_out_offsets.clear();
+        self.slice_release_receiver.clear();
+    }
+
+    //
+    // Check if the reader still reads from the given segment, data of released segments may still
+    // be received from the background read tasks.
+    //
+    fn is_segment_owned(&self, segment: &str) -> bool {
+        self.slices.contains_key(segment) || self.slice_release_receiver.contains_key(segment)
     }
 
     fn get_segment_id_with_data(&self) -> Option<String> {
@@ -226,69 +312,99 @@ impl ReaderMeta {
 
 impl EventReader {
     ///
-    /// Initialize the reader. This fetches the initial segments of the stream and spawns background
-    /// tasks to start reads from those Segments.
-    /// Note: In future the TableSyncrhonizer can be used to fetch the segments assigned to this EventReader.
+    /// Initialize the reader. The reader acquires its share of the unassigned segments from the
+    /// reader group state and spawns background tasks to start reads from those Segments.
+    /// If the reader group state can not be accessed the segments are acquired on the next
+    /// invocation of `acquire_segment`.
     ///
-    pub async fn init(stream: ScopedStream, factory: ClientFactory) -> Self {
+    pub(crate) async fn init_reader(
+        id: Reader,
+        rg_state: Arc<Mutex<ReaderGroupState>>,
+        refresh_interval: Duration,
+        factory: ClientFactory,
+    ) -> Self {
         let (tx, rx) = mpsc::channel(1);
-        let slice_meta_map = EventReader::get_slice_meta(&stream, &factory).await;
-        let mut stop_reading_map: HashMap<String, oneshot::Sender<()>> = HashMap::new();
-        // spawn background fetch tasks.
-        slice_meta_map.iter().for_each(|(segment, meta)| {
-            let (tx_stop, rx_stop) = oneshot::channel();
-            stop_reading_map.insert(segment.clone(), tx_stop);
-            factory.get_runtime_handle().enter(|| {
-                tokio::spawn(SegmentSlice::get_segment_data(
-                    ScopedSegment::from(segment.as_str()),
-                    meta.start_offset,
-                    tx.clone(),
-                    rx_stop,
-                    factory.clone(),
-                ))
-            });
+        let mut reader = EventReader::init_event_reader(
+            id,
+            rg_state,
+            refresh_interval,
+            factory,
+            tx,
+            rx,
+            HashMap::new(),
+            HashMap::new(),
+        );
+        reader.start_heartbeats();
+        if let Err(e) = reader.rebalance_segments().await {
+            warn!("Reader {:?} failed to acquire its segments: {}", reader.id, e);
+            // retry on the next acquire_segment.
+            reader.last_refresh = Instant::now()
+                .checked_sub(refresh_interval)
+                .unwrap_or(reader.last_refresh);
+        }
+        reader
+    }
+
+    //
+    // Spawn a background task sending the heartbeats of this reader.
+    //
+    fn start_heartbeats(&mut self) {
+        let (heartbeat_stop_tx, heartbeat_stop_rx) = oneshot::channel();
+        let id = self.id.clone();
+        let rg_state = self.rg_state.clone();
+        let interval = self.refresh_interval;
+        self.factory.get_runtime_handle().enter(|| {
+            tokio::spawn(EventReader::send_heartbeats(
+                id,
+                rg_state,
+                interval,
+                heartbeat_stop_rx,
+            ))
         });
+        self.heartbeat_stop = Some(heartbeat_stop_tx);
+    }
 
-        // initialize the event reader.
-        EventReader::init_event_reader(stream, factory, tx, rx, slice_meta_map, stop_reading_map)
+    //
+    // Stop the background task sending the heartbeats of this reader.
+    //
+    fn stop_heartbeats(&mut self) {
+        if let Some(tx) = self.heartbeat_stop.take() {
+            if tx.send(()).is_err() {
+                debug!("Heartbeat task already stopped, ignoring the error");
+            }
+        }
     }
 
     //
-    // Fetch slice meta for this Event Reader.
-    // At present this data is fetched from the controller. In future this can be read from the Reader group state.
+    // Periodically report to the reader group state that this reader is alive, until the reader
+    // is dropped or put offline. A heartbeat which fails is sent again on the next interval.
     //
-    async fn get_slice_meta(
-        stream: &ScopedStream,
-        factory: &ClientFactory,
-    ) -> HashMap<String, SliceMetadata> {
-        let segments = factory
-            .get_controller_client()
-            .get_head_segments(stream)
-            .await
-            .expect("Error while fetching  current segments to read from ");
-        // slice meta
-        let mut slice_meta_map: HashMap<String, SliceMetadata> = HashMap::new();
-        slice_meta_map.extend(segments.iter().map(|(seg, offset)| {
-            let scoped_segment = ScopedSegment {
-                scope: stream.scope.clone(),
-                stream: stream.stream.clone(),
-                segment: seg.clone(),
-            };
-            (
-                scoped_segment.to_string(),
-                SliceMetadata {
-                    scoped_segment: scoped_segment.to_string(),
-                    start_offset: *offset,
-                    ..Default::default()
-                },
-            )
-        }));
-        slice_meta_map
+    async fn send_heartbeats(
+        id: Reader,
+        rg_state: Arc<Mutex<ReaderGroupState>>,
+        interval: Duration,
+        mut stop: oneshot::Receiver<()>,
+    ) {
+        loop {
+            delay_for(interval).await;
+            match stop.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                _ => {
+                    debug!("Stop sending heartbeats for reader {:?}", id);
+                    break;
+                }
+            }
+            if let Err(e) = rg_state.lock().await.update_heartbeat(&id).await {
+                warn!("Failed to send heartbeat for reader {:?}: {:?}", id, e);
+            }
+        }
     }
 
-    #[doc(hidden)]
-    pub fn init_event_reader(
-        stream: ScopedStream,
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn init_event_reader(
+        id: Reader,
+        rg_state: Arc<Mutex<ReaderGroupState>>,
+        refresh_interval: Duration,
         factory: ClientFactory,
         tx: Sender<SegmentReadResult>,
         rx: Receiver<SegmentReadResult>,
@@ -296,23 +412,537 @@ impl EventReader {
         slice_stop_reading: HashMap<String, oneshot::Sender<()>>,
     ) -> Self {
         EventReader {
-            stream,
+            id,
             factory,
             rx,
             tx,
             meta: ReaderMeta {
                 slices: segment_slice_map,
-                future_segments: HashMap::new(),
+                segment_ranges: HashMap::new(),
+                read_offsets: HashMap::new(),
+                dished_out_offsets: HashMap::new(),
                 slice_release_receiver: HashMap::new(),
                 slice_stop_reading,
             },
+            rg_state,
+            refresh_interval,
+            last_refresh: Instant::now(),
+            heartbeat_stop: None,
+            online: true,
+            checkpoint: None,
+            watermark_readers: HashMap::new(),
+            metadata_clients: HashMap::new(),
         }
     }
 
+    ///
+    /// Put the reader offline. The segments owned by this reader are released at the offsets up to
+    /// which they have been consumed so that the other readers in the reader group can acquire them.
+    /// The events of the segment slices which have not been released are read again by the
+    /// readers acquiring those segments.
+    ///
+    pub async fn reader_offline(mut self) -> Result<(), EventReaderError> {
+        self.stop_heartbeats();
+        if !self.online {
+            debug!(
+                "Reader {:?} has already been put offline by the reader group",
+                self.id
+            );
+            return Ok(());
+        }
+        let position = self.get_position();
+        let owned_segments = self.meta.segment_ranges.keys().cloned().collect::<Vec<String>>();
+        for segment in owned_segments {
+            self.meta.stop_reading(&segment);
+        }
+        info!("Reader {:?} goes offline at position {:?}", self.id, position);
+        self.rg_state
+            .lock()
+            .await
+            .reader_offline(&self.id, Some(position))
+            .await
+            .context(ReaderStateError {
+                operation: "put reader offline",
+            })
+    }
+
+    ///
+    /// Returns the position of this reader, i.e. the offsets up to which the events of the segments
+    /// owned by the reader have been consumed. The events of a segment slice which has not been
+    /// consumed or released yet are considered not consumed.
+    ///
+    /// A reader can be created at this position using `ReaderGroup::create_reader_from_position`.
+    ///
+    pub fn get_position(&mut self) -> PositionVersioned {
+        self.meta.reclaim_returned_slices();
+        let segments = self
+            .meta
+            .segment_ranges
+            .iter()
+            .filter_map(|(segment, segment_with_range)| {
+                let offset = match self.meta.slices.get(segment) {
+                    Some(slice_meta) => Some(slice_meta.get_consumed_offset()),
+                    None => self.meta.dished_out_offsets.get(segment).copied(),
+                };
+                offset.map(|offset| (segment_with_range.clone(), offset))
+            })
+            .collect::<HashMap<SegmentWithRange, i64>>();
+        PositionVersioned::V1(PositionV1::new(segments))
+    }
+
+    ///
+    /// Returns the time window of the events this reader is currently reading from the given
+    /// stream, based on the watermarks the controller computes from the times noted by the writers
+    /// of the stream. The window is computed using the position of this reader, see `get_position`.
+    ///
+    pub async fn get_current_time_window(
+        &mut self,
+        stream: &ScopedStream,
+    ) -> Result<TimeWindow, EventReaderError> {
+        let position = self
+            .get_position()
+            .get_segments_with_offsets()
+            .into_iter()
+            .filter(|(segment, _offset)| ScopedStream::from(&segment.scoped_segment) == *stream)
+            .collect::<HashMap<SegmentWithRange, i64>>();
+        let watermark_reader = self
+            .watermark_readers
+            .entry(stream.clone())
+            .or_insert_with(|| WatermarkReader::new(stream));
+        watermark_reader.fetch_updates(&self.factory).await?;
+        Ok(watermark_reader.compute_time_window(&position))
+    }
+
+    //
+    // Start reading the segments which are assigned to this reader in the reader group state but
+    // are not read by the reader, e.g. the segments of the position the reader is created at.
+    //
+    async fn resume_owned_segments(
+        &mut self,
+        state: &mut ReaderGroupState,
+        assigned: &HashSet<ScopedSegment>,
+    ) -> Result<(), EventReaderError> {
+        if assigned
+            .iter()
+            .all(|segment| self.meta.segment_ranges.contains_key(&segment.to_string()))
+        {
+            return Ok(());
+        }
+        let owned = state
+            .get_reader_positions(&self.id)
+            .await
+            .context(ReaderSyncError {
+                operation: "get segments owned by the reader",
+            })?;
+        let end_offsets = state.get_end_offsets().await.context(ReaderStateError {
+            operation: "get end offsets",
+        })?;
+        for (segment, offset) in owned {
+            if self
+                .meta
+                .segment_ranges
+                .contains_key(&segment.scoped_segment.to_string())
+            {
+                continue;
+            }
+            let end_offset = end_offsets
+                .get(&segment.scoped_segment)
+                .map_or(i64::MAX, |offset| offset.to_owned());
+            self.start_reading(segment, offset, end_offset)?;
+        }
+        Ok(())
+    }
+
+    //
+    // Report the distance to tail of this reader to the reader group state, then acquire or
+    // release segments so that the segments are evenly distributed among the readers.
+    //
+    async fn rebalance_segments(&mut self) -> Result<(), EventReaderError> {
+        self.last_refresh = Instant::now();
+        let distance = self.compute_distance_to_tail().await;
+        let rg_state = self.rg_state.clone();
+        let mut state = rg_state.lock().await;
+        state
+            .remove_offline_readers(self.refresh_interval * MAX_MISSED_HEARTBEATS)
+            .await
+            .context(ReaderStateError {
+                operation: "remove offline readers",
+            })?;
+        if !state.is_reader_online(&self.id) {
+            return self.evicted();
+        }
+        let assigned = state
+            .get_segments_for_reader(&self.id)
+            .await
+            .context(ReaderSyncError {
+                operation: "get segments for reader",
+            })?;
+        self.meta.stop_revoked_segments(&assigned);
+        self.resume_owned_segments(&mut state, &assigned).await?;
+        state
+            .update_distance_to_tail(&self.id, distance)
+            .await
+            .context(ReaderStateError {
+                operation: "update distance to tail",
+            })?;
+        // the segments owned by the reader should not change until it passes the pending checkpoint.
+        self.checkpoint = state
+            .get_checkpoint_for_reader(&self.id)
+            .await
+            .context(ReaderStateError {
+                operation: "get checkpoint for reader",
+            })?;
+        if let Some(checkpoint) = &self.checkpoint {
+            debug!("Reader {:?} has not passed checkpoint {}", self.id, checkpoint);
+            return Ok(());
+        }
+        let to_acquire_or_release = state
+            .compute_segments_to_acquire_or_release(&self.id)
+            .await
+            .context(ReaderSyncError {
+                operation: "compute segments to acquire or release",
+            })?;
+        match to_acquire_or_release.cmp(&0) {
+            Ordering::Greater => {
+                let end_offsets = state.get_end_offsets().await.context(ReaderStateError {
+                    operation: "get end offsets",
+                })?;
+                for _ in 0..to_acquire_or_release {
+                    match state
+                        .assign_segment_to_reader(&self.id)
+                        .await
+                        .context(ReaderStateError {
+                            operation: "assign segment to reader",
+                        })? {
+                        Some((segment, offset)) => {
+                            let end_offset = end_offsets
+                                .get(&segment.scoped_segment)
+                                .map_or(i64::MAX, |offset| offset.to_owned());
+                            self.start_reading(segment, offset, end_offset)?;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            Ordering::Less => {
+                // only the segments which are not being consumed by the application can be released.
+                self.meta.reclaim_returned_slices();
+                let to_release = self
+                    .meta
+                    .slices
+                    .keys()
+                    .take(-to_acquire_or_release as usize)
+                    .cloned()
+                    .collect::<Vec<String>>();
+                for segment in to_release {
+                    if let Some((segment, offset)) = self.stop_reading(&segment) {
+                        info!(
+                            "Reader {:?} releases segment {:?} at offset {}",
+                            self.id, segment, offset
+                        );
+                        state
+                            .release_segment(
+                                &self.id,
+                                &segment.scoped_segment,
+                                &Offset::new(offset as u64, offset as u64),
+                            )
+                            .await
+                            .context(ReaderStateError {
+                                operation: "release segment",
+                            })?;
+                    }
+                }
+            }
+            Ordering::Equal => debug!("Reader {:?} owns its fair share of segments", self.id),
+        }
+        Ok(())
+    }
+
+    //
+    // The reader has been put offline by the reader group, e.g. because its heartbeats were not
+    // received in time. Its segments may already be read by other readers, so it stops reading
+    // and drops the data it has buffered.
+    //
+    fn evicted(&mut self) -> Result<(), EventReaderError> {
+        warn!("Reader {:?} has been put offline by the reader group", self.id);
+        self.stop_heartbeats();
+        self.meta.stop_all();
+        self.checkpoint = None;
+        self.online = false;
+        ReaderNotOnline {
+            reader: self.id.to_string(),
+        }
+        .fail()
+    }
+
+    //
+    // Add the reader back to the reader group after it was put offline, it then acquires its
+    // share of the unassigned segments.
+    //
+    async fn rejoin(&mut self) -> Result<(), EventReaderError> {
+        info!("Reader {:?} rejoins the reader group", self.id);
+        self.rg_state
+            .lock()
+            .await
+            .add_reader(&self.id)
+            .await
+            .context(ReaderStateError {
+                operation: "add reader to the reader group",
+            })?;
+        self.online = true;
+        self.start_heartbeats();
+        self.rebalance_segments().await
+    }
+
+    //
+    // Compute the number of bytes this reader has yet to read from its assigned segments.
+    //
+    #[allow(clippy::map_entry)] // clippy warns about using entry, but async closure is not stable
+    async fn compute_distance_to_tail(&mut self) -> u64 {
+        // the clients of the segments that are no longer read are dropped.
+        let read_offsets = &self.meta.read_offsets;
+        self.metadata_clients
+            .retain(|segment, _client| read_offsets.contains_key(segment));
+        let mut distance = 0;
+        for (segment, read_offset) in &self.meta.read_offsets {
+            if !self.metadata_clients.contains_key(segment) {
+                let metadata_client = self
+                    .factory
+                    .create_segment_metadata_client(ScopedSegment::from(segment.as_str()))
+                    .await;
+                self.metadata_clients.insert(segment.to_owned(), metadata_client);
+            }
+            let metadata_client = self
+                .metadata_clients
+                .get(segment)
+                .expect("must have metadata client");
+            match metadata_client.fetch_current_segment_length().await {
+                Ok(tail) => distance += cmp::max(tail - read_offset, 0) as u64,
+                Err(e) => warn!("Failed to fetch the length of segment {:?}: {:?}", segment, e),
+            }
+        }
+        distance
+    }
+
+    //
+    // The read task of a segment which was stopped may have reported the end of the segment before
+    // the segment was acquired again. The report is stale if the segment is not read up to its end.
+    //
+    async fn is_stale_end_of_segment(&mut self, slice_meta: &SliceMetadata) -> bool {
+        let segment = &slice_meta.scoped_segment;
+        let read_offset = match self.meta.read_offsets.get(segment) {
+            Some(read_offset) if *read_offset < slice_meta.end_offset => *read_offset,
+            _ => return false,
+        };
+        if !self.metadata_clients.contains_key(segment) {
+            let metadata_client = self
+                .factory
+                .create_segment_metadata_client(ScopedSegment::from(segment.as_str()))
+                .await;
+            self.metadata_clients.insert(segment.to_owned(), metadata_client);
+        }
+        let metadata_client = self
+            .metadata_clients
+            .get(segment)
+            .expect("must have metadata client");
+        match metadata_client.fetch_current_segment_length().await {
+            Ok(length) => read_offset < length,
+            Err(e) => {
+                warn!("Failed to fetch the length of segment {:?}: {:?}", segment, e);
+                false
+            }
+        }
+    }
+
+    //
+    // Stop reading from a segment that is not being consumed by the application, returns the
+    // segment and the offset up to which it has been consumed.
+    //
+    fn stop_reading(&mut self, segment: &str) -> Option<(SegmentWithRange, i64)> {
+        self.meta.stop_reading(segment);
+        self.meta.read_offsets.remove(segment);
+        let slice_meta = self.meta.slices.remove(segment)?;
+        let segment_with_range = self.meta.segment_ranges.remove(segment)?;
+        Some((segment_with_range, slice_meta.get_consumed_offset()))
+    }
+
+    //
+    // Spawn a background task to read from the given segment starting at the given offset until
+    // the given end offset. An error is returned if the segment is already being read.
+    //
+    fn start_reading(
+        &mut self,
+        segment: SegmentWithRange,
+        offset: Offset,
+        end_offset: i64,
+    ) -> Result<(), EventReaderError> {
+        info!(
+            "Reader {:?} starts reading segment {:?} at {:?}",
+            self.id, segment, offset
+        );
+        let scoped_segment = segment.scoped_segment.clone();
+        let meta = SliceMetadata {
+            scoped_segment: scoped_segment.to_string(),
+            start_offset: offset.read as i64,
+            read_offset: offset.read as i64,
+            end_offset,
+            ..Default::default()
+        };
+        let segment_name = meta.scoped_segment.clone();
+        let start_offset = meta.start_offset;
+        self.meta.add_slices(meta)?;
+        let (tx_stop, rx_stop) = oneshot::channel();
+        if let Err(e) = self.meta.add_stop_reading_tx(segment_name.clone(), tx_stop) {
+            self.meta.slices.remove(&segment_name);
+            return Err(e);
+        }
+        let tx = self.tx.clone();
+        let factory = self.factory.clone();
+        self.factory.get_runtime_handle().enter(|| {
+            tokio::spawn(SegmentSlice::get_segment_data(
+                scoped_segment,
+                start_offset,
+                end_offset,
+                tx,
+                rx_stop,
+                factory,
+            ))
+        });
+        self.meta.segment_ranges.insert(segment_name.clone(), segment);
+        self.meta.read_offsets.insert(segment_name, start_offset);
+        Ok(())
+    }
+
+    //
+    // Read the segments of the dropped segment slices again, starting at the offsets the slices
+    // were handed out at, so that the events of the dropped slices are delivered again.
+    //
+    async fn recover_dropped_slices(&mut self, dropped: Vec<String>) -> Result<(), EventReaderError> {
+        if dropped.is_empty() {
+            return Ok(());
+        }
+        let end_offsets = self
+            .rg_state
+            .lock()
+            .await
+            .get_end_offsets()
+            .await
+            .context(ReaderStateError {
+                operation: "get end offsets",
+            })?;
+        for segment in &dropped {
+            self.meta.stop_reading(segment);
+            self.meta.read_offsets.remove(segment);
+            self.meta.slices.remove(segment);
+            self.meta.slice_release_receiver.remove(segment);
+            let offset = self.meta.dished_out_offsets.get(segment).copied();
+            match (self.meta.segment_ranges.remove(segment), offset) {
+                (Some(segment_with_range), Some(offset)) => {
+                    warn!(
+                        "Segment slice of segment {:?} was dropped, read it again from offset {}",
+                        segment, offset
+                    );
+                    let end_offset = end_offsets
+                        .get(&segment_with_range.scoped_segment)
+                        .map_or(i64::MAX, |offset| offset.to_owned());
+                    self.start_reading(
+                        segment_with_range,
+                        Offset::new(offset as u64, offset as u64),
+                        end_offset,
+                    )?;
+                }
+                _ => debug!("Dropped segment {:?} is no longer owned by the reader", segment),
+            }
+        }
+        ReaderSliceDropped { segments: dropped }.fail()
+    }
+
+    //
+    // Release a segment which is no longer read by this reader at the given offset, so that it is
+    // acquired and read again by a reader of the reader group.
+    //
+    async fn release_unread_segment(
+        &mut self,
+        segment: &SegmentWithRange,
+        offset: i64,
+    ) -> Result<(), EventReaderError> {
+        info!(
+            "Reader {:?} releases segment {:?} at offset {} to be read again",
+            self.id, segment, offset
+        );
+        self.rg_state
+            .lock()
+            .await
+            .release_segment(
+                &self.id,
+                &segment.scoped_segment,
+                &Offset::new(offset as u64, offset as u64),
+            )
+            .await
+            .context(ReaderStateError {
+                operation: "release segment",
+            })
+    }
+
+    //
+    // Record the position of this reader in the pending checkpoint and return a checkpoint marker.
+    // The position is only known once all the segment slices have been returned by the application.
+    //
+    async fn pass_checkpoint(&mut self) -> Result<Option<SegmentSlice>, EventReaderError> {
+        let checkpoint = match self.checkpoint.clone() {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+        let dropped = self.meta.reclaim_returned_slices();
+        self.recover_dropped_slices(dropped).await?;
+        if !self.meta.slice_release_receiver.is_empty() {
+            debug!(
+                "Reader {:?} waits for the segment slices to be returned to pass checkpoint {}",
+                self.id, checkpoint
+            );
+            return Ok(None);
+        }
+        let positions = self
+            .meta
+            .slices
+            .iter()
+            .map(|(segment, meta)| (ScopedSegment::from(segment.as_str()), meta.get_consumed_offset()))
+            .collect::<HashMap<ScopedSegment, i64>>();
+        self.rg_state
+            .lock()
+            .await
+            .checkpoint(&self.id, &checkpoint, &positions)
+            .await
+            .context(ReaderStateError {
+                operation: "pass checkpoint",
+            })?;
+        info!(
+            "Reader {:?} passes checkpoint {} at {:?}",
+            self.id, checkpoint, positions
+        );
+        self.checkpoint = None;
+        Ok(Some(SegmentSlice::checkpoint_marker(checkpoint)))
+    }
+
     ///
     /// Release a partially read segment slice back to event reader.
     ///
     pub fn release_segment_at(&mut self, slice: SegmentSlice) {
+        if slice.is_checkpoint() {
+            debug!("Ignore the release of checkpoint marker {:?}", slice.checkpoint);
+            return;
+        }
+        if !self
+            .meta
+            .slice_release_receiver
+            .contains_key(&slice.meta.scoped_segment)
+        {
+            debug!(
+                "Ignore the release of segment {:?} which is no longer assigned to the reader",
+                slice.meta.scoped_segment
+            );
+            return;
+        }
         //stop reading data
         if let Some(tx) = slice.slice_return_tx {
             if let Err(_e) = tx.send(slice.meta.clone()) {
@@ -322,10 +952,16 @@ impl EventReader {
                 );
             }
         } else {
-            panic!("This is unexpected, No sender for SegmentSlice present.");
+            warn!(
+                "Ignore the release of segment slice {:?} which was not handed out by the reader",
+                slice.meta
+            );
+            return;
         }
         //update meta data.
-        self.meta.add_slices(slice.meta);
+        if let Err(e) = self.meta.add_slices(slice.meta) {
+            warn!("Ignore the release of segment slice: {}", e);
+        }
     }
 
     ///
@@ -337,74 +973,197 @@ impl EventReader {
     /// acquired SegmentSlice this method waits until SegmentSlice is completely consumed before
     /// returning the data.
     ///
-    pub async fn acquire_segment(&mut self) -> Option<SegmentSlice> {
+    /// When a checkpoint of the reader group is in progress a checkpoint marker is returned, see
+    /// `SegmentSlice::is_checkpoint`. The marker is returned once all the SegmentSlices handed out
+    /// by this reader have been consumed or released, so the application can persist its own state
+    /// consistently with the checkpoint.
+    ///
+    /// None is returned once the reader group has read all of its segments, either up to the
+    /// ending stream cuts of the reader group config or until the segments are sealed.
+    ///
+    /// An error is returned if the reader group state, the controller or the segment store fail,
+    /// or if a SegmentSlice was dropped without being released. The reader recovers from these
+    /// errors: the segments affected are read again, so this function can be invoked again.
+    ///
+    /// `EventReaderError::ReaderNotOnline` is returned if the reader group has put this reader
+    /// offline because its heartbeats were missed. The reader then drops the SegmentSlices it has
+    /// not handed out and ignores the release of those handed out, as their segments are read by
+    /// other readers. The next invocation adds the reader back to the reader group.
+    ///
+    pub async fn acquire_segment(&mut self) -> Result<Option<SegmentSlice>, EventReaderError> {
+        if !self.online {
+            self.rejoin().await?;
+        }
+        if self.last_refresh.elapsed() >= self.refresh_interval {
+            self.rebalance_segments().await?;
+        }
+        if let Some(marker) = self.pass_checkpoint().await? {
+            return Ok(Some(marker));
+        }
         // 1.Check if any of the segments have event data
         if let Some(segment_with_data) = self.meta.get_segment_id_with_data() {
-            let slice_meta = self.meta.slices.remove(segment_with_data.as_str()).unwrap();
-            // Create an one-shot channel to receive SegmentSlice return.
-            let (slice_return_tx, slice_return_rx) = oneshot::channel();
-            self.meta
-                .add_slice_release_receiver(slice_meta.scoped_segment.clone(), slice_return_rx);
-
-            info!(
-                "Segment Slice for {:?} is returned for consumption",
-                slice_meta.scoped_segment
-            );
-            Some(SegmentSlice {
-                meta: slice_meta,
-                slice_return_tx: Some(slice_return_tx),
-            })
-        } else if let Some(read_result) = self.rx.recv().await {
-            match read_result {
-                // received segment data
-                Ok(data) => {
-                    assert_eq!(
-                        self.stream,
-                        ScopedSegment::from(data.segment.as_str()).get_scoped_stream(),
-                        "Received data from a different segment"
-                    );
-                    let mut slice_meta = self.meta.remove_segment(data.segment.clone()).await;
-                    // add received data to Segment slice.
-                    EventReader::add_data_to_segment_slice(data, &mut slice_meta);
-
-                    // Create an one-shot channel to receive SegmentSlice return.
-                    let (slice_return_tx, slice_return_rx) = oneshot::channel();
-                    self.meta
-                        .add_slice_release_receiver(slice_meta.scoped_segment.clone(), slice_return_rx);
-
-                    info!(
-                        "Segment Slice for {:?} is returned for consumption",
-                        slice_meta.scoped_segment
-                    );
-                    Some(SegmentSlice {
-                        meta: slice_meta,
-                        slice_return_tx: Some(slice_return_tx),
-                    })
+            if let Some(slice_meta) = self.meta.slices.remove(segment_with_data.as_str()) {
+                return Ok(Some(self.dish_out_slice(slice_meta)));
+            }
+        }
+        // 2.Wait for data, periodically checking the reader group state for unassigned segments.
+        loop {
+            match timeout(self.refresh_interval, self.rx.recv()).await {
+                Ok(Some(read_result)) => {
+                    let segment = match &read_result {
+                        Ok(data) => data.segment.clone(),
+                        Err(e) => e.get_segment(),
+                    };
+                    if !self.meta.is_segment_owned(&segment) {
+                        debug!("Ignore the read result of released segment {:?}", segment);
+                        continue;
+                    }
+                    if let Some(slice) = self.handle_read_result(read_result).await? {
+                        return Ok(Some(slice));
+                    }
+                    if self.reached_end_of_stream().await? {
+                        return Ok(None);
+                    }
+                }
+                Ok(None) => {
+                    info!("All Segment slices have completed reading from the stream.");
+                    return Ok(None);
+                }
+                Err(_) => {
+                    self.rebalance_segments().await?;
+                    if let Some(marker) = self.pass_checkpoint().await? {
+                        return Ok(Some(marker));
+                    }
+                    if self.reached_end_of_stream().await? {
+                        return Ok(None);
+                    }
                 }
-                Err(e) => {
-                    let segment = e.get_segment();
-                    debug!("Reader Error observed {:?} on segment {:?}", e, segment);
-                    // Remove the slice from the reader meta and fetch successors.
-                    let slice_meta = self.meta.remove_segment(segment.clone()).await;
+            }
+        }
+    }
 
-                    info!("Segment slice {:?} has received error {:?}", slice_meta, e);
-                    self.fetch_successors(e).await;
+    //
+    // The end of stream is reached once this reader has no segments to read and all the segments
+    // of the reader group have been read.
+    //
+    async fn reached_end_of_stream(&mut self) -> Result<bool, EventReaderError> {
+        if !self.meta.segment_ranges.is_empty() {
+            return Ok(false);
+        }
+        let end_of_data = self
+            .rg_state
+            .lock()
+            .await
+            .is_end_of_data()
+            .await
+            .context(ReaderStateError {
+                operation: "check end of data",
+            })?;
+        if end_of_data {
+            info!("Reader {:?} has reached the end of stream", self.id);
+        }
+        Ok(end_of_data)
+    }
+
+    //
+    // Hand out a segment slice for consumption, the slice is returned to the reader once it is
+    // consumed or released.
+    //
+    fn dish_out_slice(&mut self, slice_meta: SliceMetadata) -> SegmentSlice {
+        // Create an one-shot channel to receive SegmentSlice return.
+        let (slice_return_tx, slice_return_rx) = oneshot::channel();
+        self.meta
+            .add_slice_release_receiver(slice_meta.scoped_segment.clone(), slice_return_rx);
+        // the events of the slice are consumed only once the slice is returned.
+        self.meta.dished_out_offsets.insert(
+            slice_meta.scoped_segment.clone(),
+            slice_meta.get_consumed_offset(),
+        );
+
+        info!(
+            "Segment Slice for {:?} is returned for consumption",
+            slice_meta.scoped_segment
+        );
+        SegmentSlice {
+            meta: slice_meta,
+            slice_return_tx: Some(slice_return_tx),
+            checkpoint: None,
+        }
+    }
 
-                    debug!("segment Slice meta {:?}", self.meta.slices);
-                    None
+    async fn handle_read_result(
+        &mut self,
+        read_result: SegmentReadResult,
+    ) -> Result<Option<SegmentSlice>, EventReaderError> {
+        match read_result {
+            // received segment data
+            Ok(data) => {
+                if let Some(read_offset) = self.meta.read_offsets.get_mut(&data.segment) {
+                    // data read before the segment was read again is stale.
+                    if *read_offset != data.offset_in_segment {
+                        debug!(
+                            "Ignore stale data of segment {:?} at offset {}",
+                            data.segment, data.offset_in_segment
+                        );
+                        return Ok(None);
+                    }
+                    *read_offset = data.offset_in_segment + data.value.len() as i64;
+                }
+                let mut slice_meta = match self.meta.remove_segment(data.segment.clone()).await {
+                    Some(slice_meta) => slice_meta,
+                    None => {
+                        return self
+                            .recover_dropped_slices(vec![data.segment])
+                            .await
+                            .map(|_| None)
+                    }
+                };
+                // add received data to Segment slice.
+                EventReader::add_data_to_segment_slice(data, &mut slice_meta);
+                if !slice_meta.has_events() {
+                    // a large event spans several reads, keep buffering until it is complete.
+                    self.meta.add_slices(slice_meta)?;
+                    return Ok(None);
                 }
+                Ok(Some(self.dish_out_slice(slice_meta)))
+            }
+            Err(e) => {
+                let segment = e.get_segment();
+                debug!("Reader Error observed {:?} on segment {:?}", e, segment);
+                // Remove the slice from the reader meta and fetch successors.
+                let slice_meta = match self.meta.remove_segment(segment.clone()).await {
+                    Some(slice_meta) => slice_meta,
+                    None => return self.recover_dropped_slices(vec![segment]).await.map(|_| None),
+                };
+                if let ReaderError::SegmentSealed { .. } = e {
+                    if self.is_stale_end_of_segment(&slice_meta).await {
+                        debug!("Ignore the stale end of segment {:?}", segment);
+                        self.meta.add_slices(slice_meta)?;
+                        return Ok(None);
+                    }
+                }
+
+                info!("Segment slice {:?} has received error {:?}", slice_meta, e);
+                self.fetch_successors(e, &slice_meta).await?;
+
+                debug!("segment Slice meta {:?}", self.meta.slices);
+                Ok(None)
             }
-        } else {
-            info!("All Segment slices have completed reading from the stream, fetch it from the state synchronizer.");
-            None
         }
     }
 
     //
     // Fetch successors of the segment where an error was observed.
-    // ensure we stop the read task and spawn read tasks for the successor segments.
-    // (This logic will be moved to the TableSynchronizer in the subsequent PR).
-    async fn fetch_successors(&mut self, e: ReaderError) {
+    // ensure we stop the read task, mark the segment as completed in the reader group state
+    // and acquire the successors which are ready to be read. The successors of a segment which is
+    // bounded by an ending stream cut are beyond the cut, so they are not read.
+    // If the successors can not be fetched or the segment can not be read, the segment is released
+    // to be read again.
+    async fn fetch_successors(
+        &mut self,
+        e: ReaderError,
+        slice_meta: &SliceMetadata,
+    ) -> Result<(), EventReaderError> {
         match e {
             ReaderError::SegmentSealed {
                 segment,
@@ -419,32 +1178,90 @@ impl EventReader {
                 error_msg: _,
             } => {
                 self.meta.stop_reading(&segment); // stop reading segment.
-                                                  // Fetch next segments that can be read from.
-                let successors = self.get_successors(&segment).await;
-                let s = self.meta.next_segments_to_read(segment, successors);
-                debug!("Segments which can be read next are {:?}", s);
-                {
-                    for seg in s {
-                        let meta = SliceMetadata {
-                            scoped_segment: seg.to_string(),
-                            ..Default::default()
-                        };
-                        let (tx_drop_fetch, rx_drop_fetch) = oneshot::channel();
-                        tokio::spawn(SegmentSlice::get_segment_data(
-                            seg.clone(),
-                            meta.start_offset,
-                            self.tx.clone(),
-                            rx_drop_fetch,
-                            self.factory.clone(),
-                        ));
-                        self.meta.add_stop_reading_tx(seg.to_string(), tx_drop_fetch);
-                        // update map with newer segments.
-                        self.meta.add_slices(meta);
+                self.meta.read_offsets.remove(&segment);
+                let completed = match self.meta.segment_ranges.remove(&segment) {
+                    Some(completed) => completed,
+                    None => {
+                        debug!("Completed segment {:?} is no longer owned by the reader", segment);
+                        return Ok(());
+                    }
+                };
+                // the segment may have been taken away from the reader while it was read, e.g. by a
+                // reset of the reader group, the segments the reader owns are refreshed instead.
+                if !self.is_segment_assigned(&completed.scoped_segment).await? {
+                    debug!(
+                        "Completed segment {:?} is no longer assigned to the reader",
+                        segment
+                    );
+                    return self.rebalance_segments().await;
+                }
+                let successors = if slice_meta.end_offset == i64::MAX {
+                    match self.get_successors(&segment).await {
+                        Ok(successors) => successors
+                            .into_iter()
+                            .collect::<HashMap<SegmentWithRange, Vec<Segment>>>(),
+                        Err(err) => {
+                            self.release_unread_segment(&completed, slice_meta.get_consumed_offset())
+                                .await?;
+                            return ReaderSuccessorsError { segment, err }.fail();
+                        }
+                    }
+                } else {
+                    HashMap::new()
+                };
+                debug!("Successors of the completed segment are {:?}", successors);
+                let res = self
+                    .rg_state
+                    .lock()
+                    .await
+                    .segment_completed(&self.id, &completed, &successors)
+                    .await;
+                match res {
+                    Err(ReaderGroupStateError::SegmentNotOwned { .. }) => {
+                        // the segment has been taken away from the reader in the meantime, the
+                        // segments it owns are refreshed below.
+                        debug!("Completed segment {:?} is no longer owned by the reader", segment);
                     }
+                    res => res.context(ReaderStateError {
+                        operation: "mark segment as completed",
+                    })?,
                 }
+                // acquire the successors which are ready to be read.
+                self.rebalance_segments().await
             }
-            _ => error!("Error observed while reading from Pravega {:?}", e),
-        };
+            _ => {
+                error!("Error observed while reading from Pravega {:?}", e);
+                // the read task stops on errors that can not be retried.
+                let segment = e.get_segment();
+                self.meta.stop_reading(&segment);
+                self.meta.read_offsets.remove(&segment);
+                if let Some(segment_with_range) = self.meta.segment_ranges.remove(&segment) {
+                    self.release_unread_segment(&segment_with_range, slice_meta.get_consumed_offset())
+                        .await?;
+                }
+                Err(EventReaderError::ReaderSegmentError { source: e })
+            }
+        }
+    }
+
+    //
+    // Check against the reader group state whether the segment is still assigned to this reader.
+    //
+    async fn is_segment_assigned(&mut self, segment: &ScopedSegment) -> Result<bool, EventReaderError> {
+        let mut state = self.rg_state.lock().await;
+        let online_readers = state.get_online_readers().await.context(ReaderStateError {
+            operation: "get online readers",
+        })?;
+        if !online_readers.contains(&self.id) {
+            return Ok(false);
+        }
+        let assigned = state
+            .get_segments_for_reader(&self.id)
+            .await
+            .context(ReaderSyncError {
+                operation: "get segments for reader",
+            })?;
+        Ok(assigned.contains(segment))
     }
 
     // Helper method to append data to SliceMetadata.
@@ -460,33 +1277,90 @@ impl EventReader {
     async fn get_successors(
         &mut self,
         completed_scoped_segment: &str,
-    ) -> ImHashMap<SegmentWithRange, Vec<Segment>> {
+    ) -> Result<ImHashMap<SegmentWithRange, Vec<Segment>>, RetryError<ControllerError>> {
         let completed_scoped_segment = ScopedSegment::from(completed_scoped_segment);
         self.factory
             .get_controller_client()
             .get_successors(&completed_scoped_segment)
             .await
-            .expect("Failed to fetch successors of the segment")
-            .segment_with_predecessors
+            .map(|successors| successors.segment_with_predecessors)
+    }
+}
+
+///
+/// TypedEventReader reads events of type T, the events read by the wrapped EventReader are
+/// converted using the given serializer.
+///
+#[derive(new)]
+pub struct TypedEventReader<T> {
+    reader: EventReader,
+    serializer: Arc<dyn Serializer<T>>,
+}
+
+impl<T> TypedEventReader<T> {
+    ///
+    /// Returns a TypedSegmentSlice whose events are deserialized to type T,
+    /// see `EventReader::acquire_segment`.
+    ///
+    pub async fn acquire_segment(&mut self) -> Result<Option<TypedSegmentSlice<T>>, EventReaderError> {
+        let slice = self.reader.acquire_segment().await?;
+        Ok(slice.map(|slice| TypedSegmentSlice::new(slice, self.serializer.clone())))
+    }
+
+    ///
+    /// Release a partially read typed segment slice back to event reader.
+    ///
+    pub fn release_segment_at(&mut self, slice: TypedSegmentSlice<T>) {
+        self.reader.release_segment_at(slice.into_slice())
+    }
+
+    ///
+    /// Returns the underlying EventReader.
+    ///
+    pub fn get_reader(&mut self) -> &mut EventReader {
+        &mut self.reader
+    }
+
+    ///
+    /// Put the reader offline, see `EventReader::reader_offline`.
+    ///
+    pub async fn reader_offline(self) -> Result<(), EventReaderError> {
+        self.reader.reader_offline().await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::client_factory::ClientFactory;
+    use crate::error::EventReaderError;
     use crate::event_reader::{EventReader, SegmentReadResult};
+    use crate::reader_group::reader_group_config::{ReaderGroupConfigV1, ReaderGroupConfigVersioned};
+    use crate::reader_group::reader_group_state::ReaderGroupState;
+    use crate::reader_group::ReaderGroup;
     use crate::segment_slice::{SegmentDataBuffer, SegmentSlice, SliceMetadata};
+    use crate::stream::stream_cut::{StreamCutV1, StreamCutVersioned};
+    use crate::stream::watermark::tests::{encode, watermark};
+    use crate::{create_mock_factory, create_stream};
     use bytes::{BufMut, BytesMut};
-    use pravega_rust_client_config::{ClientConfigBuilder, MOCK_CONTROLLER_URI};
-    use pravega_rust_client_shared::{Scope, ScopedSegment, ScopedStream, Stream};
+    use lazy_static::*;
+    use pravega_rust_client_shared::{
+        Reader, Retention, RetentionType, ScaleType, Scaling, Scope, ScopedSegment, ScopedStream, Segment,
+        Stream, StreamConfiguration,
+    };
     use pravega_wire_protocol::commands::{Command, EventCommand};
     use std::collections::HashMap;
     use std::iter;
+    use std::sync::Arc;
     use tokio::sync::mpsc;
     use tokio::sync::mpsc::Sender;
+    use tokio::sync::Mutex;
     use tokio::time::{delay_for, Duration};
     use tracing::Level;
 
+    lazy_static! {
+        static ref READER: Reader = Reader::from("reader".to_owned());
+    }
+
     /*
      This test verifies EventReader reads from a stream where only one segment has data while the other segment is empty.
     */
@@ -495,13 +1369,9 @@ mod tests {
         const NUM_EVENTS: usize = 100;
         let (tx, rx) = mpsc::channel(1);
         tracing_subscriber::fmt().with_max_level(Level::TRACE).finish();
-        let cf = ClientFactory::new(
-            ClientConfigBuilder::default()
-                .controller_uri(MOCK_CONTROLLER_URI)
-                .build()
-                .unwrap(),
-        );
+        let cf = create_mock_factory();
         let stream = get_scoped_stream("scope", "test");
+        let rg_state = create_reader_group_state(stream, &cf);
 
         // simulate data being received from Segment store.
         cf.get_runtime_handle().enter(|| {
@@ -519,7 +1389,9 @@ mod tests {
 
         // create a new Event Reader with the segment slice data.
         let mut reader = EventReader::init_event_reader(
-            stream,
+            READER.clone(),
+            rg_state,
+            Duration::from_millis(3000),
             cf.clone(),
             tx.clone(),
             rx,
@@ -531,7 +1403,11 @@ mod tests {
         let mut event_size = 0;
 
         // Attempt to acquire a segment.
-        while let Some(mut slice) = cf.get_runtime_handle().block_on(reader.acquire_segment()) {
+        while let Some(mut slice) = cf
+            .get_runtime_handle()
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+        {
             loop {
                 if let Some(event) = slice.next() {
                     println!("Read event {:?}", event);
@@ -562,13 +1438,9 @@ mod tests {
         const NUM_EVENTS: usize = 100;
         let (tx, rx) = mpsc::channel(1);
         tracing_subscriber::fmt().with_max_level(Level::TRACE).finish();
-        let cf = ClientFactory::new(
-            ClientConfigBuilder::default()
-                .controller_uri(MOCK_CONTROLLER_URI)
-                .build()
-                .unwrap(),
-        );
+        let cf = create_mock_factory();
         let stream = get_scoped_stream("scope", "test");
+        let rg_state = create_reader_group_state(stream, &cf);
 
         // simulate data being received from Segment store. 2 async tasks pumping in data.
         cf.get_runtime_handle().enter(|| {
@@ -596,7 +1468,9 @@ mod tests {
 
         // create a new Event Reader with the segment slice data.
         let mut reader = EventReader::init_event_reader(
-            stream,
+            READER.clone(),
+            rg_state,
+            Duration::from_millis(3000),
             cf.clone(),
             tx.clone(),
             rx,
@@ -608,7 +1482,11 @@ mod tests {
 
         let mut total_events_read = 0;
         // Attempt to acquire a segment.
-        while let Some(mut slice) = cf.get_runtime_handle().block_on(reader.acquire_segment()) {
+        while let Some(mut slice) = cf
+            .get_runtime_handle()
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+        {
             let segment = slice.meta.scoped_segment.clone();
             println!("Received Segment Slice {:?}", segment);
             let mut event_count = 0;
@@ -641,13 +1519,9 @@ mod tests {
         const NUM_EVENTS: usize = 2;
         let (tx, rx) = mpsc::channel(1);
         tracing_subscriber::fmt().with_max_level(Level::TRACE).finish();
-        let cf = ClientFactory::new(
-            ClientConfigBuilder::default()
-                .controller_uri(MOCK_CONTROLLER_URI)
-                .build()
-                .unwrap(),
-        );
+        let cf = create_mock_factory();
         let stream = get_scoped_stream("scope", "test");
+        let rg_state = create_reader_group_state(stream, &cf);
 
         // simulate data being received from Segment store.
         cf.get_runtime_handle().enter(|| {
@@ -665,7 +1539,9 @@ mod tests {
 
         // create a new Event Reader with the segment slice data.
         let mut reader = EventReader::init_event_reader(
-            stream,
+            READER.clone(),
+            rg_state,
+            Duration::from_millis(3000),
             cf.clone(),
             tx.clone(),
             rx,
@@ -677,6 +1553,7 @@ mod tests {
         let mut slice = cf
             .get_runtime_handle()
             .block_on(reader.acquire_segment())
+            .expect("acquire segment")
             .unwrap();
 
         // read an event.
@@ -692,6 +1569,7 @@ mod tests {
         let slice = cf
             .get_runtime_handle()
             .block_on(reader.acquire_segment())
+            .expect("acquire segment")
             .unwrap();
         //Do not read, simply return it back.
         reader.release_segment_at(slice);
@@ -700,6 +1578,7 @@ mod tests {
         let mut slice = cf
             .get_runtime_handle()
             .block_on(reader.acquire_segment())
+            .expect("acquire segment")
             .unwrap();
         // Verify a partial event being present. This implies
         let event = slice.next().unwrap();
@@ -708,6 +1587,466 @@ mod tests {
         assert_eq!(event.offset_in_segment, 8 + 1); // first event.
     }
 
+    #[test]
+    fn test_drop_slice() {
+        const NUM_EVENTS: usize = 2;
+        let (tx, rx) = mpsc::channel(1);
+        let cf = create_mock_factory();
+        let stream = get_scoped_stream("scope", "test");
+        let rg_state = create_reader_group_state(stream, &cf);
+
+        // simulate data being received from Segment store.
+        cf.get_runtime_handle().enter(|| {
+            tokio::spawn(generate_variable_size_events(
+                tx.clone(),
+                10,
+                NUM_EVENTS,
+                0,
+                false,
+            ));
+        });
+
+        let init_segments = vec![create_segment_slice(0), create_segment_slice(1)];
+        let mut reader = EventReader::init_event_reader(
+            READER.clone(),
+            rg_state,
+            Duration::from_millis(3000),
+            cf.clone(),
+            tx.clone(),
+            rx,
+            create_slice_map(init_segments),
+            HashMap::new(),
+        );
+
+        // acquire a segment and drop it without releasing it.
+        let slice = cf
+            .get_runtime_handle()
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+            .unwrap();
+        drop(slice);
+
+        // the reader reports the dropped slice instead of aborting.
+        let result = cf.get_runtime_handle().block_on(reader.acquire_segment());
+        match result {
+            Err(EventReaderError::ReaderSliceDropped { segments }) => {
+                assert_eq!(segments, vec!["scope/test/0.#epoch.0".to_string()])
+            }
+            _ => panic!("expected the dropped segment slice to be reported"),
+        }
+    }
+
+    #[test]
+    fn test_get_current_time_window() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        handle.block_on(create_stream(&cf, "scope", "watermark"));
+        handle.block_on(create_stream(&cf, "scope", "_MARKwatermark"));
+        let stream = ScopedStream::from("scope/watermark");
+
+        // two events of 18 bytes each including the header.
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        for _ in 0..2 {
+            handle.block_on(writer.write_event(vec![1; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        // the controller appends each watermark as an event to the mark stream.
+        let mut mark_writer = cf.create_event_stream_writer(ScopedStream::from("scope/_MARKwatermark"));
+        handle.block_on(mark_writer.write_event(encode(&watermark(10, 20, &[(0, 0.0, 1.0, 18)]))));
+        handle.block_on(mark_writer.write_event(encode(&watermark(20, 30, &[(0, 0.0, 1.0, 36)]))));
+        handle.block_on(mark_writer.flush()).expect("flush mark writer");
+
+        let reader_group = handle
+            .block_on(cf.create_reader_group(
+                Scope::from("scope".to_owned()),
+                "rg".to_owned(),
+                stream.clone(),
+            ))
+            .expect("create reader group");
+        let mut reader = handle
+            .block_on(reader_group.create_reader("reader".to_owned()))
+            .expect("create reader");
+
+        // the reader has not passed any watermark
+        let window = handle
+            .block_on(reader.get_current_time_window(&stream))
+            .expect("get time window");
+        assert_eq!(window.lower_time_bound, None);
+        assert_eq!(window.upper_time_bound, Some(20));
+
+        let expected = vec![(Some(10), Some(30)), (Some(20), None)];
+        for (lower, upper) in expected {
+            let mut slice = handle
+                .block_on(reader.acquire_segment())
+                .expect("acquire segment")
+                .expect("segment slice");
+            slice.next().expect("read event");
+            reader.release_segment_at(slice);
+            let window = handle
+                .block_on(reader.get_current_time_window(&stream))
+                .expect("get time window");
+            assert_eq!(window.lower_time_bound, lower);
+            assert_eq!(window.upper_time_bound, upper);
+        }
+    }
+
+    #[test]
+    fn test_compute_distance_to_tail() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        handle.block_on(create_stream(&cf, "scope", "distance"));
+        let stream = ScopedStream::from("scope/distance");
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        for _ in 0..2 {
+            handle.block_on(writer.write_event(vec![1; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let reader_group = handle
+            .block_on(cf.create_reader_group(Scope::from("scope".to_owned()), "rg".to_owned(), stream))
+            .expect("create reader group");
+        let mut reader = handle
+            .block_on(reader_group.create_reader("reader".to_owned()))
+            .expect("create reader");
+
+        // nothing has been read yet, each event is written with a header of 8 bytes.
+        assert_eq!(handle.block_on(reader.compute_distance_to_tail()), 2 * (8 + 10));
+        assert_eq!(reader.metadata_clients.len(), 1);
+        // the metadata client of the segment is reused on the next refresh.
+        assert_eq!(handle.block_on(reader.compute_distance_to_tail()), 2 * (8 + 10));
+        assert_eq!(reader.metadata_clients.len(), 1);
+
+        // the metadata client is dropped once the segment is no longer read.
+        reader.stop_reading("scope/distance/0.#epoch.0");
+        assert_eq!(handle.block_on(reader.compute_distance_to_tail()), 0);
+        assert!(reader.metadata_clients.is_empty());
+    }
+
+    #[test]
+    fn test_read_events_across_scaled_segments() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        handle.block_on(create_stream(&cf, "scope", "scale"));
+        let stream = ScopedStream::from("scope/scale");
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        for i in 0..3 {
+            handle.block_on(writer.write_event(vec![i; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let reader_group = handle
+            .block_on(cf.create_reader_group(
+                Scope::from("scope".to_owned()),
+                "rg".to_owned(),
+                stream.clone(),
+            ))
+            .expect("create reader group");
+        let mut reader = handle
+            .block_on(reader_group.create_reader("reader".to_owned()))
+            .expect("create reader");
+        let mut slice = handle
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+            .expect("segment slice");
+        let mut events = vec![slice.next().expect("read event").value];
+        reader.release_segment_at(slice);
+
+        // the stream is scaled while the reader is in the middle of the segment.
+        handle
+            .block_on(
+                cf.get_controller_client()
+                    .scale_stream(&stream, &[Segment::from(0)], &[(0.0, 1.0)]),
+            )
+            .expect("scale stream");
+        let metadata_client =
+            handle.block_on(cf.create_segment_metadata_client(ScopedSegment::from("scope/scale/0")));
+        handle
+            .block_on(metadata_client.seal_segment())
+            .expect("seal segment");
+        let mut writer = cf.create_event_stream_writer(stream);
+        for i in 3..6 {
+            handle.block_on(writer.write_event(vec![i; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        // the successor is sealed without successors so the reader reaches the end of the stream.
+        let metadata_client =
+            handle.block_on(cf.create_segment_metadata_client(ScopedSegment::from("scope/scale/1")));
+        handle
+            .block_on(metadata_client.seal_segment())
+            .expect("seal segment");
+
+        // the reader completes the sealed segment and acquires its successor, no event is lost
+        // or read twice.
+        while let Some(mut slice) = handle
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+        {
+            for event in &mut slice {
+                events.push(event.value);
+            }
+            reader.release_segment_at(slice);
+        }
+        let expected: Vec<Vec<u8>> = (0..6).map(|i| vec![i; 10]).collect();
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_reclaimed_reader_completes_segment() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        handle.block_on(create_stream(&cf, "scope", "reclaim"));
+        let stream = ScopedStream::from("scope/reclaim");
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        for i in 0..3 {
+            handle.block_on(writer.write_event(vec![i; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let reader_group = handle
+            .block_on(cf.create_reader_group(Scope::from("scope".to_owned()), "rg".to_owned(), stream))
+            .expect("create reader group");
+        let mut reader = handle
+            .block_on(reader_group.create_reader("reader".to_owned()))
+            .expect("create reader");
+        let mut slice = handle
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+            .expect("segment slice");
+        slice.next().expect("read event");
+        reader.release_segment_at(slice);
+
+        // the reader is too slow to send its heartbeats, the reader group reclaims its segments.
+        let rg_state = reader.rg_state.clone();
+        handle.block_on(async {
+            let mut state = rg_state.lock().await;
+            for _ in 0..2 {
+                state
+                    .remove_offline_readers(Duration::from_millis(0))
+                    .await
+                    .expect("remove offline readers");
+                delay_for(Duration::from_millis(1)).await;
+            }
+            assert!(!state.is_reader_online(&reader.id));
+        });
+        let metadata_client =
+            handle.block_on(cf.create_segment_metadata_client(ScopedSegment::from("scope/reclaim/0")));
+        handle
+            .block_on(metadata_client.seal_segment())
+            .expect("seal segment");
+
+        // the reader keeps reading the events it has buffered until it completes the segment.
+        let result = loop {
+            match handle.block_on(reader.acquire_segment()) {
+                Ok(Some(mut slice)) => {
+                    for _event in &mut slice {}
+                    reader.release_segment_at(slice);
+                }
+                result => break result,
+            }
+        };
+        match result {
+            Err(EventReaderError::ReaderNotOnline { reader: id }) => assert_eq!(id, READER.to_string()),
+            _ => panic!("expected the reader to be offline"),
+        }
+        // the segment is not completed by the reader, it is left for the other readers.
+        let segments = handle
+            .block_on(async { rg_state.lock().await.get_segments().await })
+            .expect("get segments");
+        assert_eq!(segments.len(), 1);
+    }
+
+    #[test]
+    fn test_rebalance_segments_after_reader_joins() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        let stream = ScopedStream::from("scope/rebalance");
+        handle.block_on(async {
+            let controller = cf.get_controller_client();
+            controller.create_scope(&stream.scope).await.unwrap();
+            controller
+                .create_stream(&StreamConfiguration {
+                    scoped_stream: stream.clone(),
+                    scaling: Scaling {
+                        scale_type: ScaleType::FixedNumSegments,
+                        target_rate: 0,
+                        scale_factor: 0,
+                        min_num_segments: 4,
+                    },
+                    retention: Retention {
+                        retention_type: RetentionType::None,
+                        retention_param: 0,
+                    },
+                })
+                .await
+                .unwrap();
+        });
+        // the writer sets up all the segments on the mock segment store.
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        let rx = handle.block_on(writer.write_event(vec![1; 8]));
+        handle.block_on(rx).expect("receive ack").expect("write event");
+
+        let reader_group = handle
+            .block_on(cf.create_reader_group(Scope::from("scope".to_owned()), "rg".to_owned(), stream))
+            .expect("create reader group");
+        let mut reader1 = handle
+            .block_on(reader_group.create_reader("reader1".to_owned()))
+            .expect("create reader");
+        assert_eq!(
+            reader1.meta.segment_ranges.len(),
+            4,
+            "the first reader holds every segment"
+        );
+        let mut reader2 = handle
+            .block_on(reader_group.create_reader("reader2".to_owned()))
+            .expect("create reader");
+        assert!(
+            reader2.meta.segment_ranges.is_empty(),
+            "no segment is left to acquire"
+        );
+
+        // the first reader gives back the segments beyond its share, the second reader acquires them.
+        handle
+            .block_on(reader1.rebalance_segments())
+            .expect("rebalance segments");
+        assert_eq!(reader1.meta.segment_ranges.len(), 2);
+        handle
+            .block_on(reader2.rebalance_segments())
+            .expect("rebalance segments");
+        assert_eq!(reader2.meta.segment_ranges.len(), 2);
+        assert!(reader1
+            .meta
+            .segment_ranges
+            .keys()
+            .all(|segment| !reader2.meta.segment_ranges.contains_key(segment)));
+    }
+
+    #[test]
+    fn test_read_up_to_ending_stream_cut() {
+        let cf = create_mock_factory();
+        let handle = cf.get_runtime_handle();
+        handle.block_on(create_stream(&cf, "scope", "bounded"));
+        let stream = ScopedStream::from("scope/bounded");
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        for i in 0..3 {
+            handle.block_on(writer.write_event(vec![i; 10]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        // the successor of the segment holds events past the cut.
+        handle
+            .block_on(
+                cf.get_controller_client()
+                    .scale_stream(&stream, &[Segment::from(0)], &[(0.0, 1.0)]),
+            )
+            .expect("scale stream");
+        let metadata_client =
+            handle.block_on(cf.create_segment_metadata_client(ScopedSegment::from("scope/bounded/0")));
+        handle
+            .block_on(metadata_client.seal_segment())
+            .expect("seal segment");
+        let mut writer = cf.create_event_stream_writer(stream.clone());
+        handle.block_on(writer.write_event(vec![3; 10]));
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        // the cut lies after the first two events of 18 bytes each including the header.
+        let mut positions = HashMap::new();
+        positions.insert(ScopedSegment::from("scope/bounded/0"), 36);
+        let cut = StreamCutVersioned::V1(StreamCutV1::new(stream.clone(), positions));
+        let config = ReaderGroupConfigVersioned::V1(
+            ReaderGroupConfigV1::new()
+                .stream(stream, None, Some(cut))
+                .group_refresh_time_millis(10),
+        );
+        let reader_group = handle
+            .block_on(ReaderGroup::create(
+                Scope::from("scope".to_owned()),
+                "rg".to_owned(),
+                config,
+                cf.clone(),
+            ))
+            .expect("create reader group");
+        let mut reader = handle
+            .block_on(reader_group.create_reader("reader".to_owned()))
+            .expect("create reader");
+
+        let mut events = vec![];
+        while let Some(mut slice) = handle
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment")
+        {
+            for event in &mut slice {
+                events.push(event.value);
+            }
+            reader.release_segment_at(slice);
+        }
+        assert_eq!(
+            events,
+            vec![vec![0; 10], vec![1; 10]],
+            "events past the cut are not read"
+        );
+        // the successor is not acquired and the end of the stream is reported again.
+        assert!(reader.meta.segment_ranges.is_empty());
+        let segments = handle
+            .block_on(async { reader.rg_state.lock().await.get_segments().await })
+            .expect("get segments");
+        assert!(segments.is_empty(), "the successor is past the cut");
+        let slice = handle
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment");
+        assert!(slice.is_none());
+    }
+
+    #[test]
+    fn test_reader_put_offline() {
+        let (tx, rx) = mpsc::channel(1);
+        let cf = create_mock_factory();
+        let stream = get_scoped_stream("scope", "test");
+        let rg_state = create_reader_group_state(stream, &cf);
+
+        let init_segments = vec![create_segment_slice(0), create_segment_slice(1)];
+        let mut reader = EventReader::init_event_reader(
+            READER.clone(),
+            rg_state.clone(),
+            Duration::from_millis(10),
+            cf.clone(),
+            tx,
+            rx,
+            create_slice_map(init_segments),
+            HashMap::new(),
+        );
+
+        // the reader group puts the reader offline, e.g. after missing its heartbeats.
+        cf.get_runtime_handle().block_on(async {
+            rg_state
+                .lock()
+                .await
+                .reader_offline(&READER, None)
+                .await
+                .expect("put reader offline");
+            delay_for(Duration::from_millis(10)).await;
+        });
+
+        // the reader finds out on its next refresh and drops the data it has buffered.
+        let result = cf.get_runtime_handle().block_on(reader.acquire_segment());
+        match result {
+            Err(EventReaderError::ReaderNotOnline { reader: id }) => assert_eq!(id, READER.to_string()),
+            _ => panic!("expected the reader to be offline"),
+        }
+        assert!(reader.meta.slices.is_empty(), "buffered slices should be dropped");
+        assert!(reader.meta.slice_stop_reading.is_empty());
+
+        // the reader rejoins the reader group on the next acquire.
+        let slice = cf
+            .get_runtime_handle()
+            .block_on(reader.acquire_segment())
+            .expect("acquire segment");
+        assert!(slice.is_none(), "the reader group has no segments to read");
+        let online_readers = cf
+            .get_runtime_handle()
+            .block_on(async { rg_state.lock().await.get_online_readers().await })
+            .expect("get online readers");
+        assert_eq!(online_readers, vec![READER.clone()]);
+    }
+
     fn read_n_events(slice: &mut SegmentSlice, events_to_read: usize) {
         let mut event_count = 0;
         loop {
@@ -737,6 +2076,19 @@ mod tests {
         map
     }
 
+    // Create a reader group state without any segment and add the test reader to it.
+    fn create_reader_group_state(stream: ScopedStream, cf: &ClientFactory) -> Arc<Mutex<ReaderGroupState>> {
+        let config = ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new());
+        let mut state = cf
+            .get_runtime_handle()
+            .block_on(ReaderGroupState::new(stream, cf, config, HashMap::new()))
+            .expect("create reader group state");
+        cf.get_runtime_handle()
+            .block_on(state.add_reader(&READER))
+            .expect("add reader");
+        Arc::new(Mutex::new(state))
+    }
+
     fn get_scoped_stream(scope: &str, stream: &str) -> ScopedStream {
         let stream: ScopedStream = ScopedStream {
             scope: Scope {
@@ -826,6 +2178,7 @@ mod tests {
                 partial_data_present: false,
             },
             slice_return_tx: None,
+            checkpoint: None,
         };
         segment_slice
     }
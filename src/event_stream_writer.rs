@@ -9,46 +9,69 @@
 //
 
 use crate::reactor::reactors::Reactor;
+use pravega_rust_client_channel::{create_channel, ChannelSender};
 use pravega_rust_client_shared::*;
-use tokio::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+use tokio::runtime::Handle;
 use tokio::sync::oneshot;
 
 use crate::client_factory::ClientFactory;
 use crate::error::*;
 use crate::get_random_u128;
-use crate::reactor::event::{Incoming, PendingEvent};
-use tracing::info_span;
+use crate::reactor::event::{Incoming, NoteTime, PendingEvent};
+use crate::serializer::Serializer;
+use tracing::{debug, info_span};
 use tracing_futures::Instrument;
 
 /// EventStreamWriter contains a writer id and a mpsc sender which is used to send Event
 /// to the StreamWriter
 pub struct EventStreamWriter {
     writer_id: WriterId,
-    sender: Sender<Incoming>,
+    sender: ChannelSender<Incoming>,
+    handle: Handle,
+    closed: bool,
+    large_events: bool,
 }
 
 impl EventStreamWriter {
     pub const MAX_EVENT_SIZE: usize = 8 * 1024 * 1024;
-    const CHANNEL_CAPACITY: usize = 100;
+    /// The maximum size of an event if large events are enabled in the client config.
+    pub const MAX_LARGE_EVENT_SIZE: usize = 1024 * 1024 * 1024;
 
     pub(crate) fn new(stream: ScopedStream, factory: ClientFactory) -> Self {
-        let (tx, rx) = channel(EventStreamWriter::CHANNEL_CAPACITY);
+        EventStreamWriter::with_writer_id(stream, WriterId::from(get_random_u128()), factory)
+    }
+
+    pub(crate) fn with_writer_id(stream: ScopedStream, writer_id: WriterId, factory: ClientFactory) -> Self {
+        // the channel capacity bounds the size of the events that are not acknowledged yet.
+        let (tx, rx) = create_channel(factory.get_config().max_outstanding_bytes());
         let handle = factory.get_runtime_handle();
-        let writer_id = WriterId::from(get_random_u128());
+        let large_events = factory.get_config().enable_large_events();
         let span = info_span!("StreamReactor", event_stream_writer = %writer_id);
         // tokio::spawn is tied to the factory runtime.
-        handle.enter(|| tokio::spawn(Reactor::run(stream, tx.clone(), rx, factory.clone()).instrument(span)));
+        handle.enter(|| {
+            tokio::spawn(Reactor::run(stream, writer_id, tx.clone(), rx, factory.clone()).instrument(span))
+        });
         EventStreamWriter {
             writer_id,
             sender: tx,
+            handle,
+            closed: false,
+            large_events,
         }
     }
 
+    /// The id this writer uses to append to the segments of the stream.
+    pub fn writer_id(&self) -> WriterId {
+        self.writer_id
+    }
+
     pub async fn write_event(&mut self, event: Vec<u8>) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
         let (tx, rx) = oneshot::channel();
-        if let Some(pending_event) = PendingEvent::with_header(None, event, tx) {
+        if let Some(pending_event) = self.create_pending_event(None, event, tx) {
+            let size = pending_event.data.len();
             let append_event = Incoming::AppendEvent(pending_event);
-            self.writer_event_internal(append_event, rx).await
+            self.writer_event_internal(append_event, size, rx).await
         } else {
             rx
         }
@@ -60,20 +83,117 @@ impl EventStreamWriter {
         event: Vec<u8>,
     ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
         let (tx, rx) = oneshot::channel();
-        if let Some(pending_event) = PendingEvent::with_header(Some(routing_key), event, tx) {
+        if let Some(pending_event) = self.create_pending_event(Some(routing_key), event, tx) {
+            let size = pending_event.data.len();
             let append_event = Incoming::AppendEvent(pending_event);
-            self.writer_event_internal(append_event, rx).await
+            self.writer_event_internal(append_event, size, rx).await
+        } else {
+            rx
+        }
+    }
+
+    /// Writes an event numbered by the caller. The event numbers written by a writer must be
+    /// increasing, an event whose number is not greater than the last event number written to its
+    /// segment is failed. A producer that restarts with the same writer id, see
+    /// `ClientFactory::create_event_stream_writer_with_id`, and writes its events with the same
+    /// numbers and routing keys again has the events already persisted before the restart
+    /// acknowledged without being written twice.
+    pub async fn write_event_with_number(
+        &mut self,
+        event: Vec<u8>,
+        event_number: i64,
+    ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
+        let (tx, rx) = oneshot::channel();
+        if let Some(pending_event) = self.create_pending_event(None, event, tx) {
+            let size = pending_event.data.len();
+            let append_event = Incoming::AppendEvent(pending_event.with_event_number(event_number));
+            self.writer_event_internal(append_event, size, rx).await
         } else {
             rx
         }
     }
 
+    /// Writes an event numbered by the caller using the routing key, see
+    /// `EventStreamWriter::write_event_with_number`.
+    pub async fn write_event_by_routing_key_with_number(
+        &mut self,
+        routing_key: String,
+        event: Vec<u8>,
+        event_number: i64,
+    ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
+        let (tx, rx) = oneshot::channel();
+        if let Some(pending_event) = self.create_pending_event(Some(routing_key), event, tx) {
+            let size = pending_event.data.len();
+            let append_event = Incoming::AppendEvent(pending_event.with_event_number(event_number));
+            self.writer_event_internal(append_event, size, rx).await
+        } else {
+            rx
+        }
+    }
+
+    // Events larger than a single append are written in chunks if large events are enabled.
+    fn create_pending_event(
+        &self,
+        routing_key: Option<String>,
+        event: Vec<u8>,
+        tx: oneshot::Sender<Result<(), SegmentWriterError>>,
+    ) -> Option<PendingEvent> {
+        if self.large_events {
+            PendingEvent::large_with_header(routing_key, event, tx)
+        } else {
+            PendingEvent::with_header(routing_key, event, tx)
+        }
+    }
+
+    /// Waits until all the events written by this writer so far are acknowledged by the
+    /// segment store. The error of the first event this writer failed to write is returned.
+    pub async fn flush(&mut self) -> Result<(), SegmentWriterError> {
+        let (tx, rx) = oneshot::channel();
+        self.wait_for_reactor(Incoming::Flush(tx), rx).await
+    }
+
+    /// Notes a time, usually the time of the events written so far, together with the position
+    /// of this writer in the stream, so that the controller can compute the watermarks of the
+    /// stream. The timestamps noted by a writer should be increasing. The position only covers
+    /// the events acknowledged so far, call flush before to include all the written events.
+    pub async fn note_time(&mut self, timestamp: u64) -> Result<(), SegmentWriterError> {
+        let (tx, rx) = oneshot::channel();
+        self.wait_for_reactor(Incoming::NoteTime(NoteTime::new(timestamp, tx)), rx)
+            .await
+    }
+
+    /// Waits until all the events written by this writer are acknowledged by the segment store
+    /// and shuts down the writer. The error of the first event this writer failed to write, or the
+    /// error which stopped the writer, is returned if the events could not be written.
+    pub async fn close(mut self) -> Result<(), SegmentWriterError> {
+        let (tx, rx) = oneshot::channel();
+        let result = self.wait_for_reactor(Incoming::Close(tx), rx).await;
+        self.closed = true;
+        result
+    }
+
+    async fn wait_for_reactor(
+        &mut self,
+        incoming: Incoming,
+        rx: oneshot::Receiver<Result<(), SegmentWriterError>>,
+    ) -> Result<(), SegmentWriterError> {
+        if self.sender.send((incoming, 0)).await.is_err() {
+            return Err(SegmentWriterError::SendToProcessor {});
+        }
+        rx.await.unwrap_or_else(|_| {
+            Err(SegmentWriterError::ReactorClosed {
+                msg: "reactor exited before replying".to_owned(),
+            })
+        })
+    }
+
     async fn writer_event_internal(
         &mut self,
         append_event: Incoming,
+        size: usize,
         rx: oneshot::Receiver<Result<(), SegmentWriterError>>,
     ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
-        if let Err(_e) = self.sender.send(append_event).await {
+        if let Err(_e) = self.sender.send((append_event, size)).await {
             let (tx_error, rx_error) = oneshot::channel();
             tx_error
                 .send(Err(SegmentWriterError::SendToProcessor {}))
@@ -85,12 +205,79 @@ impl EventStreamWriter {
     }
 }
 
+/// TypedEventStreamWriter writes events of type T to a stream, the events are converted to bytes
+/// using the given serializer. The serializer can be shared with the readers of the stream.
+#[derive(new)]
+pub struct TypedEventStreamWriter<T> {
+    writer: EventStreamWriter,
+    serializer: Arc<dyn Serializer<T>>,
+}
+
+impl<T> TypedEventStreamWriter<T> {
+    pub async fn write_event(
+        &mut self,
+        event: &T,
+    ) -> Result<oneshot::Receiver<Result<(), SegmentWriterError>>, SerdeError> {
+        let data = self.serializer.serialize(event)?;
+        Ok(self.writer.write_event(data).await)
+    }
+
+    pub async fn write_event_by_routing_key(
+        &mut self,
+        routing_key: String,
+        event: &T,
+    ) -> Result<oneshot::Receiver<Result<(), SegmentWriterError>>, SerdeError> {
+        let data = self.serializer.serialize(event)?;
+        Ok(self.writer.write_event_by_routing_key(routing_key, data).await)
+    }
+
+    /// Waits until all the events written by this writer so far are acknowledged by the
+    /// segment store. The error of the first event this writer failed to write is returned.
+    pub async fn flush(&mut self) -> Result<(), SegmentWriterError> {
+        self.writer.flush().await
+    }
+
+    /// Notes a time together with the position of this writer in the stream, see
+    /// EventStreamWriter::note_time.
+    pub async fn note_time(&mut self, timestamp: u64) -> Result<(), SegmentWriterError> {
+        self.writer.note_time(timestamp).await
+    }
+
+    /// Waits until all the events written by this writer are acknowledged by the segment store
+    /// and shuts down the writer.
+    pub async fn close(self) -> Result<(), SegmentWriterError> {
+        self.writer.close().await
+    }
+}
+
+impl Drop for EventStreamWriter {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        // shut down the reactor once the events written so far are acknowledged.
+        let sender = self.sender.clone();
+        let (tx, _rx) = oneshot::channel();
+        self.handle.enter(|| {
+            tokio::spawn(async move {
+                if sender.send((Incoming::Close(tx), 0)).await.is_err() {
+                    debug!("reactor is already closed");
+                }
+            })
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tokio::runtime::Runtime;
 
     use super::*;
     use crate::reactor::event::PendingEvent;
+    use crate::serializer::CborSerializer;
+    use crate::{create_mock_factory, create_stream, mock_config_builder, read_segment};
+    use pravega_controller_client::mock_controller::MockController;
+    use pravega_wire_protocol::commands::{Command, EventCommand};
 
     #[test]
     fn test_pending_event() {
@@ -114,4 +301,220 @@ mod tests {
         let reply = rt.block_on(rx).expect("get reply");
         assert!(reply.is_err());
     }
+
+    #[test]
+    fn test_flush_and_close() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "stream"));
+        let mut writer = factory.create_event_stream_writer(ScopedStream::from("scope/stream"));
+
+        let mut acks = vec![];
+        for _ in 0..10 {
+            acks.push(handle.block_on(writer.write_event(vec![1; 100])));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        // all the events written before the flush are acknowledged.
+        for mut ack in acks {
+            assert!(ack.try_recv().expect("event is acknowledged").is_ok());
+        }
+
+        let ack = handle.block_on(writer.write_event(vec![1; 100]));
+        handle.block_on(writer.close()).expect("close writer");
+        assert!(handle.block_on(ack).expect("event is acknowledged").is_ok());
+    }
+
+    #[test]
+    fn test_flush_and_close_report_failure() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "failure"));
+        let mut writer = factory.create_event_stream_writer(ScopedStream::from("scope/failure"));
+
+        for i in 1..=3 {
+            handle.block_on(writer.write_event_with_number(vec![1; 100], i));
+        }
+        // the event number does not increase so the event fails.
+        let failed = handle.block_on(writer.write_event_with_number(vec![1; 100], 2));
+        let ack = handle.block_on(writer.write_event_with_number(vec![1; 100], 4));
+        match handle.block_on(writer.flush()) {
+            Err(SegmentWriterError::EventWriteFailed { error_msg }) => assert!(
+                error_msg.contains("Event number 2"),
+                "flush should report the failed event: {}",
+                error_msg
+            ),
+            result => panic!("flush should fail, got {:?}", result),
+        }
+        assert!(handle.block_on(failed).expect("event is failed").is_err());
+        assert!(handle.block_on(ack).expect("event is acknowledged").is_ok());
+
+        // the failure is reported until the writer is closed.
+        assert!(handle.block_on(writer.close()).is_err());
+    }
+
+    #[test]
+    fn test_note_time() {
+        let config = mock_config_builder().build().unwrap();
+        let controller = MockController::new(config.controller_uri.clone());
+        let noted_times = controller.get_noted_times();
+        let factory = ClientFactory::with_controller(config, Box::new(controller));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "watermark"));
+        let stream = ScopedStream::from("scope/watermark");
+        let mut writer = factory.create_event_stream_writer(stream.clone());
+        let writer_id = writer.writer_id();
+
+        // the time can be noted before anything is written
+        handle.block_on(writer.note_time(10)).expect("note time");
+        let (time, position) = handle
+            .block_on(noted_times.read())
+            .get(&writer_id)
+            .cloned()
+            .expect("time is noted");
+        assert_eq!(time, Timestamp(10));
+        assert_eq!(position.scoped_stream, stream);
+        // the offset of a segment is unknown until an event written to it is acknowledged
+        assert_eq!(position.segment_offset_map.get(&0), Some(&-1));
+
+        for _ in 0..3 {
+            handle.block_on(writer.write_event(vec![1; 100]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        handle.block_on(writer.note_time(20)).expect("note time");
+        let (time, position) = handle
+            .block_on(noted_times.read())
+            .get(&writer_id)
+            .cloned()
+            .expect("time is noted");
+        assert_eq!(time, Timestamp(20));
+        // the position covers the acknowledged events, each written with a header of 8 bytes.
+        assert_eq!(position.segment_offset_map.get(&0), Some(&(3 * (8 + 100))));
+
+        // the writer is removed from the watermarking on close
+        handle.block_on(writer.close()).expect("close writer");
+        assert!(handle.block_on(noted_times.read()).get(&writer_id).is_none());
+    }
+
+    #[test]
+    fn test_writer_resumes_after_restart() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "idempotent"));
+        let stream = ScopedStream::from("scope/idempotent");
+        let writer_id = WriterId::from(get_random_u128());
+
+        let mut writer = factory.create_event_stream_writer_with_id(stream.clone(), writer_id);
+        assert_eq!(writer.writer_id(), writer_id);
+        for i in 1..=5 {
+            handle.block_on(writer.write_event_with_number(vec![i as u8; 100], i));
+        }
+        handle.block_on(writer.close()).expect("close writer");
+
+        // the restarted writer writes the same events again followed by new ones,
+        // the events persisted before the restart are not written twice.
+        let mut writer = factory.create_event_stream_writer_with_id(stream.clone(), writer_id);
+        let mut acks = vec![];
+        for i in 1..=8 {
+            acks.push(handle.block_on(writer.write_event_with_number(vec![i as u8; 100], i)));
+        }
+        // an event number that does not increase is rejected
+        let out_of_order = handle.block_on(writer.write_event_with_number(vec![0; 100], 7));
+        assert!(
+            handle.block_on(writer.close()).is_err(),
+            "close should report the rejected event"
+        );
+        for ack in acks {
+            assert!(handle.block_on(ack).expect("event is acknowledged").is_ok());
+        }
+        assert!(handle.block_on(out_of_order).expect("event is rejected").is_err());
+
+        // events without event numbers are written after the persisted ones instead of being dropped.
+        let mut writer = factory.create_event_stream_writer_with_id(stream, writer_id);
+        let mut acks = vec![];
+        for i in 9..=10 {
+            acks.push(handle.block_on(writer.write_event(vec![i; 100])));
+        }
+        handle.block_on(writer.close()).expect("close writer");
+        for ack in acks {
+            assert!(handle.block_on(ack).expect("event is acknowledged").is_ok());
+        }
+
+        let data = handle.block_on(read_segment(&factory, &ScopedSegment::from("scope/idempotent/0")));
+        let mut expected = vec![];
+        for i in 1..=10 {
+            let event = EventCommand { data: vec![i; 100] };
+            expected.extend(event.write_fields().expect("encode event"));
+        }
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_typed_writer() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "typed"));
+        let mut writer = factory.create_typed_event_stream_writer(
+            ScopedStream::from("scope/typed"),
+            Arc::new(CborSerializer::<(u64, String)>::new()),
+        );
+
+        let ack = handle
+            .block_on(writer.write_event(&(1, "event".to_owned())))
+            .expect("serialize event");
+        handle.block_on(writer.close()).expect("close writer");
+        assert!(handle.block_on(ack).expect("event is acknowledged").is_ok());
+    }
+
+    #[test]
+    fn test_write_large_event() {
+        let config = mock_config_builder().enable_large_events(true).build().unwrap();
+        let factory = ClientFactory::new(config);
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "large"));
+        let mut writer = factory.create_event_stream_writer(ScopedStream::from("scope/large"));
+
+        // the large event is ordered between the events written before and after it
+        let events = vec![vec![1; 100], vec![2; 20 * 1024 * 1024], vec![3; 100]];
+        let mut acks = vec![];
+        for event in &events {
+            acks.push(handle.block_on(writer.write_event(event.clone())));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+        for mut ack in acks {
+            assert!(ack.try_recv().expect("event is acknowledged").is_ok());
+        }
+
+        let metadata_client =
+            handle.block_on(factory.create_segment_metadata_client(ScopedSegment::from("scope/large/0")));
+        let length = handle
+            .block_on(metadata_client.fetch_current_segment_length())
+            .expect("get segment length");
+        assert_eq!(length, 108 + 20 * 1024 * 1024 + 8 + 108);
+
+        // the large event is read back once and in order.
+        let data = handle.block_on(read_segment(&factory, &ScopedSegment::from("scope/large/0")));
+        let mut expected = vec![];
+        for event in events {
+            expected.extend_from_slice(&EventCommand::TYPE_CODE.to_be_bytes());
+            expected.extend_from_slice(&(event.len() as i32).to_be_bytes());
+            expected.extend(event);
+        }
+        assert!(
+            data == expected,
+            "the events read back differ from the events written"
+        );
+    }
+
+    #[test]
+    fn test_large_event_disabled() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "nolarge"));
+        let mut writer = factory.create_event_stream_writer(ScopedStream::from("scope/nolarge"));
+
+        let ack = handle.block_on(writer.write_event(vec![1; 20 * 1024 * 1024]));
+        let result = handle.block_on(ack).expect("event is rejected");
+        // the event does not fit in a single append
+        assert!(result.is_err());
+    }
 }
@@ -37,6 +37,7 @@ use std::sync::atomic::{AtomicI64, Ordering};
 
 pub mod byte_stream;
 pub mod client_factory;
+pub mod conditional_segment_writer;
 pub mod error;
 pub mod event_reader;
 pub mod event_stream_writer;
@@ -48,7 +49,8 @@ pub mod reader_group;
 pub mod segment_metadata;
 pub mod segment_reader;
 pub mod segment_slice;
-mod stream;
+pub mod serializer;
+pub mod stream;
 pub mod table_synchronizer;
 pub mod tablemap;
 pub mod trace;
@@ -119,3 +121,50 @@ async fn create_stream(factory: &ClientFactory, scope: &str, stream: &str) {
         .await
         .unwrap();
 }
+
+// helper method that returns a builder of the config of a client which uses the mock controller
+// and the happy mock segment store.
+#[cfg(test)]
+fn mock_config_builder() -> pravega_rust_client_config::ClientConfigBuilder {
+    use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
+    let mut builder = pravega_rust_client_config::ClientConfigBuilder::default();
+    builder
+        .connection_type(ConnectionType::Mock(MockType::Happy))
+        .mock(true)
+        .controller_uri(pravega_rust_client_shared::PravegaNodeUri::from("127.0.0.2:9091"));
+    builder
+}
+
+// helper method that creates a client factory which uses the mock controller and the happy mock
+// segment store.
+#[cfg(test)]
+fn create_mock_factory() -> ClientFactory {
+    ClientFactory::new(mock_config_builder().build().expect("build client config"))
+}
+
+// helper method that reads all the data of a segment.
+#[cfg(test)]
+async fn read_segment(
+    factory: &ClientFactory,
+    segment: &pravega_rust_client_shared::ScopedSegment,
+) -> Vec<u8> {
+    use crate::segment_reader::AsyncSegmentReader;
+    let length = factory
+        .create_segment_metadata_client(segment.clone())
+        .await
+        .fetch_current_segment_length()
+        .await
+        .expect("fetch segment length");
+    let reader = factory.create_async_event_reader(segment.clone()).await;
+    let mut data = vec![];
+    while (data.len() as i64) < length {
+        // a single read reply is limited in size.
+        let read_length = std::cmp::min(length - data.len() as i64, 1024 * 1024);
+        let reply = reader
+            .read(data.len() as i64, read_length as i32)
+            .await
+            .expect("read segment");
+        data.extend(reply.data);
+    }
+    data
+}
@@ -10,8 +10,9 @@
 use tokio::sync::oneshot;
 use tracing::warn;
 
+use pravega_rust_client_channel::CapacityGuard;
 use pravega_rust_client_shared::*;
-use pravega_wire_protocol::commands::{Command, EventCommand};
+use pravega_wire_protocol::commands::{Command, EventCommand, TYPE_PLUS_LENGTH_SIZE};
 use pravega_wire_protocol::wire_commands::Replies;
 
 use crate::error::*;
@@ -21,6 +22,11 @@ pub(crate) enum Incoming {
     AppendEvent(PendingEvent),
     ServerReply(ServerReply),
     ConnectionFailure(ConnectionFailure),
+    LingerExpired(LingerExpired),
+    LargeEventWritten(LargeEventWritten),
+    NoteTime(NoteTime),
+    Flush(oneshot::Sender<Result<(), SegmentWriterError>>),
+    Close(oneshot::Sender<Result<(), SegmentWriterError>>),
 }
 
 #[derive(new, Debug)]
@@ -34,29 +40,58 @@ pub(crate) struct ConnectionFailure {
     pub(crate) segment: ScopedSegment,
 }
 
+#[derive(new, Debug)]
+pub(crate) struct LingerExpired {
+    pub(crate) segment: ScopedSegment,
+}
+
+#[derive(new, Debug)]
+pub(crate) struct LargeEventWritten {
+    pub(crate) segment: ScopedSegment,
+    pub(crate) event_id: i64,
+    /// The length of the segment after the event or the error which stopped the write.
+    pub(crate) result: Result<i64, SegmentWriterError>,
+}
+
+#[derive(new, Debug)]
+pub(crate) struct NoteTime {
+    pub(crate) timestamp: u64,
+    pub(crate) oneshot_sender: oneshot::Sender<Result<(), SegmentWriterError>>,
+}
+
 #[derive(Debug)]
 pub(crate) struct PendingEvent {
     pub(crate) routing_key: Option<String>,
     pub(crate) data: Vec<u8>,
     pub(crate) oneshot_sender: oneshot::Sender<Result<(), SegmentWriterError>>,
+    /// The event number assigned by the caller, None if the segment writer numbers the event.
+    pub(crate) event_number: Option<i64>,
+    /// Keeps the writer capacity taken by this event until the event is acknowledged or failed.
+    pub(crate) capacity_guard: Option<CapacityGuard>,
 }
 
 impl PendingEvent {
     pub(crate) const MAX_WRITE_SIZE: usize = 8 * 1024 * 1024 + 8;
+    pub(crate) const MAX_LARGE_WRITE_SIZE: usize = 1024 * 1024 * 1024 + 8;
     pub(crate) fn new(
         routing_key: Option<String>,
         data: Vec<u8>,
         oneshot_sender: oneshot::Sender<Result<(), SegmentWriterError>>,
     ) -> Option<Self> {
-        if data.len() > PendingEvent::MAX_WRITE_SIZE {
-            warn!(
-                "event size {:?} exceeds limit {:?}",
-                data.len(),
-                PendingEvent::MAX_WRITE_SIZE
-            );
+        PendingEvent::with_limit(routing_key, data, PendingEvent::MAX_WRITE_SIZE, oneshot_sender)
+    }
+
+    fn with_limit(
+        routing_key: Option<String>,
+        data: Vec<u8>,
+        limit: usize,
+        oneshot_sender: oneshot::Sender<Result<(), SegmentWriterError>>,
+    ) -> Option<Self> {
+        if data.len() > limit {
+            warn!("event size {:?} exceeds limit {:?}", data.len(), limit);
             oneshot_sender
                 .send(Err(SegmentWriterError::EventSizeTooLarge {
-                    limit: PendingEvent::MAX_WRITE_SIZE,
+                    limit,
                     size: data.len(),
                 }))
                 .expect("send error to caller");
@@ -66,6 +101,8 @@ impl PendingEvent {
                 routing_key,
                 data,
                 oneshot_sender,
+                event_number: None,
+                capacity_guard: None,
             })
         }
     }
@@ -88,6 +125,26 @@ impl PendingEvent {
         }
     }
 
+    /// Creates an event with header that may exceed the maximum size of a single append, see
+    /// `PendingEvent::is_large`. The header is written by hand because the encoding of an
+    /// EventCommand is limited to the maximum size of a wire command.
+    pub(crate) fn large_with_header(
+        routing_key: Option<String>,
+        data: Vec<u8>,
+        oneshot_sender: oneshot::Sender<Result<(), SegmentWriterError>>,
+    ) -> Option<PendingEvent> {
+        let mut event = Vec::with_capacity(TYPE_PLUS_LENGTH_SIZE as usize + data.len());
+        event.extend_from_slice(&EventCommand::TYPE_CODE.to_be_bytes());
+        event.extend_from_slice(&(data.len() as i32).to_be_bytes());
+        event.extend(data);
+        PendingEvent::with_limit(
+            routing_key,
+            event,
+            PendingEvent::MAX_LARGE_WRITE_SIZE,
+            oneshot_sender,
+        )
+    }
+
     pub(crate) fn without_header(
         routing_key: Option<String>,
         data: Vec<u8>,
@@ -96,7 +153,18 @@ impl PendingEvent {
         PendingEvent::new(routing_key, data, oneshot_sender)
     }
 
+    /// Sets the event number assigned by the caller, see `EventStreamWriter::write_event_with_number`.
+    pub(crate) fn with_event_number(mut self, event_number: i64) -> Self {
+        self.event_number = Some(event_number);
+        self
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns true if the event does not fit in a single append and has to be written in chunks.
+    pub(crate) fn is_large(&self) -> bool {
+        self.data.len() > PendingEvent::MAX_WRITE_SIZE
+    }
 }
@@ -0,0 +1,263 @@
+//
+// Copyright (c) Dell Inc., or its subsidiaries. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use snafu::ResultExt;
+use tracing::{debug, warn};
+
+use pravega_rust_client_auth::DelegationTokenProvider;
+use pravega_rust_client_retry::retry_async::retry_async;
+use pravega_rust_client_retry::retry_result::RetryResult;
+use pravega_rust_client_shared::naming_utils::NameUtils;
+use pravega_rust_client_shared::*;
+use pravega_wire_protocol::commands::{
+    AppendBlockEndCommand, CreateSegmentCommand, DeleteSegmentCommand, GetStreamSegmentInfoCommand,
+    MergeSegmentsCommand, SetupAppendCommand,
+};
+use pravega_wire_protocol::wire_commands::{Replies, Requests};
+
+use crate::client_factory::ClientFactory;
+use crate::error::*;
+use crate::raw_client::{RawClient, RawClientImpl};
+use crate::reactor::event::PendingEvent;
+use crate::{get_random_u128, get_request_id};
+
+/// Writes an event that is larger than the maximum size of a single append. The event is written
+/// in chunks to a transient segment which is then merged into the segment of the event, so the
+/// event shows up in the segment atomically.
+pub(crate) struct LargeEventWriter {
+    segment: ScopedSegment,
+    factory: ClientFactory,
+    delegation_token_provider: Arc<DelegationTokenProvider>,
+}
+
+impl LargeEventWriter {
+    pub(crate) fn new(
+        segment: ScopedSegment,
+        factory: ClientFactory,
+        delegation_token_provider: Arc<DelegationTokenProvider>,
+    ) -> Self {
+        LargeEventWriter {
+            segment,
+            factory,
+            delegation_token_provider,
+        }
+    }
+
+    pub(crate) fn segment(&self) -> &ScopedSegment {
+        &self.segment
+    }
+
+    /// Writes the event, including its header, and returns the length of the segment after it.
+    /// The events written to the segment before must be acknowledged, otherwise they would be
+    /// ordered after this event.
+    pub(crate) async fn write(&self, event: &[u8]) -> Result<i64, SegmentWriterError> {
+        let controller = self.factory.get_controller_client();
+        let endpoint = controller
+            .get_endpoint_for_segment(&self.segment)
+            .await
+            .map_err(|e| SegmentWriterError::RetryControllerWriting { err: e })?;
+        let raw_client = self.factory.create_raw_client_for_endpoint(endpoint);
+        let token = self.delegation_token_provider.retrieve_token(controller).await;
+        let transient = NameUtils::get_transient_name_from_id(&self.segment.to_string(), get_random_u128());
+
+        let request = Requests::CreateSegment(CreateSegmentCommand {
+            request_id: get_request_id(),
+            segment: transient.clone(),
+            target_rate: 0,
+            // no scaling policy
+            scale_type: 0,
+            delegation_token: token.clone(),
+        });
+        match self.send_request(&raw_client, &request).await? {
+            // a retried request finds the segment created by the first attempt
+            Replies::SegmentCreated(_) | Replies::SegmentAlreadyExists(_) => {}
+            reply => {
+                return Err(SegmentWriterError::WrongReply {
+                    expected: String::from("SegmentCreated"),
+                    actual: reply,
+                })
+            }
+        }
+
+        let result = match self.append_chunks(&raw_client, &transient, &token, event).await {
+            Ok(()) => self.merge(&raw_client, &transient, &token).await,
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            self.delete(&raw_client, &transient, &token).await;
+        }
+        result
+    }
+
+    async fn append_chunks(
+        &self,
+        raw_client: &RawClientImpl<'_>,
+        transient: &str,
+        token: &str,
+        event: &[u8],
+    ) -> Result<(), SegmentWriterError> {
+        let writer_id = get_random_u128();
+        let request = Requests::SetupAppend(SetupAppendCommand {
+            request_id: get_request_id(),
+            writer_id,
+            segment: transient.to_owned(),
+            delegation_token: token.to_owned(),
+        });
+        let result = retry_async(self.factory.get_config().retry_policy, || async {
+            match raw_client.send_setup_request(&request).await {
+                Ok((reply, connection)) => RetryResult::Success((reply, connection)),
+                Err(e) => {
+                    warn!("failed to setup append to transient segment due to {:?}", e);
+                    if e.is_token_expired() {
+                        self.delegation_token_provider.signal_token_expiry();
+                    }
+                    RetryResult::Retry(e)
+                }
+            }
+        })
+        .await;
+        let (reply, mut connection) = result.map_err(|e| SegmentWriterError::RetryRawClient { err: e })?;
+        if !matches!(reply, Replies::AppendSetup(_)) {
+            return Err(SegmentWriterError::WrongReply {
+                expected: String::from("AppendSetup"),
+                actual: reply,
+            });
+        }
+
+        for (i, chunk) in event.chunks(PendingEvent::MAX_WRITE_SIZE).enumerate() {
+            let request = Requests::AppendBlockEnd(AppendBlockEndCommand {
+                writer_id,
+                size_of_whole_events: chunk.len() as i32,
+                data: chunk.to_vec(),
+                num_event: 1,
+                last_event_number: i as i64 + 1,
+                request_id: get_request_id(),
+            });
+            connection.write(&request).await.context(SegmentWriting {})?;
+            match connection.read().await.context(SegmentWriting {})? {
+                Replies::DataAppended(_) => {}
+                reply => {
+                    return Err(SegmentWriterError::WrongReply {
+                        expected: String::from("DataAppended"),
+                        actual: reply,
+                    })
+                }
+            }
+        }
+        debug!(
+            "wrote large event of size {} to transient segment {}",
+            event.len(),
+            transient
+        );
+        Ok(())
+    }
+
+    // Merging is not idempotent, the reply of an attempt may be lost after the transient segment
+    // was merged. A retried merge which does not find the transient segment anymore has succeeded,
+    // it must not fail the event or the event would be written again.
+    async fn merge(
+        &self,
+        raw_client: &RawClientImpl<'_>,
+        transient: &str,
+        token: &str,
+    ) -> Result<i64, SegmentWriterError> {
+        let request = Requests::MergeSegments(MergeSegmentsCommand {
+            request_id: get_request_id(),
+            target: self.segment.to_string(),
+            source: transient.to_owned(),
+            delegation_token: token.to_owned(),
+        });
+        let attempted = AtomicBool::new(false);
+        let reply = retry_async(self.factory.get_config().retry_policy, || async {
+            let retried = attempted.swap(true, Ordering::SeqCst);
+            match raw_client.send_request(&request).await {
+                Ok(Replies::NoSuchSegment(cmd)) if retried && cmd.segment == transient => {
+                    RetryResult::Success(None)
+                }
+                Ok(reply) => RetryResult::Success(Some(reply)),
+                Err(e) => {
+                    warn!("failed to merge transient segment {} due to {:?}", transient, e);
+                    if e.is_token_expired() {
+                        self.delegation_token_provider.signal_token_expiry();
+                    }
+                    RetryResult::Retry(e)
+                }
+            }
+        })
+        .await
+        .map_err(|e| SegmentWriterError::RetryRawClient { err: e })?;
+        match reply {
+            Some(Replies::SegmentsMerged(cmd)) => Ok(cmd.new_target_write_offset),
+            Some(reply) => Err(SegmentWriterError::WrongReply {
+                expected: String::from("SegmentsMerged"),
+                actual: reply,
+            }),
+            None => {
+                debug!("transient segment {} was merged by a previous attempt", transient);
+                self.get_segment_length(raw_client, token).await
+            }
+        }
+    }
+
+    async fn get_segment_length(
+        &self,
+        raw_client: &RawClientImpl<'_>,
+        token: &str,
+    ) -> Result<i64, SegmentWriterError> {
+        let request = Requests::GetStreamSegmentInfo(GetStreamSegmentInfoCommand {
+            request_id: get_request_id(),
+            segment_name: self.segment.to_string(),
+            delegation_token: token.to_owned(),
+        });
+        match self.send_request(raw_client, &request).await? {
+            Replies::StreamSegmentInfo(cmd) => Ok(cmd.write_offset),
+            reply => Err(SegmentWriterError::WrongReply {
+                expected: String::from("StreamSegmentInfo"),
+                actual: reply,
+            }),
+        }
+    }
+
+    // The transient segment is deleted on a best effort basis.
+    async fn delete(&self, raw_client: &RawClientImpl<'_>, transient: &str, token: &str) {
+        let request = Requests::DeleteSegment(DeleteSegmentCommand {
+            request_id: get_request_id(),
+            segment: transient.to_owned(),
+            delegation_token: token.to_owned(),
+        });
+        if let Err(e) = self.send_request(raw_client, &request).await {
+            warn!("failed to delete transient segment {} due to {:?}", transient, e);
+        }
+    }
+
+    async fn send_request(
+        &self,
+        raw_client: &RawClientImpl<'_>,
+        request: &Requests,
+    ) -> Result<Replies, SegmentWriterError> {
+        retry_async(self.factory.get_config().retry_policy, || async {
+            match raw_client.send_request(request).await {
+                Ok(reply) => RetryResult::Success(reply),
+                Err(e) => {
+                    warn!("failed to send request for large event due to {:?}", e);
+                    if e.is_token_expired() {
+                        self.delegation_token_provider.signal_token_expiry();
+                    }
+                    RetryResult::Retry(e)
+                }
+            }
+        })
+        .await
+        .map_err(|e| SegmentWriterError::RetryRawClient { err: e })
+    }
+}
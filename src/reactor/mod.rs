@@ -9,6 +9,7 @@
 //
 
 pub(crate) mod event;
+pub(crate) mod large_event_writer;
 pub(crate) mod reactors;
 pub(crate) mod segment_selector;
 pub(crate) mod segment_writer;
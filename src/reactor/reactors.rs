@@ -9,14 +9,16 @@
 //
 
 use std::sync::Arc;
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::oneshot;
 use tracing::{debug, error, info, warn};
 
+use pravega_rust_client_channel::{ChannelReceiver, ChannelSender};
 use pravega_rust_client_shared::*;
 use pravega_wire_protocol::wire_commands::Replies;
 
 use crate::client_factory::ClientFactory;
-use crate::reactor::event::{Incoming, ServerReply};
+use crate::error::*;
+use crate::reactor::event::{Incoming, LargeEventWritten, ServerReply};
 use crate::reactor::segment_selector::SegmentSelector;
 
 #[derive(new)]
@@ -25,13 +27,15 @@ pub(crate) struct Reactor {}
 impl Reactor {
     pub(crate) async fn run(
         stream: ScopedStream,
-        sender: Sender<Incoming>,
-        mut receiver: Receiver<Incoming>,
+        writer_id: WriterId,
+        sender: ChannelSender<Incoming>,
+        mut receiver: ChannelReceiver<Incoming>,
         factory: ClientFactory,
     ) {
         let delegation_token_provider = factory.create_delegation_token_provider(stream.clone()).await;
         let mut selector = SegmentSelector::new(
             stream,
+            writer_id,
             sender,
             factory.get_config().to_owned(),
             factory.clone(),
@@ -40,24 +44,76 @@ impl Reactor {
         // get the current segments and create corresponding event segment writers
         selector.initialize().await;
         info!("starting reactor");
-        while Reactor::run_once(&mut selector, &mut receiver, &factory)
-            .await
-            .is_ok()
-        {}
+        let result = loop {
+            let result = Reactor::run_once(&mut selector, &mut receiver, &factory).await;
+            selector.record_failures();
+            if let Err(e) = result {
+                break Err(e);
+            }
+            if selector.is_drained() {
+                let flush_waiters = selector.flush_waiters.drain(..).collect::<Vec<_>>();
+                Reactor::notify_waiters(flush_waiters, || selector.get_flush_result());
+                if let Some(close_waiter) = selector.close_waiter.take() {
+                    let removed = Reactor::remove_writer(&selector, &factory).await;
+                    let result = selector.get_flush_result().and(removed);
+                    if close_waiter.send(result).is_err() {
+                        debug!("failed to notify the caller due to Receiver dropped");
+                    }
+                    break Ok(());
+                }
+            }
+        };
+        if let Err(msg) = result {
+            let error = || Err(SegmentWriterError::ReactorClosed { msg: msg.to_owned() });
+            Reactor::notify_waiters(selector.flush_waiters.drain(..), error);
+            Reactor::notify_waiters(selector.close_waiter.take(), error);
+        }
         info!("reactor is closed");
     }
 
+    // Reply to the callers waiting for the events to be flushed or for the reactor to be closed.
+    fn notify_waiters<I, F>(waiters: I, result: F)
+    where
+        I: IntoIterator<Item = oneshot::Sender<Result<(), SegmentWriterError>>>,
+        F: Fn() -> Result<(), SegmentWriterError>,
+    {
+        for waiter in waiters {
+            if waiter.send(result()).is_err() {
+                debug!("failed to notify the caller due to Receiver dropped");
+            }
+        }
+    }
+
+    // Removes the writer from the watermarking of the stream if it has ever noted a time.
+    async fn remove_writer(
+        selector: &SegmentSelector,
+        factory: &ClientFactory,
+    ) -> Result<(), SegmentWriterError> {
+        if !selector.time_noted {
+            return Ok(());
+        }
+        factory
+            .get_controller_client()
+            .remove_writer(&selector.stream, selector.writer_id)
+            .await
+            .map_err(|e| SegmentWriterError::RetryControllerWriting { err: e })
+    }
+
     async fn run_once(
         selector: &mut SegmentSelector,
-        receiver: &mut Receiver<Incoming>,
+        receiver: &mut ChannelReceiver<Incoming>,
         factory: &ClientFactory,
     ) -> Result<(), &'static str> {
-        let event = receiver.recv().await.expect("sender closed, processor exit");
+        let (event, capacity_guard) = match receiver.recv_with_guard().await {
+            Some(event) => event,
+            None => return Err("Sender closed"),
+        };
         match event {
-            Incoming::AppendEvent(pending_event) => {
+            Incoming::AppendEvent(mut pending_event) => {
+                pending_event.capacity_guard = Some(capacity_guard);
                 let event_segment_writer = selector.get_segment_writer(&pending_event.routing_key);
 
-                if let Err(e) = event_segment_writer.write(pending_event).await {
+                if let Err(e) = event_segment_writer.write(pending_event, factory).await {
                     warn!("failed to write append to segment due to {:?}, reconnecting", e);
                     event_segment_writer.reconnect(factory).await;
                 }
@@ -79,6 +135,51 @@ impl Reactor {
                 writer.reconnect(factory).await;
                 Ok(())
             }
+            Incoming::LingerExpired(linger_expired) => {
+                // the writer is gone if its segment was sealed in the meantime
+                if let Some(writer) = selector.writers.get_mut(&linger_expired.segment) {
+                    if let Err(e) = writer.linger_expired(factory).await {
+                        warn!("failed to write lingering events due to {:?}, reconnecting", e);
+                        writer.reconnect(factory).await;
+                    }
+                }
+                Ok(())
+            }
+            Incoming::LargeEventWritten(written) => {
+                Reactor::process_large_event_written(written, selector, factory).await
+            }
+            Incoming::NoteTime(note_time) => {
+                // the position only covers the events acknowledged so far
+                let position = selector.get_writer_position();
+                let writer_id = selector.writer_id;
+                selector.time_noted = true;
+                let factory = factory.clone();
+                tokio::spawn(async move {
+                    let result = factory
+                        .get_controller_client()
+                        .note_timestamp_from_writer(writer_id, Timestamp(note_time.timestamp), &position)
+                        .await
+                        .map_err(|e| SegmentWriterError::RetryControllerWriting { err: e });
+                    if note_time.oneshot_sender.send(result).is_err() {
+                        debug!("failed to notify the caller due to Receiver dropped");
+                    }
+                });
+                Ok(())
+            }
+            Incoming::Flush(flush_waiter) => {
+                selector.flush_waiters.push(flush_waiter);
+                for writer in selector.writers.values_mut() {
+                    if let Err(e) = writer.flush_pending_events(factory).await {
+                        warn!("failed to flush pending events due to {:?}, reconnecting", e);
+                        writer.reconnect(factory).await;
+                    }
+                }
+                Ok(())
+            }
+            Incoming::Close(close_waiter) => {
+                selector.close_waiter = Some(close_waiter);
+                Ok(())
+            }
         }
     }
 
@@ -100,7 +201,8 @@ impl Reactor {
                     writer.id, cmd.event_number
                 );
                 writer.ack(cmd.event_number);
-                if let Err(e) = writer.write_pending_events().await {
+                writer.last_write_offset = cmd.current_segment_write_offset;
+                if let Err(e) = writer.write_pending_events(factory).await {
                     warn!(
                         "writer {:?} failed to flush data to segment {:?} due to {:?}, reconnecting",
                         writer.id, writer.segment, e
@@ -116,13 +218,7 @@ impl Reactor {
                     cmd.segment, cmd.server_stack_trace
                 );
                 let segment = ScopedSegment::from(&*cmd.segment);
-                if let Some(inflight) = selector.refresh_segment_event_writers_upon_sealed(&segment).await {
-                    selector.resend(inflight).await;
-                    selector.remove_segment_event_writer(&segment);
-                    Ok(())
-                } else {
-                    Err("Stream is sealed")
-                }
+                Reactor::resend_upon_sealed(&segment, selector).await
             }
 
             Replies::NoSuchSegment(cmd) => {
@@ -131,13 +227,7 @@ impl Reactor {
                     cmd.segment, cmd.server_stack_trace
                 );
                 let segment = ScopedSegment::from(&*cmd.segment);
-                if let Some(inflight) = selector.refresh_segment_event_writers_upon_sealed(&segment).await {
-                    selector.resend(inflight).await;
-                    selector.remove_segment_event_writer(&segment);
-                    Ok(())
-                } else {
-                    Err("Stream is sealed")
-                }
+                Reactor::resend_upon_sealed(&segment, selector).await
             }
 
             Replies::WrongHost(cmd) => {
@@ -159,17 +249,75 @@ impl Reactor {
             }
         }
     }
+
+    async fn process_large_event_written(
+        written: LargeEventWritten,
+        selector: &mut SegmentSelector,
+        factory: &ClientFactory,
+    ) -> Result<(), &'static str> {
+        // the writer is gone if its segment was sealed in the meantime
+        let writer = match selector.writers.get_mut(&written.segment) {
+            Some(writer) => writer,
+            None => return Ok(()),
+        };
+        // only a sealed or missing target segment moves the event to the successors, the event
+        // fails if the transient segment it is written to is missing.
+        let target = written.segment.to_string();
+        let target_sealed = match &written.result {
+            Err(SegmentWriterError::WrongReply {
+                actual: Replies::SegmentIsSealed(cmd),
+                ..
+            }) => cmd.segment == target,
+            Err(SegmentWriterError::WrongReply {
+                actual: Replies::NoSuchSegment(cmd),
+                ..
+            }) => cmd.segment == target,
+            _ => false,
+        };
+        match written.result {
+            // the event stays inflight and is written to the successors
+            _ if target_sealed => {
+                writer.large_event_interrupted();
+                Reactor::resend_upon_sealed(&written.segment, selector).await
+            }
+            result => {
+                writer.large_event_written(written.event_id, result);
+                if let Err(e) = writer.write_pending_events(factory).await {
+                    warn!(
+                        "writer {:?} failed to flush data to segment {:?} due to {:?}, reconnecting",
+                        writer.id, writer.segment, e
+                    );
+                    writer.reconnect(factory).await;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Moves the events of the sealed segment to its successors.
+    async fn resend_upon_sealed(
+        segment: &ScopedSegment,
+        selector: &mut SegmentSelector,
+    ) -> Result<(), &'static str> {
+        if let Some(inflight) = selector.refresh_segment_event_writers_upon_sealed(segment).await {
+            selector.resend(inflight).await;
+            selector.remove_segment_event_writer(segment);
+            Ok(())
+        } else {
+            Err("Stream is sealed")
+        }
+    }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
-    use crate::error::*;
     use crate::reactor::event::PendingEvent;
     use crate::reactor::segment_selector::test::create_segment_selector;
     use crate::reactor::segment_writer::SegmentWriter;
     use pravega_rust_client_config::connection_type::MockType;
     use tokio::sync::oneshot;
+    use tokio::time::{timeout, Duration};
 
     type EventHandle = oneshot::Receiver<Result<(), SegmentWriterError>>;
 
@@ -219,6 +367,39 @@ pub(crate) mod test {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_reactor_outstanding_bytes() {
+        let mut rt = tokio::runtime::Runtime::new().unwrap();
+        // the channel of the segment selector holds 1 MiB of events
+        let (mut selector, mut receiver, factory) = rt.block_on(create_segment_selector(MockType::Happy));
+        rt.block_on(selector.initialize());
+        let sender = selector.sender.clone();
+
+        // the event keeps its capacity after the reactor writes it
+        let (event, event_handle) = create_event(600 * 1024);
+        rt.block_on(sender.send((Incoming::AppendEvent(event), 600 * 1024)))
+            .expect("send event to reactor");
+        rt.block_on(Reactor::run_once(&mut selector, &mut receiver, &factory))
+            .expect("write event");
+        let (event, _event_handle) = create_event(600 * 1024);
+        let result = rt.block_on(async {
+            let send = sender.send((Incoming::AppendEvent(event), 600 * 1024));
+            timeout(Duration::from_millis(100), send).await
+        });
+        assert!(result.is_err());
+
+        // the capacity is released once the event is acked
+        rt.block_on(Reactor::run_once(&mut selector, &mut receiver, &factory))
+            .expect("process server reply");
+        assert!(rt.block_on(event_handle).expect("event is acknowledged").is_ok());
+        let (event, _event_handle) = create_event(600 * 1024);
+        let result = rt.block_on(async {
+            let send = sender.send((Incoming::AppendEvent(event), 600 * 1024));
+            timeout(Duration::from_millis(100), send).await
+        });
+        assert!(result.is_ok());
+    }
+
     // helper function section
     async fn write_once_for_selector(
         selector: &mut SegmentSelector,
@@ -227,19 +408,21 @@ pub(crate) mod test {
         let (oneshot_sender, oneshot_receiver) = tokio::sync::oneshot::channel();
         let event = PendingEvent::new(Some("routing_key".into()), vec![1; size], oneshot_sender)
             .expect("create pending event");
+        let factory = selector.factory.clone();
         let writer = selector.get_segment_writer(&event.routing_key);
-        writer.write(event).await.expect("write data");
+        writer.write(event, &factory).await.expect("write data");
         oneshot_receiver
     }
 
     async fn write_once(
         writer: &mut SegmentWriter,
         size: usize,
+        factory: &ClientFactory,
     ) -> oneshot::Receiver<Result<(), SegmentWriterError>> {
         let (oneshot_sender, oneshot_receiver) = tokio::sync::oneshot::channel();
         let event = PendingEvent::new(Some("routing_key".into()), vec![1; size], oneshot_sender)
             .expect("create pending event");
-        writer.write(event).await.expect("write data");
+        writer.write(event, factory).await.expect("write data");
         oneshot_receiver
     }
 
@@ -10,16 +10,18 @@
 
 use std::collections::{BTreeMap, HashMap};
 
+use crate::error::*;
 use crate::get_random_f64;
-use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
 use tracing::{debug, warn};
 
+use pravega_rust_client_channel::ChannelSender;
 use pravega_rust_client_config::ClientConfig;
 use pravega_rust_client_shared::*;
 
 use crate::client_factory::ClientFactory;
 use crate::reactor::event::{Incoming, PendingEvent};
-use crate::reactor::segment_writer::SegmentWriter;
+use crate::reactor::segment_writer::{BatchPolicy, SegmentWriter};
 use pravega_rust_client_auth::DelegationTokenProvider;
 use std::sync::Arc;
 
@@ -27,6 +29,9 @@ pub(crate) struct SegmentSelector {
     /// The stream of this SegmentSelector.
     pub(crate) stream: ScopedStream,
 
+    /// The writer id shared by the segment writers of this stream.
+    pub(crate) writer_id: WriterId,
+
     /// Maps segment to SegmentWriter.
     pub(crate) writers: HashMap<ScopedSegment, SegmentWriter>,
 
@@ -34,7 +39,7 @@ pub(crate) struct SegmentSelector {
     pub(crate) current_segments: StreamSegments,
 
     /// The sender that sends reply back to Reactor.
-    pub(crate) sender: Sender<Incoming>,
+    pub(crate) sender: ChannelSender<Incoming>,
 
     /// Client config that contains the retry policy.
     pub(crate) config: ClientConfig,
@@ -44,27 +49,85 @@ pub(crate) struct SegmentSelector {
 
     /// Delegation token for authentication.
     pub(crate) delegation_token_provider: Arc<DelegationTokenProvider>,
+
+    /// Callers waiting until the events written so far are acknowledged.
+    pub(crate) flush_waiters: Vec<oneshot::Sender<Result<(), SegmentWriterError>>>,
+
+    /// Caller waiting until the events written so far are acknowledged and the reactor is closed.
+    pub(crate) close_waiter: Option<oneshot::Sender<Result<(), SegmentWriterError>>>,
+
+    /// Whether this writer has reported a timestamp to the controller and has to be removed
+    /// from the watermarking of the stream when it is closed.
+    pub(crate) time_noted: bool,
+
+    /// The error of the first event the segment writers failed to write, it is returned to the
+    /// callers waiting for the events to be flushed or for the reactor to be closed.
+    pub(crate) failure: Option<String>,
 }
 
 impl SegmentSelector {
     pub(crate) fn new(
         stream: ScopedStream,
-        sender: Sender<Incoming>,
+        writer_id: WriterId,
+        sender: ChannelSender<Incoming>,
         config: ClientConfig,
         factory: ClientFactory,
         delegation_token_provider: Arc<DelegationTokenProvider>,
     ) -> Self {
         SegmentSelector {
             stream,
+            writer_id,
             writers: HashMap::new(),
             current_segments: StreamSegments::new(BTreeMap::new()),
             sender,
             config,
             factory,
             delegation_token_provider,
+            flush_waiters: vec![],
+            close_waiter: None,
+            time_noted: false,
+            failure: None,
         }
     }
 
+    /// Returns true if all the events sent to the segment writers have been acknowledged.
+    pub(crate) fn is_drained(&self) -> bool {
+        self.writers
+            .values()
+            .all(|writer| writer.pending_append_num() == 0 && writer.inflight_append_num() == 0)
+    }
+
+    /// Records the first failure of the segment writers, the failures of the writers which are
+    /// recorded later are ignored.
+    pub(crate) fn record_failures(&mut self) {
+        for writer in self.writers.values_mut() {
+            if let Some(failure) = writer.failure.take() {
+                self.failure.get_or_insert(failure);
+            }
+        }
+    }
+
+    /// Returns the first failure of the segment writers as the result of a flush or close.
+    pub(crate) fn get_flush_result(&self) -> Result<(), SegmentWriterError> {
+        match &self.failure {
+            Some(failure) => Err(SegmentWriterError::EventWriteFailed {
+                error_msg: failure.to_owned(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the position of this writer in the stream, made of the last acknowledged write
+    /// offset of every segment it is writing to.
+    pub(crate) fn get_writer_position(&self) -> StreamCut {
+        let segment_offset_map = self
+            .writers
+            .iter()
+            .map(|(segment, writer)| (segment.segment.number, writer.last_write_offset))
+            .collect();
+        StreamCut::new(self.stream.clone(), segment_offset_map)
+    }
+
     /// Gets all the segments in the stream from controller and creates corresponding
     /// segment writers. Initializes segment writers by setting up connections so that segment
     /// writers are ready to use after initialization.
@@ -144,10 +207,12 @@ impl SegmentSelector {
         for scoped_segment in self.current_segments.get_segments() {
             if !self.writers.contains_key(&scoped_segment) {
                 let mut writer = SegmentWriter::new(
+                    self.writer_id,
                     scoped_segment.clone(),
                     self.sender.clone(),
                     self.config.retry_policy,
                     self.delegation_token_provider.clone(),
+                    BatchPolicy::from_config(&self.config),
                 );
 
                 debug!(
@@ -168,7 +233,7 @@ impl SegmentSelector {
         for event in to_resend {
             let segment = self.get_segment_for_event(&event.routing_key);
             let segment_writer = self.writers.get_mut(&segment).expect("must have writer");
-            if let Err(e) = segment_writer.write(event).await {
+            if let Err(e) = segment_writer.write(event, &self.factory).await {
                 warn!(
                     "failed to resend an event due to: {:?}, reconnecting the event segment writer",
                     e
@@ -180,6 +245,7 @@ impl SegmentSelector {
 
     /// Removes segment writer from the internal map.
     pub(crate) fn remove_segment_event_writer(&mut self, segment: &ScopedSegment) -> Option<SegmentWriter> {
+        self.record_failures();
         self.writers.remove(segment)
     }
 }
@@ -187,13 +253,13 @@ impl SegmentSelector {
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
+    use crate::get_random_u128;
     use im::HashMap as ImHashMap;
     use ordered_float::OrderedFloat;
+    use pravega_rust_client_channel::{create_channel, ChannelReceiver};
     use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
     use pravega_rust_client_config::ClientConfigBuilder;
     use tokio::runtime::Runtime;
-    use tokio::sync::mpsc;
-    use tokio::sync::mpsc::Receiver;
 
     #[test]
     fn test_segment_selector() {
@@ -236,7 +302,7 @@ pub(crate) mod test {
     // helper function section
     pub(crate) async fn create_segment_selector(
         mock: MockType,
-    ) -> (SegmentSelector, Receiver<Incoming>, ClientFactory) {
+    ) -> (SegmentSelector, ChannelReceiver<Incoming>, ClientFactory) {
         let stream = ScopedStream::from("testScope/testStream");
         let config = ClientConfigBuilder::default()
             .connection_type(ConnectionType::Mock(mock))
@@ -269,11 +335,12 @@ pub(crate) mod test {
             })
             .await
             .unwrap();
-        let (sender, receiver) = mpsc::channel(10);
+        let (sender, receiver) = create_channel(1024 * 1024);
         let delegation_token_provider = DelegationTokenProvider::new(stream.clone());
         (
             SegmentSelector::new(
                 stream,
+                WriterId::from(get_random_u128()),
                 sender,
                 factory.get_config().to_owned(),
                 factory.clone(),
@@ -8,11 +8,10 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 
+use crate::get_request_id;
 use crate::trace;
-use crate::{get_random_u128, get_request_id};
 use snafu::ResultExt;
 use std::collections::VecDeque;
-use tokio::sync::mpsc::Sender;
 use tracing::{debug, error, field, info, info_span, warn};
 
 use pravega_rust_client_retry::retry_async::retry_async;
@@ -27,10 +26,17 @@ use crate::client_factory::ClientFactory;
 use crate::error::*;
 use crate::metric::ClientMetrics;
 use crate::raw_client::RawClient;
-use crate::reactor::event::{ConnectionFailure, Incoming, PendingEvent, ServerReply};
+use crate::reactor::event::{
+    ConnectionFailure, Incoming, LargeEventWritten, LingerExpired, PendingEvent, ServerReply,
+};
+use crate::reactor::large_event_writer::LargeEventWriter;
 use pravega_rust_client_auth::DelegationTokenProvider;
+use pravega_rust_client_channel::ChannelSender;
+use pravega_rust_client_config::ClientConfig;
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
 use tracing_futures::Instrument;
 
 pub(crate) struct SegmentWriter {
@@ -49,41 +55,100 @@ pub(crate) struct SegmentWriter {
     /// Events that are waiting to be sent.
     pending: VecDeque<Append>,
 
-    /// Incremental event id.
+    /// The id of the last event added to this writer, events are numbered in increasing order.
     event_num: i64,
 
+    /// The length of the segment reported by the last acknowledgement, -1 if nothing was acknowledged yet.
+    pub(crate) last_write_offset: i64,
+
+    /// The last event id the segmentstore has persisted for this writer id, as reported in AppendSetup.
+    persisted_event_num: i64,
+
     /// The sender that sends back reply to reactor for processing.
-    sender: Sender<Incoming>,
+    sender: ChannelSender<Incoming>,
 
     /// The client retry policy.
     retry_policy: RetryWithBackoff,
 
     /// Delegation token provider used to authenticate client when communicating with segmentstore.
     delegation_token_provider: Arc<DelegationTokenProvider>,
+
+    /// Thresholds that decide when the pending events are sent.
+    batch_policy: BatchPolicy,
+
+    /// The time the oldest unsent event was added to the pending list.
+    batch_start: Option<Instant>,
+
+    /// Whether a linger timer is running for this writer.
+    linger_scheduled: bool,
+
+    /// Whether a large event is being written to a transient segment, the event is the only
+    /// inflight event until it is merged into the segment.
+    large_event_in_progress: bool,
+
+    /// The error of the first event this writer failed to write, it is reported to the callers
+    /// waiting for the writer to be flushed or closed.
+    pub(crate) failure: Option<String>,
 }
 
-impl SegmentWriter {
-    /// Maximum data size in one append block.
-    const MAX_WRITE_SIZE: i32 = 8 * 1024 * 1024 + 8;
-    /// Maximum event number in one append block.
-    const MAX_EVENTS: i32 = 500;
+/// Decides how many events go into one append block and how long a writer waits for
+/// more events before sending a batch that is not full yet.
+#[derive(new, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BatchPolicy {
+    max_bytes: usize,
+    max_events: usize,
+    linger: Duration,
+}
 
+impl BatchPolicy {
+    /// Creates the batch policy from the client config. The byte threshold cannot exceed
+    /// the size of an append block and at least one event is sent in a block.
+    pub(crate) fn from_config(config: &ClientConfig) -> Self {
+        BatchPolicy {
+            max_bytes: config.max_batch_bytes().min(PendingEvent::MAX_WRITE_SIZE),
+            max_events: config.max_batch_events().max(1),
+            linger: Duration::from_millis(config.batch_linger_time()),
+        }
+    }
+}
+
+impl Default for BatchPolicy {
+    /// Sends the pending events right away, up to the maximum append block size.
+    fn default() -> Self {
+        BatchPolicy {
+            max_bytes: PendingEvent::MAX_WRITE_SIZE,
+            max_events: 500,
+            linger: Duration::from_millis(0),
+        }
+    }
+}
+
+impl SegmentWriter {
     pub(crate) fn new(
+        id: WriterId,
         segment: ScopedSegment,
-        sender: Sender<Incoming>,
+        sender: ChannelSender<Incoming>,
         retry_policy: RetryWithBackoff,
         delegation_token_provider: Arc<DelegationTokenProvider>,
+        batch_policy: BatchPolicy,
     ) -> Self {
         SegmentWriter {
-            id: WriterId::from(get_random_u128()),
+            id,
             connection: None,
             segment,
             inflight: VecDeque::new(),
             pending: VecDeque::new(),
             event_num: 0,
+            last_write_offset: -1,
+            persisted_event_num: i64::MIN,
             sender,
             retry_policy,
             delegation_token_provider,
+            batch_policy,
+            batch_start: None,
+            linger_scheduled: false,
+            large_event_in_progress: false,
+            failure: None,
         }
     }
 
@@ -147,6 +212,7 @@ impl SegmentWriter {
                             self.id, self.segment
                         );
                         self.ack(cmd.last_event_number);
+                        self.skip_persisted(cmd.last_event_number);
                         connection
                     }
                     _ => {
@@ -165,7 +231,7 @@ impl SegmentWriter {
             self.connection = Some(w);
 
             let segment = self.segment.clone();
-            let mut sender = self.sender.clone();
+            let sender = self.sender.clone();
 
             // spins up a connection listener that keeps listening on the connection
             let listener_span = info_span!("connection listener", connection = %connection_id);
@@ -176,9 +242,9 @@ impl SegmentWriter {
                         Err(e) => {
                             warn!("connection failed to read data back from segmentstore due to {:?}, closing the listener task", e);
                             let result = sender
-                                .send(Incoming::ConnectionFailure(ConnectionFailure {
+                                .send((Incoming::ConnectionFailure(ConnectionFailure {
                                     segment: segment.clone(),
-                                })).await;
+                                }), 0)).await;
                             if let Err(e) = result {
                                 error!("failed to send connectionFailure signal to reactor {:?}", e);
                             }
@@ -187,10 +253,10 @@ impl SegmentWriter {
                     };
 
                     let result = sender
-                        .send(Incoming::ServerReply(ServerReply {
+                        .send((Incoming::ServerReply(ServerReply {
                             segment: segment.clone(),
                             reply,
-                        }))
+                        }), 0))
                         .await;
 
                     if let Err(e) = result {
@@ -206,40 +272,232 @@ impl SegmentWriter {
 
     /// Adds the event to the pending list
     /// then writes the pending list if the inflight list is empty.
-    pub(crate) async fn write(&mut self, event: PendingEvent) -> Result<(), SegmentWriterError> {
+    pub(crate) async fn write(
+        &mut self,
+        event: PendingEvent,
+        factory: &ClientFactory,
+    ) -> Result<(), SegmentWriterError> {
         self.add_pending(event);
-        self.write_pending_events().await
+        self.write_pending_events(factory).await
     }
 
-    /// Adds the event to the pending list
+    /// Adds the event to the pending list. Events without an event number are numbered after the
+    /// last event of this writer. An event numbered by the caller that the segmentstore has already
+    /// persisted for this writer id, e.g. before the producer restarted, is acked right away
+    /// instead, and one that is not numbered after the last event is failed.
     pub(crate) fn add_pending(&mut self, event: PendingEvent) {
-        self.event_num += 1;
-        self.pending.push_back(Append {
-            event_id: self.event_num,
-            event,
-        });
+        let event_id = match event.event_number {
+            Some(event_number) if event_number <= self.persisted_event_num => {
+                debug!(
+                    "event {} has been persisted before, skip writing it to segment {}",
+                    event_number, self.segment
+                );
+                if event.oneshot_sender.send(Result::Ok(())).is_err() {
+                    debug!("failed to send ack back to caller using oneshot due to Receiver dropped");
+                }
+                return;
+            }
+            Some(event_number) if event_number <= self.event_num => {
+                warn!(
+                    "event number {} is not greater than the last event number {} of segment {}",
+                    event_number, self.event_num, self.segment
+                );
+                let error = SegmentWriterError::EventNumberOutOfOrder {
+                    event_number,
+                    last_event_number: self.event_num,
+                };
+                self.fail_event(event, error);
+                return;
+            }
+            Some(event_number) => event_number,
+            None => self.event_num + 1,
+        };
+        self.event_num = event_id;
+        if self.pending.is_empty() {
+            self.batch_start = Some(Instant::now());
+        }
+        self.pending.push_back(Append { event_id, event });
+    }
+
+    /// Writes the pending events to the server once the batch is ready, that is when the pending
+    /// events reach one of the batch thresholds or the oldest of them has lingered long enough.
+    /// Otherwise a timer is scheduled that notifies the reactor when the linger time is up.
+    /// A large event is written on its own once the events before it are acknowledged.
+    pub(crate) async fn write_pending_events(
+        &mut self,
+        factory: &ClientFactory,
+    ) -> Result<(), SegmentWriterError> {
+        if !self.inflight.is_empty() || self.pending.is_empty() {
+            return Ok(());
+        }
+        if self.is_large_event_next() {
+            self.write_large_event(factory);
+            return Ok(());
+        }
+
+        if let Some(remaining) = self.remaining_linger() {
+            self.schedule_linger(remaining);
+            return Ok(());
+        }
+        self.send_pending_events().await
     }
 
-    /// Writes the pending events to the server. It will grab at most MAX_WRITE_SIZE of data
-    /// from the pending list and send them to the server. Those events will be moved to inflight list waiting to be acked.
-    pub(crate) async fn write_pending_events(&mut self) -> Result<(), SegmentWriterError> {
+    /// Writes the pending events to the server regardless of the linger time.
+    pub(crate) async fn flush_pending_events(
+        &mut self,
+        factory: &ClientFactory,
+    ) -> Result<(), SegmentWriterError> {
         if !self.inflight.is_empty() || self.pending.is_empty() {
             return Ok(());
         }
+        if self.is_large_event_next() {
+            self.write_large_event(factory);
+            return Ok(());
+        }
+        self.send_pending_events().await
+    }
+
+    /// Called by the reactor when the linger timer of this writer fires.
+    pub(crate) async fn linger_expired(&mut self, factory: &ClientFactory) -> Result<(), SegmentWriterError> {
+        self.linger_scheduled = false;
+        self.write_pending_events(factory).await
+    }
+
+    fn is_large_event_next(&self) -> bool {
+        matches!(self.pending.front(), Some(append) if append.event.is_large())
+    }
+
+    // Moves the large event at the head of the pending list to the inflight list and spawns a task
+    // that writes it to the segment, the reactor is notified once the task is done.
+    fn write_large_event(&mut self, factory: &ClientFactory) {
+        let append = self.pending.pop_front().expect("must have large event");
+        if self.pending.is_empty() {
+            self.batch_start = None;
+        }
+        let event_id = append.event_id;
+        // the event is kept so it can be written again to the successors if the segment is sealed
+        let data = append.event.data.clone();
+        self.inflight.push_back(append);
+        self.large_event_in_progress = true;
+
+        let writer = LargeEventWriter::new(
+            self.segment.clone(),
+            factory.clone(),
+            self.delegation_token_provider.clone(),
+        );
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            let result = writer.write(&data).await;
+            let written = LargeEventWritten::new(writer.segment().clone(), event_id, result);
+            if let Err(e) = sender.send((Incoming::LargeEventWritten(written), 0)).await {
+                debug!(
+                    "failed to notify reactor that a large event is written due to {:?}",
+                    e
+                );
+            }
+        });
+    }
+
+    /// Called by the reactor once the large event is written. The event is acknowledged or
+    /// failed with the error returned by the write.
+    pub(crate) fn large_event_written(&mut self, event_id: i64, result: Result<i64, SegmentWriterError>) {
+        self.large_event_in_progress = false;
+        let append = match self.inflight.front() {
+            Some(append) if append.event_id == event_id => self.inflight.pop_front().expect("must have"),
+            _ => {
+                debug!("large event {} is no longer inflight", event_id);
+                return;
+            }
+        };
+        match result {
+            Ok(write_offset) => {
+                self.last_write_offset = write_offset;
+                if append.event.oneshot_sender.send(Ok(())).is_err() {
+                    debug!(
+                        "failed to send ack back to caller using oneshot due to Receiver dropped: event id {:?}",
+                        event_id
+                    );
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "failed to write large event {} to segment {} due to {:?}",
+                    event_id, self.segment, e
+                );
+                self.fail_event(append.event, e);
+            }
+        }
+    }
+
+    // Fails the event with the given error and records the first failure of this writer.
+    fn fail_event(&mut self, event: PendingEvent, error: SegmentWriterError) {
+        if self.failure.is_none() {
+            self.failure = Some(error.to_string());
+        }
+        if event.oneshot_sender.send(Err(error)).is_err() {
+            debug!("failed to send error back to caller using oneshot due to Receiver dropped");
+        }
+    }
+
+    /// Called by the reactor when the large event could not be merged because the segment is
+    /// sealed, the event stays inflight so that it is written again to the successors.
+    pub(crate) fn large_event_interrupted(&mut self) {
+        self.large_event_in_progress = false;
+    }
+
+    // Returns the time left before the pending events must be sent, or None if they should be sent now.
+    fn remaining_linger(&self) -> Option<Duration> {
+        if self.pending.len() >= self.batch_policy.max_events {
+            return None;
+        }
+        let pending_size: usize = self.pending.iter().map(|append| append.event.data.len()).sum();
+        if pending_size >= self.batch_policy.max_bytes {
+            return None;
+        }
+        let lingered = self
+            .batch_start
+            .map_or(self.batch_policy.linger, |start| start.elapsed());
+        self.batch_policy
+            .linger
+            .checked_sub(lingered)
+            .filter(|d| *d > Duration::from_millis(0))
+    }
+
+    fn schedule_linger(&mut self, delay: Duration) {
+        if self.linger_scheduled {
+            return;
+        }
+        self.linger_scheduled = true;
+        let segment = self.segment.clone();
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            delay_for(delay).await;
+            if let Err(e) = sender
+                .send((Incoming::LingerExpired(LingerExpired { segment }), 0))
+                .await
+            {
+                debug!("failed to notify reactor that linger time is up due to {:?}", e);
+            }
+        });
+    }
 
+    // Grabs at most max batch bytes or max batch events from the pending list and sends them to the server.
+    // Those events will be moved to inflight list waiting to be acked.
+    async fn send_pending_events(&mut self) -> Result<(), SegmentWriterError> {
         let mut total_size = 0;
         let mut to_send = vec![];
         let mut event_count = 0;
 
         while let Some(append) = self.pending.pop_front() {
-            assert!(
-                append.event.data.len() <= SegmentWriter::MAX_WRITE_SIZE as usize,
-                "event size {} must be under {}",
-                append.event.data.len(),
-                SegmentWriter::MAX_WRITE_SIZE
-            );
-            if append.event.data.len() + to_send.len() <= SegmentWriter::MAX_WRITE_SIZE as usize
-                || event_count == SegmentWriter::MAX_EVENTS as usize
+            // a large event is written on its own once this batch is acknowledged
+            if append.event.is_large() {
+                self.pending.push_front(append);
+                break;
+            }
+            // the first event is always sent even if it alone exceeds the byte threshold
+            if event_count == 0
+                || (append.event.data.len() + to_send.len() <= self.batch_policy.max_bytes
+                    && event_count < self.batch_policy.max_events)
             {
                 event_count += 1;
                 total_size += append.event.data.len();
@@ -250,6 +508,12 @@ impl SegmentWriter {
                 break;
             }
         }
+        if self.pending.is_empty() {
+            self.batch_start = None;
+        }
+        if event_count == 0 {
+            return Ok(());
+        }
 
         debug!(
             "flushing {} events of total size {} to segment {:?}; event segment writer id {:?}/connection id: {:?}",
@@ -316,6 +580,50 @@ impl SegmentWriter {
         }
     }
 
+    /// Records the last event id that the segmentstore has persisted for this writer id. The pending
+    /// events numbered by the caller up to it are acked so that they are not written twice, while
+    /// the events numbered by this writer are renumbered after it so that they are not dropped by
+    /// the segmentstore, e.g. when a writer restarts with the id of a previous writer.
+    fn skip_persisted(&mut self, last_event_number: i64) {
+        if last_event_number <= self.persisted_event_num {
+            return;
+        }
+        self.persisted_event_num = last_event_number;
+        let mut last_event_id = last_event_number;
+        let mut pending = VecDeque::with_capacity(self.pending.len());
+        while let Some(mut append) = self.pending.pop_front() {
+            match append.event.event_number {
+                Some(event_number) if event_number <= last_event_number => {
+                    if append.event.oneshot_sender.send(Result::Ok(())).is_err() {
+                        debug!(
+                            "failed to send ack back to caller using oneshot due to Receiver dropped: event id {:?}",
+                            append.event_id
+                        );
+                    }
+                    continue;
+                }
+                Some(event_number) if event_number <= last_event_id => {
+                    let error = SegmentWriterError::EventNumberOutOfOrder {
+                        event_number,
+                        last_event_number: last_event_id,
+                    };
+                    self.fail_event(append.event, error);
+                    continue;
+                }
+                Some(_) => {}
+                None if append.event_id <= last_event_id => append.event_id = last_event_id + 1,
+                None => {}
+            }
+            last_event_id = append.event_id;
+            pending.push_back(append);
+        }
+        self.pending = pending;
+        self.event_num = self.event_num.max(last_event_id);
+        if self.pending.is_empty() {
+            self.batch_start = None;
+        }
+    }
+
     /// Gets the unacked events. Notice that it will pass the ownership
     /// of the unacked events to the caller, which means this method can only be called once.
     pub(crate) fn get_unacked_events(&mut self) -> Vec<PendingEvent> {
@@ -346,13 +654,14 @@ impl SegmentWriter {
                 continue;
             }
 
-            while self.inflight.back().is_some() {
+            // a large event in progress does not use this connection, it stays inflight
+            while !self.large_event_in_progress && self.inflight.back().is_some() {
                 self.pending
                     .push_front(self.inflight.pop_back().expect("must have event"));
             }
 
             // flush any pending events
-            let flush_res = self.write_pending_events().await;
+            let flush_res = self.flush_pending_events(factory).await;
             if flush_res.is_err() {
                 continue;
             }
@@ -406,10 +715,11 @@ struct WriteHalfConnectionWrapper {
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
+    use crate::get_random_u128;
+    use pravega_rust_client_channel::{create_channel, ChannelReceiver};
     use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
     use pravega_rust_client_config::ClientConfigBuilder;
-    use tokio::sync::mpsc::Receiver;
-    use tokio::sync::{mpsc, oneshot};
+    use tokio::sync::oneshot;
 
     type EventHandle = oneshot::Receiver<Result<(), SegmentWriterError>>;
 
@@ -417,7 +727,8 @@ pub(crate) mod test {
     fn test_segment_writer_happy_write() {
         // set up segment writer
         let mut rt = tokio::runtime::Runtime::new().unwrap();
-        let (mut segment_writer, mut receiver, factory) = create_segment_writer(MockType::Happy);
+        let (mut segment_writer, mut receiver, factory) =
+            create_segment_writer(MockType::Happy, BatchPolicy::default());
 
         // test set up connection
         let result = rt.block_on(segment_writer.setup_connection(&factory));
@@ -427,8 +738,8 @@ pub(crate) mod test {
         let (event, event_handle1) = create_event(512);
         let reply = rt
             .block_on(async {
-                segment_writer.write(event).await.expect("write data");
-                receiver.recv().await
+                segment_writer.write(event, &factory).await.expect("write data");
+                receiver.recv().await.map(|(incoming, _size)| incoming)
             })
             .expect("receive DataAppend from segment writer");
 
@@ -437,15 +748,16 @@ pub(crate) mod test {
         assert!(segment_writer.pending.is_empty());
 
         let (event, event_handle2) = create_event(512);
-        rt.block_on(segment_writer.write(event)).expect("write data");
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
 
         assert_eq!(segment_writer.event_num, 2);
         assert_eq!(segment_writer.inflight.len(), 1);
         assert_eq!(segment_writer.pending.len(), 1);
         ack_server_reply(reply, &mut segment_writer);
-        rt.block_on(segment_writer.write_pending_events())
+        rt.block_on(segment_writer.write_pending_events(&factory))
             .expect("write data");
-        let reply = rt
+        let (reply, _size) = rt
             .block_on(receiver.recv())
             .expect("receive DataAppend from segment writer");
         ack_server_reply(reply, &mut segment_writer);
@@ -463,7 +775,8 @@ pub(crate) mod test {
     fn test_segment_writer_reply_error() {
         // set up segment writer
         let mut rt = tokio::runtime::Runtime::new().unwrap();
-        let (mut segment_writer, mut receiver, factory) = create_segment_writer(MockType::SegmentIsSealed);
+        let (mut segment_writer, mut receiver, factory) =
+            create_segment_writer(MockType::SegmentIsSealed, BatchPolicy::default());
 
         // test set up connection
         let result = rt.block_on(segment_writer.setup_connection(&factory));
@@ -473,8 +786,8 @@ pub(crate) mod test {
         let (event, _event_handle) = create_event(512);
         let reply = rt
             .block_on(async {
-                segment_writer.write(event).await.expect("write data");
-                receiver.recv().await
+                segment_writer.write(event, &factory).await.expect("write data");
+                receiver.recv().await.map(|(incoming, _size)| incoming)
             })
             .expect("receive DataAppend from segment writer");
 
@@ -490,10 +803,89 @@ pub(crate) mod test {
         assert!(result);
     }
 
+    #[test]
+    fn test_segment_writer_batching() {
+        let mut rt = tokio::runtime::Runtime::new().unwrap();
+        let policy = BatchPolicy::new(2048, 3, Duration::from_millis(100));
+        let (mut segment_writer, mut receiver, factory) = create_segment_writer(MockType::Happy, policy);
+        rt.block_on(segment_writer.setup_connection(&factory))
+            .expect("set up connection");
+
+        // events below the thresholds linger in the pending list
+        let (event, _event_handle1) = create_event(100);
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
+        let (event, _event_handle2) = create_event(100);
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
+        assert!(segment_writer.inflight.is_empty());
+        assert_eq!(segment_writer.pending.len(), 2);
+
+        // reaching the max batch events sends the batch
+        let (event, _event_handle3) = create_event(100);
+        let reply = rt
+            .block_on(async {
+                segment_writer.write(event, &factory).await.expect("write data");
+                receiver.recv().await.map(|(incoming, _size)| incoming)
+            })
+            .expect("receive reply");
+        assert_eq!(segment_writer.inflight.len(), 3);
+        assert!(segment_writer.pending.is_empty());
+        // the linger timer scheduled by the first event may fire before the server replies
+        let reply = match reply {
+            Incoming::LingerExpired(_) => rt
+                .block_on(receiver.recv())
+                .map(|(incoming, _size)| incoming)
+                .expect("receive reply"),
+            reply => reply,
+        };
+        ack_server_reply(reply, &mut segment_writer);
+
+        // an event lingers until the timer fires
+        let (event, _event_handle4) = create_event(100);
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
+        assert_eq!(segment_writer.pending.len(), 1);
+        loop {
+            match rt
+                .block_on(receiver.recv())
+                .map(|(incoming, _size)| incoming)
+                .expect("receive linger expired")
+            {
+                Incoming::LingerExpired(linger) => {
+                    assert_eq!(linger.segment, segment_writer.segment);
+                    rt.block_on(segment_writer.linger_expired(&factory))
+                        .expect("write data");
+                    if segment_writer.pending.is_empty() {
+                        break;
+                    }
+                }
+                other => panic!("unexpected incoming {:?}", other),
+            }
+        }
+        assert_eq!(segment_writer.inflight.len(), 1);
+        let reply = rt
+            .block_on(receiver.recv())
+            .map(|(incoming, _size)| incoming)
+            .expect("receive reply");
+        ack_server_reply(reply, &mut segment_writer);
+
+        // reaching the max batch bytes sends the batch, one block holds at most 2048 bytes
+        let (event, _event_handle5) = create_event(1024);
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
+        let (event, _event_handle6) = create_event(1024);
+        rt.block_on(segment_writer.write(event, &factory))
+            .expect("write data");
+        assert_eq!(segment_writer.inflight.len(), 2);
+        assert!(segment_writer.pending.is_empty());
+    }
+
     // helper function section
     pub(crate) fn create_segment_writer(
         mock: MockType,
-    ) -> (SegmentWriter, Receiver<Incoming>, ClientFactory) {
+        batch_policy: BatchPolicy,
+    ) -> (SegmentWriter, ChannelReceiver<Incoming>, ClientFactory) {
         let segment = ScopedSegment::from("testScope/testStream/0");
         let config = ClientConfigBuilder::default()
             .connection_type(ConnectionType::Mock(mock))
@@ -502,14 +894,16 @@ pub(crate) mod test {
             .build()
             .unwrap();
         let factory = ClientFactory::new(config);
-        let (sender, receiver) = mpsc::channel(10);
+        let (sender, receiver) = create_channel(1024 * 1024);
         let delegation_token_provider = DelegationTokenProvider::new(ScopedStream::from(&segment));
         (
             SegmentWriter::new(
+                WriterId::from(get_random_u128()),
                 segment,
                 sender,
                 factory.get_config().retry_policy,
                 Arc::new(delegation_token_provider),
+                batch_policy,
             ),
             receiver,
             factory,
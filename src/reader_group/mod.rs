@@ -10,3 +10,641 @@
 
 pub(crate) mod reader_group_config;
 pub(crate) mod reader_group_state;
+
+pub use reader_group_config::{ReaderGroupConfigV1, ReaderGroupConfigVersioned};
+pub use reader_group_state::ReaderGroupStateError;
+
+use crate::client_factory::ClientFactory;
+use crate::error::*;
+use crate::event_reader::{EventReader, TypedEventReader};
+use crate::reader_group::reader_group_state::{Offset, ReaderGroupState};
+use crate::serializer::Serializer;
+use crate::stream::position::PositionVersioned;
+use crate::stream::stream_cut::{StreamCutV1, StreamCutVersioned};
+use pravega_rust_client_shared::{
+    Reader, Scope, ScopedSegment, ScopedStream, Segment, SegmentWithRange, Stream,
+};
+use snafu::ResultExt;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+use tokio::time::{delay_for, Duration};
+use tracing::{debug, info, warn};
+
+/// ReaderGroup is a collection of readers that collectively read all the events in the
+/// configured streams. The segments of the streams are distributed among the online readers
+/// through the reader group state, which is shared by the readers using a TableSynchronizer.
+///
+/// A reader group should be created using the ClientFactory.
+pub struct ReaderGroup {
+    name: String,
+    scope: Scope,
+    config: ReaderGroupConfigVersioned,
+    state: Arc<Mutex<ReaderGroupState>>,
+    client_factory: ClientFactory,
+}
+
+impl ReaderGroup {
+    // ReaderGroup should be created by the client factory, so this method is not public.
+    pub(crate) async fn create(
+        scope: Scope,
+        name: String,
+        config: ReaderGroupConfigVersioned,
+        client_factory: ClientFactory,
+    ) -> Result<ReaderGroup, ReaderGroupStateError> {
+        let init_segments = ReaderGroup::get_starting_segments(&config, &client_factory).await?;
+        info!(
+            "initial segments of reader group {} are {:?}",
+            name, init_segments
+        );
+
+        let scoped_synchronizer_stream =
+            ScopedStream::new(scope.clone(), Stream::from(format!("_RG{}", name)));
+        let state = ReaderGroupState::new(
+            scoped_synchronizer_stream,
+            &client_factory,
+            config.clone(),
+            init_segments,
+        )
+        .await?;
+        Ok(ReaderGroup {
+            name,
+            scope,
+            config,
+            state: Arc::new(Mutex::new(state)),
+            client_factory,
+        })
+    }
+
+    // Gets the segments and offsets the reader group starts reading from according to the
+    // starting stream cuts of the config.
+    async fn get_starting_segments(
+        config: &ReaderGroupConfigVersioned,
+        client_factory: &ClientFactory,
+    ) -> Result<HashMap<SegmentWithRange, Offset>, ReaderGroupStateError> {
+        let mut init_segments = HashMap::new();
+        for (stream, cut) in config.get_starting_stream_cuts() {
+            let segments = match cut {
+                StreamCutVersioned::V1(cut) => cut
+                    .get_positions()
+                    .into_iter()
+                    .map(|(segment, offset)| (segment.segment, offset))
+                    .collect::<HashMap<Segment, i64>>(),
+                StreamCutVersioned::UNBOUNDED => client_factory
+                    .get_controller_client()
+                    .get_head_segments(&stream)
+                    .await
+                    .map_err(|err| ReaderGroupStateError::StateControllerError {
+                        error_msg: format!("get head segments of stream {}", stream),
+                        err,
+                    })?
+                    .into_iter()
+                    .collect::<HashMap<Segment, i64>>(),
+            };
+            init_segments
+                .extend(ReaderGroup::get_segments_with_range(&stream, segments, client_factory).await?);
+        }
+        Ok(init_segments)
+    }
+
+    // The key range of a segment is not part of the head segments or the stream cut, it is
+    // looked up from the epoch the segment was created in.
+    async fn get_segments_with_range(
+        stream: &ScopedStream,
+        segments: HashMap<Segment, i64>,
+        client_factory: &ClientFactory,
+    ) -> Result<HashMap<SegmentWithRange, Offset>, ReaderGroupStateError> {
+        let mut segments_by_epoch = HashMap::new();
+        let mut result = HashMap::new();
+        for (segment, offset) in segments {
+            let epoch = (segment.number >> 32) as i32;
+            let epoch_segments = match segments_by_epoch.entry(epoch) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    client_factory
+                        .get_controller_client()
+                        .get_epoch_segments(stream, epoch)
+                        .await
+                        .map_err(|err| ReaderGroupStateError::StateControllerError {
+                            error_msg: format!("get segments of epoch {} of stream {}", epoch, stream),
+                            err,
+                        })?,
+                ),
+            };
+            let segment_with_range = epoch_segments
+                .key_segment_map
+                .values()
+                .find(|s| s.scoped_segment.segment == segment)
+                .ok_or(ReaderGroupStateError::SegmentNotInEpoch {
+                    segment: segment.number,
+                    epoch,
+                })?
+                .to_owned();
+            result.insert(segment_with_range, Offset::new(offset as u64, offset as u64));
+        }
+        Ok(result)
+    }
+
+    /// Creates a new reader in this reader group. The reader is added to the online readers
+    /// and acquires its share of the unassigned segments.
+    pub async fn create_reader(&self, reader_id: String) -> Result<EventReader, EventReaderError> {
+        let reader = Reader::from(reader_id);
+        self.state
+            .lock()
+            .await
+            .add_reader(&reader)
+            .await
+            .context(ReaderStateError {
+                operation: "add reader to the reader group",
+            })?;
+        Ok(EventReader::init_reader(
+            reader,
+            self.state.clone(),
+            Duration::from_millis(self.config.get_group_refresh_time_millis()),
+            self.client_factory.clone(),
+        )
+        .await)
+    }
+
+    /// Creates a new reader in this reader group which reads events of type T, the events are
+    /// deserialized using the given serializer.
+    pub async fn create_typed_reader<T>(
+        &self,
+        reader_id: String,
+        serializer: Arc<dyn Serializer<T>>,
+    ) -> Result<TypedEventReader<T>, EventReaderError> {
+        let reader = self.create_reader(reader_id).await?;
+        Ok(TypedEventReader::new(reader, serializer))
+    }
+
+    /// Creates a new reader in this reader group which resumes reading the segments of the given
+    /// position at the offsets of the position, e.g. the position of a reader with the same id
+    /// before it was restarted. If the reader is still online it is put offline at the given
+    /// position first. The segments of the position which are owned by other readers are skipped.
+    pub async fn create_reader_from_position(
+        &self,
+        reader_id: String,
+        position: PositionVersioned,
+    ) -> Result<EventReader, EventReaderError> {
+        let reader = Reader::from(reader_id);
+        let mut state = self.state.lock().await;
+        let online_readers = state.get_online_readers().await.context(ReaderStateError {
+            operation: "get online readers",
+        })?;
+        if online_readers.contains(&reader) {
+            state
+                .reader_offline(&reader, Some(position.clone()))
+                .await
+                .context(ReaderStateError {
+                    operation: "put reader offline",
+                })?;
+        }
+        state.add_reader(&reader).await.context(ReaderStateError {
+            operation: "add reader to the reader group",
+        })?;
+        state
+            .claim_segments(&reader, &position.get_segments_with_offsets())
+            .await
+            .context(ReaderStateError {
+                operation: "assign the segments of the position to the reader",
+            })?;
+        drop(state);
+        Ok(EventReader::init_reader(
+            reader,
+            self.state.clone(),
+            Duration::from_millis(self.config.get_group_refresh_time_millis()),
+            self.client_factory.clone(),
+        )
+        .await)
+    }
+
+    /// Returns the name of the reader group.
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the scope of the reader group.
+    pub fn get_scope(&self) -> Scope {
+        self.scope.clone()
+    }
+
+    /// Returns the online readers of the reader group.
+    pub async fn get_online_readers(&self) -> Result<Vec<Reader>, ReaderGroupStateError> {
+        self.state.lock().await.get_online_readers().await
+    }
+
+    /// Resets the reader group to the given config. The readers stop reading their current segments
+    /// and resume from the starting stream cuts of the config, which may be taken from a prior
+    /// checkpoint using `ReaderGroupConfigV1::start_from_checkpoint`. The checkpoints in progress
+    /// are discarded.
+    pub async fn reset_to(
+        &mut self,
+        config: ReaderGroupConfigVersioned,
+    ) -> Result<(), ReaderGroupStateError> {
+        let segments = ReaderGroup::get_starting_segments(&config, &self.client_factory).await?;
+        info!("reset reader group {} to segments {:?}", self.name, segments);
+        self.state.lock().await.reset(&config, &segments).await?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Initiates a checkpoint with the given name. The returned future completes once all the online
+    /// readers have passed the checkpoint, i.e. each of them has returned the checkpoint marker
+    /// from `EventReader::acquire_segment`. The name of the checkpoint should be unique.
+    ///
+    /// The checkpoint is removed from the reader group if the returned future is dropped before it
+    /// completes, e.g. when it is wrapped in a timeout, or if it fails.
+    pub async fn initiate_checkpoint(&self, name: String) -> Result<Checkpoint, ReaderGroupStateError> {
+        self.state.lock().await.create_checkpoint(&name).await?;
+        info!("checkpoint {} of reader group {} is initiated", name, self.name);
+        let guard = CheckpointGuard {
+            name: Some(name.clone()),
+            state: self.state.clone(),
+            runtime_handle: self.client_factory.get_runtime_handle(),
+        };
+        let poll_interval = Duration::from_millis(self.config.get_checkpoint_poll_interval_millis());
+        loop {
+            let completed = self.state.lock().await.get_completed_checkpoint(&name).await?;
+            if let Some(positions) = completed {
+                self.state.lock().await.remove_checkpoint(&name).await?;
+                guard.disarm();
+                info!("checkpoint {} of reader group {} is completed", name, self.name);
+                return Ok(Checkpoint::new(name, positions));
+            }
+            debug!("waiting for all the readers to pass checkpoint {}", name);
+            delay_for(poll_interval).await;
+        }
+    }
+}
+
+// Removes an initiated checkpoint from the reader group state when it is dropped before the
+// checkpoint completes, so that an abandoned checkpoint does not stay in the reader group forever.
+struct CheckpointGuard {
+    name: Option<String>,
+    state: Arc<Mutex<ReaderGroupState>>,
+    runtime_handle: Handle,
+}
+
+impl CheckpointGuard {
+    fn disarm(mut self) {
+        self.name = None;
+    }
+}
+
+impl Drop for CheckpointGuard {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            let state = self.state.clone();
+            self.runtime_handle.spawn(async move {
+                if let Err(e) = state.lock().await.remove_checkpoint(&name).await {
+                    warn!("failed to remove abandoned checkpoint {}: {:?}", name, e);
+                }
+            });
+        }
+    }
+}
+
+/// A checkpoint of a reader group. It holds a consistent position of the reader group in each
+/// of the streams it reads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    name: String,
+    positions: HashMap<ScopedStream, StreamCutVersioned>,
+}
+
+impl Checkpoint {
+    fn new(name: String, segment_offsets: HashMap<ScopedSegment, i64>) -> Self {
+        let mut stream_offsets = HashMap::new();
+        for (segment, offset) in segment_offsets {
+            stream_offsets
+                .entry(ScopedStream::from(&segment))
+                .or_insert_with(HashMap::new)
+                .insert(segment, offset);
+        }
+        let positions = stream_offsets
+            .into_iter()
+            .map(|(stream, offsets)| {
+                let cut = StreamCutVersioned::V1(StreamCutV1::new(stream.clone(), offsets));
+                (stream, cut)
+            })
+            .collect();
+        Checkpoint { name, positions }
+    }
+
+    /// Returns the name of the checkpoint.
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the stream cut of each of the streams read by the reader group.
+    pub fn get_positions(&self) -> HashMap<ScopedStream, StreamCutVersioned> {
+        self.positions.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::create_mock_factory;
+    use pravega_rust_client_shared::{
+        Retention, RetentionType, ScaleType, Scaling, ScopedSegment, Segment, StreamConfiguration,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_reader_group_segment_distribution() {
+        let factory = create_mock_factory();
+        let scope = Scope::from("scope".to_owned());
+        let stream = ScopedStream::new(scope.clone(), Stream::from("stream".to_owned()));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, &stream, 2));
+
+        // events are written to both segments.
+        let mut writer = factory.create_event_stream_writer(stream.clone());
+        for _ in 0..50 {
+            handle.block_on(writer.write_event(vec![1; 8]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let rg_config = ReaderGroupConfigVersioned::V1(
+            ReaderGroupConfigV1::new()
+                .stream(stream, None, None)
+                .group_refresh_time_millis(10),
+        );
+        let reader_group = handle
+            .block_on(ReaderGroup::create(
+                scope,
+                "rg".to_owned(),
+                rg_config,
+                factory.clone(),
+            ))
+            .expect("create reader group");
+        let mut reader1 = handle
+            .block_on(reader_group.create_reader("reader1".to_owned()))
+            .expect("create reader");
+        let mut reader2 = handle
+            .block_on(reader_group.create_reader("reader2".to_owned()))
+            .expect("create reader");
+        let online_readers = handle
+            .block_on(reader_group.get_online_readers())
+            .expect("get online readers");
+        assert_eq!(online_readers.len(), 2);
+
+        // the first reader gives a segment back on its next refresh and the second reader acquires it.
+        for reader in [&mut reader1, &mut reader2].iter_mut() {
+            handle.block_on(async { delay_for(Duration::from_millis(10)).await });
+            let slice = handle
+                .block_on(reader.acquire_segment())
+                .expect("acquire segment")
+                .expect("segment slice");
+            reader.release_segment_at(slice);
+        }
+
+        let mut state = handle.block_on(reader_group.state.lock());
+        let segments = handle.block_on(state.get_segments()).expect("get segments");
+        assert_eq!(
+            segments.len(),
+            2,
+            "reader group should contain all segments of the stream"
+        );
+        let owned1 = handle
+            .block_on(state.get_segments_for_reader(&Reader::from("reader1".to_owned())))
+            .expect("get segments for reader");
+        let owned2 = handle
+            .block_on(state.get_segments_for_reader(&Reader::from("reader2".to_owned())))
+            .expect("get segments for reader");
+        assert!(!owned1.is_empty(), "each reader should own a segment");
+        assert!(!owned2.is_empty(), "each reader should own a segment");
+        assert!(
+            owned1.is_disjoint(&owned2),
+            "a segment is owned by a single reader"
+        );
+        assert_eq!(
+            owned1.union(&owned2).cloned().collect::<HashSet<ScopedSegment>>(),
+            segments,
+            "the readers should own all segments of the stream"
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_without_readers() {
+        let factory = create_mock_factory();
+        let scope = Scope::from("scope".to_owned());
+        let stream = ScopedStream::new(scope.clone(), Stream::from("stream".to_owned()));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, &stream, 2));
+
+        let reader_group = handle
+            .block_on(factory.create_reader_group(scope, "rg".to_owned(), stream.clone()))
+            .expect("create reader group");
+        let checkpoint = handle
+            .block_on(reader_group.initiate_checkpoint("cp".to_owned()))
+            .expect("initiate checkpoint");
+        assert_eq!(checkpoint.get_name(), "cp");
+        let positions = checkpoint.get_positions();
+        match positions.get(&stream).expect("stream cut of the stream") {
+            StreamCutVersioned::V1(cut) => assert_eq!(
+                cut.get_positions().values().cloned().collect::<Vec<i64>>(),
+                vec![0, 0],
+                "checkpoint should contain the unassigned segments"
+            ),
+            StreamCutVersioned::UNBOUNDED => panic!("checkpoint should contain bounded stream cuts"),
+        }
+    }
+
+    #[test]
+    fn test_abandoned_checkpoint_is_removed() {
+        let factory = create_mock_factory();
+        let scope = Scope::from("scope".to_owned());
+        let stream = ScopedStream::new(scope.clone(), Stream::from("stream".to_owned()));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, &stream, 2));
+
+        let rg_config = ReaderGroupConfigVersioned::V1(
+            ReaderGroupConfigV1::new()
+                .stream(stream, None, None)
+                .checkpoint_poll_interval_millis(10),
+        );
+        let reader_group = handle
+            .block_on(ReaderGroup::create(
+                scope,
+                "rg".to_owned(),
+                rg_config,
+                factory.clone(),
+            ))
+            .expect("create reader group");
+        // the reader never passes the checkpoint, so the checkpoint cannot complete.
+        let _reader1 = handle
+            .block_on(reader_group.create_reader("reader1".to_owned()))
+            .expect("create reader");
+
+        let res = handle.block_on(async {
+            tokio::time::timeout(
+                Duration::from_millis(100),
+                reader_group.initiate_checkpoint("cp".to_owned()),
+            )
+            .await
+        });
+        assert!(res.is_err(), "checkpoint should not complete");
+
+        let mut removed = false;
+        for _ in 0..100 {
+            let completed = handle.block_on(async {
+                reader_group
+                    .state
+                    .lock()
+                    .await
+                    .get_completed_checkpoint("cp")
+                    .await
+            });
+            if completed.is_err() {
+                removed = true;
+                break;
+            }
+            handle.block_on(async { delay_for(Duration::from_millis(10)).await });
+        }
+        assert!(
+            removed,
+            "abandoned checkpoint should be removed from the reader group"
+        );
+    }
+
+    #[test]
+    fn test_reset_reader_group() {
+        let factory = create_mock_factory();
+        let scope = Scope::from("scope".to_owned());
+        let stream = ScopedStream::new(scope.clone(), Stream::from("stream".to_owned()));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, &stream, 2));
+
+        let mut writer = factory.create_event_stream_writer(stream.clone());
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .try_init();
+        for i in 0..6 {
+            handle.block_on(writer.write_event(vec![i; 8]));
+        }
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let mut reader_group = handle
+            .block_on(factory.create_reader_group(scope, "rg".to_owned(), stream.clone()))
+            .expect("create reader group");
+        let reader = Reader::from("reader1".to_owned());
+        let mut reader1 = handle
+            .block_on(reader_group.create_reader(reader.name.clone()))
+            .expect("create reader");
+        // the reader is in the middle of a segment when the reader group is reset.
+        let mut slice = handle
+            .block_on(reader1.acquire_segment())
+            .expect("acquire segment")
+            .expect("segment slice");
+        slice.next().expect("read event");
+        reader1.release_segment_at(slice);
+
+        let rg_config =
+            ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new().stream(stream.clone(), None, None));
+        handle
+            .block_on(reader_group.reset_to(rg_config.clone()))
+            .expect("reset reader group");
+        assert_eq!(reader_group.config, rg_config);
+
+        {
+            let mut state = handle.block_on(reader_group.state.lock());
+            assert_eq!(
+                handle.block_on(state.get_segments()).expect("get segments").len(),
+                2
+            );
+            assert!(
+                handle
+                    .block_on(state.get_segments_for_reader(&reader))
+                    .expect("get segments for reader")
+                    .is_empty(),
+                "segments assigned to the reader should be cleared"
+            );
+        }
+
+        // the segments are sealed before the reader refreshes its segments, it finds out the
+        // segment it completes is no longer assigned to it and reads the stream again from the head.
+        for segment in 0..2 {
+            let segment = ScopedSegment {
+                scope: stream.scope.clone(),
+                stream: stream.stream.clone(),
+                segment: Segment::from(segment),
+            };
+            let metadata_client = handle.block_on(factory.create_segment_metadata_client(segment));
+            handle
+                .block_on(metadata_client.seal_segment())
+                .expect("seal segment");
+        }
+        let mut events = HashSet::new();
+        while let Some(mut slice) = handle
+            .block_on(reader1.acquire_segment())
+            .expect("acquire segment")
+        {
+            for event in &mut slice {
+                events.insert(event.value);
+            }
+            reader1.release_segment_at(slice);
+        }
+        assert_eq!(events, (0..6).map(|i| vec![i; 8]).collect::<HashSet<Vec<u8>>>());
+    }
+
+    #[test]
+    fn test_create_reader_from_position() {
+        let factory = create_mock_factory();
+        let scope = Scope::from("scope".to_owned());
+        let stream = ScopedStream::new(scope.clone(), Stream::from("stream".to_owned()));
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, &stream, 2));
+
+        // the writer sets up both segments on the mock segment store.
+        let mut writer = factory.create_event_stream_writer(stream.clone());
+        let rx = handle.block_on(writer.write_event(vec![1; 8]));
+        handle.block_on(rx).expect("receive ack").expect("write event");
+
+        let reader_group = handle
+            .block_on(factory.create_reader_group(scope, "rg".to_owned(), stream))
+            .expect("create reader group");
+        let mut reader1 = handle
+            .block_on(reader_group.create_reader("reader1".to_owned()))
+            .expect("create reader");
+        let position = reader1.get_position();
+        assert_eq!(position.get_segments_with_offsets().len(), 2);
+
+        // the reader restarts without going offline.
+        let _reader1 = handle
+            .block_on(reader_group.create_reader_from_position("reader1".to_owned(), position.clone()))
+            .expect("create reader");
+        let mut state = handle.block_on(reader_group.state.lock());
+        let owned = handle
+            .block_on(state.get_reader_positions(&Reader::from("reader1".to_owned())))
+            .expect("get reader positions")
+            .into_iter()
+            .map(|(segment, offset)| (segment, offset.read as i64))
+            .collect::<HashMap<SegmentWithRange, i64>>();
+        assert_eq!(owned, position.get_segments_with_offsets());
+    }
+
+    async fn create_stream(factory: &ClientFactory, stream: &ScopedStream, segments: i32) {
+        let controller = factory.get_controller_client();
+        controller.create_scope(&stream.scope).await.unwrap();
+        controller
+            .create_stream(&StreamConfiguration {
+                scoped_stream: stream.clone(),
+                scaling: Scaling {
+                    scale_type: ScaleType::FixedNumSegments,
+                    target_rate: 0,
+                    scale_factor: 0,
+                    min_num_segments: segments,
+                },
+                retention: Retention {
+                    retention_type: RetentionType::None,
+                    retention_param: 0,
+                },
+            })
+            .await
+            .unwrap();
+    }
+}
@@ -8,6 +8,7 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 use crate::error::*;
+use crate::reader_group::Checkpoint;
 use crate::stream::stream_cut::StreamCutVersioned;
 use pravega_rust_client_shared::ScopedStream;
 use serde::{Deserialize, Serialize};
@@ -18,11 +19,35 @@ use std::collections::HashMap;
 
 /// ReaderGroupConfigVersioned enum contains all versions of Position struct
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) enum ReaderGroupConfigVersioned {
+pub enum ReaderGroupConfigVersioned {
     V1(ReaderGroupConfigV1),
 }
 
 impl ReaderGroupConfigVersioned {
+    pub(crate) fn get_group_refresh_time_millis(&self) -> u64 {
+        match self {
+            ReaderGroupConfigVersioned::V1(v1) => v1.group_refresh_time_millis,
+        }
+    }
+
+    pub(crate) fn get_checkpoint_poll_interval_millis(&self) -> u64 {
+        match self {
+            ReaderGroupConfigVersioned::V1(v1) => v1.checkpoint_poll_interval_millis,
+        }
+    }
+
+    pub(crate) fn get_starting_stream_cuts(&self) -> HashMap<ScopedStream, StreamCutVersioned> {
+        match self {
+            ReaderGroupConfigVersioned::V1(v1) => v1.starting_stream_cuts.clone(),
+        }
+    }
+
+    pub(crate) fn get_ending_stream_cuts(&self) -> HashMap<ScopedStream, StreamCutVersioned> {
+        match self {
+            ReaderGroupConfigVersioned::V1(v1) => v1.ending_stream_cuts.clone(),
+        }
+    }
+
     fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
         let encoded = to_vec(&self).context(Cbor {
             msg: "serialize ReaderGroupConfigVersioned".to_owned(),
@@ -39,24 +64,30 @@ impl ReaderGroupConfigVersioned {
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) struct ReaderGroupConfigV1 {
+pub struct ReaderGroupConfigV1 {
     /// maximum delay by which the readers return the latest read offsets of their
     /// assigned segments.
     group_refresh_time_millis: u64,
+    /// interval at which the completion of an initiated checkpoint is polled.
+    #[serde(default = "default_checkpoint_poll_interval_millis")]
+    checkpoint_poll_interval_millis: u64,
     starting_stream_cuts: HashMap<ScopedStream, StreamCutVersioned>,
     ending_stream_cuts: HashMap<ScopedStream, StreamCutVersioned>,
 }
 
 impl ReaderGroupConfigV1 {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         ReaderGroupConfigV1 {
             group_refresh_time_millis: 3000,
+            checkpoint_poll_interval_millis: default_checkpoint_poll_interval_millis(),
             starting_stream_cuts: HashMap::new(),
             ending_stream_cuts: HashMap::new(),
         }
     }
 
-    pub(crate) fn stream(
+    /// Adds a stream to the reader group config. The stream is read from the starting stream cut
+    /// up to the ending stream cut, the stream is unbounded if a cut is not given.
+    pub fn stream(
         mut self,
         stream: ScopedStream,
         starting_stream_cuts: Option<StreamCutVersioned>,
@@ -79,13 +110,43 @@ impl ReaderGroupConfigV1 {
         self
     }
 
-    pub(crate) fn start_from_stream_cuts(
+    /// Starts reading the streams from the given stream cuts.
+    pub fn start_from_stream_cuts(
         mut self,
         stream_cuts: HashMap<ScopedStream, StreamCutVersioned>,
     ) -> ReaderGroupConfigV1 {
         self.starting_stream_cuts = stream_cuts;
         self
     }
+
+    /// Sets the interval in milliseconds at which the readers refresh the reader group state, they
+    /// then report the latest read offsets of their assigned segments and rebalance the segments.
+    pub fn group_refresh_time_millis(mut self, millis: u64) -> ReaderGroupConfigV1 {
+        self.group_refresh_time_millis = millis;
+        self
+    }
+
+    /// Sets the interval in milliseconds at which the completion of an initiated checkpoint is polled.
+    pub fn checkpoint_poll_interval_millis(mut self, millis: u64) -> ReaderGroupConfigV1 {
+        self.checkpoint_poll_interval_millis = millis;
+        self
+    }
+
+    /// Starts reading the streams from the positions recorded by the given checkpoint.
+    pub fn start_from_checkpoint(mut self, checkpoint: &Checkpoint) -> ReaderGroupConfigV1 {
+        self.starting_stream_cuts = checkpoint.get_positions();
+        self
+    }
+}
+
+fn default_checkpoint_poll_interval_millis() -> u64 {
+    1000
+}
+
+impl Default for ReaderGroupConfigV1 {
+    fn default() -> Self {
+        ReaderGroupConfigV1::new()
+    }
 }
 
 #[cfg(test)]
@@ -11,22 +11,32 @@
 use crate::client_factory::ClientFactory;
 use crate::error::*;
 use crate::reader_group::reader_group_config::ReaderGroupConfigVersioned;
+use crate::stream::position::PositionVersioned;
+use crate::stream::stream_cut::StreamCutVersioned;
 use crate::table_synchronizer::{deserialize_from, Table, TableSynchronizer, Value};
+use pravega_controller_client::ControllerError;
+use pravega_rust_client_retry::retry_result::RetryError;
 use pravega_rust_client_shared::{Reader, ScopedSegment, ScopedStream, Segment, SegmentWithRange};
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use snafu::{ensure, Snafu};
+use std::cmp;
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
+use std::time::{Duration, Instant};
 use tracing::warn;
 
 const ASSUMED_LAG_MILLIS: u64 = 30000;
 const DEFAULT_INNER_KEY: &str = "default";
 
+const CONFIG: &str = "config";
+
 const ASSIGNED: &str = "assigned_segments";
 const UNASSIGNED: &str = "unassigned_segments";
 const FUTURE: &str = "future_segments";
 const DISTANCE: &str = "distance_to_tail";
+const HEARTBEAT: &str = "reader_heartbeats";
+const CHECKPOINTS: &str = "checkpoints";
 
 #[derive(Debug, Snafu)]
 pub enum ReaderGroupStateError {
@@ -35,6 +45,18 @@ pub enum ReaderGroupStateError {
         error_msg: String,
         source: SynchronizerError,
     },
+
+    #[snafu(display("Controller error while performing {}: {:?}", error_msg, err))]
+    StateControllerError {
+        error_msg: String,
+        err: RetryError<ControllerError>,
+    },
+
+    #[snafu(display("Segment number {} does not belong to epoch {} of its stream", segment, epoch))]
+    SegmentNotInEpoch { segment: i64, epoch: i32 },
+
+    #[snafu(display("Segment {} is not assigned to reader {}", segment, reader))]
+    SegmentNotOwned { segment: String, reader: String },
 }
 
 /// ReaderGroupState encapsulates all readers states.
@@ -52,6 +74,10 @@ pub(crate) struct ReaderGroupState {
     /// This is used to balance the workload among readers in this reader group.
     /// distance_to_tail: HashMap<Reader, u64>
     ///
+    /// The number of heartbeats each online reader has sent to report it is alive. This is used
+    /// to detect the readers that went offline without notifying the reader group.
+    /// reader_heartbeats: HashMap<Reader, u64>
+    ///
     /// Maps successor segments to their predecessors. A successor segment will ready to be
     /// read if all its predecessors have been read.
     /// future_segments: HashMap<SegmentWithRange, HashSet<i64>>
@@ -61,7 +87,15 @@ pub(crate) struct ReaderGroupState {
     ///
     /// Segments waiting to be assigned to readers.
     /// unassigned_segments: HashMap<SegmentWithRange, Offset>
+    ///
+    /// Checkpoints in progress, keyed by the name of the checkpoint.
+    /// checkpoints: HashMap<String, CheckpointState>
     sync: TableSynchronizer,
+
+    /// The heartbeat count of each reader observed by this client and the local time it was
+    /// first observed. The clocks of the readers are not compared, a reader is offline once its
+    /// heartbeat count has not changed for the timeout as measured by this client.
+    observed_heartbeats: HashMap<String, (u64, Instant)>,
 }
 
 impl ReaderGroupState {
@@ -70,9 +104,12 @@ impl ReaderGroupState {
         client_facotry: &ClientFactory,
         config: ReaderGroupConfigVersioned,
         segments_to_offsets: HashMap<SegmentWithRange, Offset>,
-    ) -> ReaderGroupState {
+    ) -> Result<ReaderGroupState, ReaderGroupStateError> {
         let mut sync = client_facotry
-            .create_table_synchronizer("ReaderGroupState".to_owned())
+            .create_table_synchronizer(format!(
+                "{}-{}",
+                scoped_synchronizer_stream.scope, scoped_synchronizer_stream.stream
+            ))
             .await;
         sync.insert(move |table| {
             if table.is_empty() {
@@ -83,7 +120,7 @@ impl ReaderGroupState {
                     Box::new(scoped_synchronizer_stream.clone()),
                 );
                 table.insert(
-                    "config".to_owned(),
+                    CONFIG.to_owned(),
                     DEFAULT_INNER_KEY.to_owned(),
                     "ReaderGroupConfigVersioned".to_owned(),
                     Box::new(config.clone()),
@@ -100,8 +137,21 @@ impl ReaderGroupState {
             Ok(None)
         })
         .await
-        .expect("should initialize table synchronizer");
-        ReaderGroupState { sync }
+        .context(SyncError {
+            error_msg: "initialize reader group state",
+        })?;
+        Ok(ReaderGroupState {
+            sync,
+            observed_heartbeats: HashMap::new(),
+        })
+    }
+
+    // Fetches the latest reader group state from the server.
+    async fn fetch_updates(&mut self) -> Result<(), SynchronizerError> {
+        self.sync.fetch_updates().await.context(SyncTableError {
+            operation: "fetch updates",
+        })?;
+        Ok(())
     }
 
     /// Adds a reader to the reader group state.
@@ -135,18 +185,116 @@ impl ReaderGroupState {
         );
 
         table.insert(
-            "distance_to_tail".to_owned(),
+            DISTANCE.to_owned(),
             reader.to_string(),
             "u64".to_owned(),
             Box::new(u64::MAX),
         );
+
+        table.insert(
+            HEARTBEAT.to_owned(),
+            reader.to_string(),
+            "u64".to_owned(),
+            Box::new(0u64),
+        );
+        Ok(None)
+    }
+
+    /// Records that the given reader is alive.
+    pub(crate) async fn update_heartbeat(&mut self, reader: &Reader) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::update_heartbeat_internal(table, reader))
+            .await
+            .context(SyncError {
+                error_msg: format!("update heartbeat of reader {:?}", reader),
+            })?;
+        Ok(())
+    }
+
+    fn update_heartbeat_internal(
+        table: &mut Table,
+        reader: &Reader,
+    ) -> Result<Option<String>, SynchronizerError> {
+        ReaderGroupState::check_reader_online(&table.get_inner_map(ASSIGNED), reader)?;
+        let count: u64 = table
+            .get_inner_map(HEARTBEAT)
+            .get(&reader.to_string())
+            .map_or(0, |v| deserialize_from(&v.data).expect("deserialize heartbeat"));
+        table.insert(
+            HEARTBEAT.to_owned(),
+            reader.to_string(),
+            "u64".to_owned(),
+            Box::new(count.wrapping_add(1)),
+        );
+        Ok(None)
+    }
+
+    /// Puts the readers which have not reported a heartbeat within the given timeout offline.
+    /// The segments owned by those readers are put to the unassigned list at the offsets last
+    /// recorded in the reader group state.
+    pub(crate) async fn remove_offline_readers(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), ReaderGroupStateError> {
+        let now = Instant::now();
+        let observed = &mut self.observed_heartbeats;
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::remove_offline_readers_internal(table, observed, now, timeout))
+            .await
+            .context(SyncError {
+                error_msg: "remove offline readers".to_owned(),
+            })?;
+        Ok(())
+    }
+
+    fn remove_offline_readers_internal(
+        table: &mut Table,
+        observed: &mut HashMap<String, (u64, Instant)>,
+        now: Instant,
+        timeout: Duration,
+    ) -> Result<Option<String>, SynchronizerError> {
+        let heartbeats = table.get_inner_map(HEARTBEAT);
+        observed.retain(|reader, _| heartbeats.contains_key(reader));
+        let mut offline_readers = vec![];
+        for (reader, v) in heartbeats {
+            let count: u64 = deserialize_from(&v.data).expect("deserialize heartbeat");
+            match observed.get(&reader) {
+                Some((last_count, since)) if *last_count == count => {
+                    if now.saturating_duration_since(*since) > timeout {
+                        offline_readers.push(Reader::from(reader));
+                    }
+                }
+                _ => {
+                    observed.insert(reader, (count, now));
+                }
+            }
+        }
+
+        for reader in offline_readers {
+            warn!(
+                "reader {:?} has not sent heartbeat in {:?}, put it offline",
+                reader, timeout
+            );
+            observed.remove(&reader.to_string());
+            if table.contains_key(ASSIGNED, &reader.to_string()) {
+                ReaderGroupState::remove_reader_internal(table, &reader, &HashMap::new())?;
+            } else {
+                table.insert_tombstone(HEARTBEAT.to_owned(), reader.to_string())?;
+            }
+        }
         Ok(None)
     }
 
     /// Returns the active readers in a vector.
-    pub(crate) async fn get_online_readers(&mut self) -> Vec<Reader> {
-        self.sync.fetch_updates().await.expect("should fetch updates");
-        ReaderGroupState::get_online_readers_internal(self.sync.get_inner_map(ASSIGNED))
+    pub(crate) async fn get_online_readers(&mut self) -> Result<Vec<Reader>, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: "get online readers",
+        })?;
+        Ok(ReaderGroupState::get_online_readers_internal(
+            self.sync.get_inner_map(ASSIGNED),
+        ))
     }
 
     fn get_online_readers_internal(assigned_segments: HashMap<String, Value>) -> Vec<Reader> {
@@ -161,7 +309,7 @@ impl ReaderGroupState {
         &mut self,
         reader: &Reader,
     ) -> Result<HashMap<SegmentWithRange, Offset>, SynchronizerError> {
-        self.sync.fetch_updates().await.expect("should fetch updates");
+        self.fetch_updates().await?;
         ReaderGroupState::get_reader_positions_internal(reader, self.sync.get_inner_map(ASSIGNED))
     }
 
@@ -254,11 +402,13 @@ impl ReaderGroupState {
     ) -> Result<Option<String>, SynchronizerError> {
         let assigned_segments = ReaderGroupState::get_reader_owned_segments_from_table(table, reader)?;
 
+        let mut released_positions = HashMap::new();
         for (segment, pos) in assigned_segments {
             // update offset using owned_segments
             let offset = owned_segments
                 .get(&segment.scoped_segment)
                 .map_or(pos, |v| v.to_owned());
+            released_positions.insert(segment.scoped_segment.clone(), offset.read as i64);
 
             table.insert(
                 UNASSIGNED.to_owned(),
@@ -267,14 +417,46 @@ impl ReaderGroupState {
                 Box::new(offset),
             );
         }
+        // the checkpoints this reader has not passed record the offsets of the released segments.
+        ReaderGroupState::add_to_pending_checkpoints(table, reader, &released_positions, true);
+
         table.insert_tombstone(ASSIGNED.to_owned(), reader.to_string())?;
         table.insert_tombstone(DISTANCE.to_owned(), reader.to_string())?;
+        if table.contains_key(HEARTBEAT, &reader.to_string()) {
+            table.insert_tombstone(HEARTBEAT.to_owned(), reader.to_string())?;
+        }
         Ok(None)
     }
 
+    /// Puts the given reader offline. The segments owned by the reader are put to the unassigned
+    /// list at the offsets of its last position, or at the offsets last recorded in the reader
+    /// group state if the last position is unknown.
+    pub(crate) async fn reader_offline(
+        &mut self,
+        reader: &Reader,
+        last_position: Option<PositionVersioned>,
+    ) -> Result<(), ReaderGroupStateError> {
+        let owned_segments = last_position.map_or_else(HashMap::new, |position| {
+            position
+                .get_segments_with_offsets()
+                .into_iter()
+                .map(|(segment, offset)| (segment.scoped_segment, Offset::new(offset as u64, offset as u64)))
+                .collect::<HashMap<ScopedSegment, Offset>>()
+        });
+        self.remove_reader(reader, owned_segments).await
+    }
+
+    /// Returns true if the given reader is online according to the reader group state last
+    /// fetched from the server.
+    pub(crate) fn is_reader_online(&self, reader: &Reader) -> bool {
+        ReaderGroupState::check_reader_online(&self.sync.get_inner_map(ASSIGNED), reader).is_ok()
+    }
+
     /// Returns the list of all segments.
-    pub(crate) async fn get_segments(&mut self) -> HashSet<ScopedSegment> {
-        self.sync.fetch_updates().await.expect("should fetch updates");
+    pub(crate) async fn get_segments(&mut self) -> Result<HashSet<ScopedSegment>, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: "get segments",
+        })?;
 
         let assigned_segments = self.sync.get_inner_map(ASSIGNED);
         let unassigned_segments = self.sync.get_inner_map(UNASSIGNED);
@@ -301,14 +483,117 @@ impl ReaderGroupState {
                 })
                 .collect::<HashSet<ScopedSegment>>(),
         );
-        set
+        Ok(set)
+    }
+
+    /// Updates the distance to tail of the given reader, which is the number of bytes the reader
+    /// has yet to read from its assigned segments.
+    pub(crate) async fn update_distance_to_tail(
+        &mut self,
+        reader: &Reader,
+        distance: u64,
+    ) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::update_distance_to_tail_internal(table, reader, distance))
+            .await
+            .context(SyncError {
+                error_msg: format!("update distance to tail of reader {:?} to {}", reader, distance),
+            })?;
+        Ok(())
+    }
+
+    fn update_distance_to_tail_internal(
+        table: &mut Table,
+        reader: &Reader,
+        distance: u64,
+    ) -> Result<Option<String>, SynchronizerError> {
+        ReaderGroupState::check_reader_online(&table.get_inner_map(ASSIGNED), reader)?;
+        table.insert(
+            DISTANCE.to_owned(),
+            reader.to_string(),
+            "u64".to_owned(),
+            Box::new(distance),
+        );
+        Ok(None)
+    }
+
+    /// Computes the number of segments the given reader should acquire or release so that the
+    /// segments are evenly distributed among the online readers. A positive value is the number
+    /// of unassigned segments to acquire and a negative value is the number of owned segments
+    /// to release.
+    pub(crate) async fn compute_segments_to_acquire_or_release(
+        &mut self,
+        reader: &Reader,
+    ) -> Result<isize, SynchronizerError> {
+        self.fetch_updates().await?;
+        ReaderGroupState::compute_segments_to_acquire_or_release_internal(
+            reader,
+            self.sync.get_inner_map(ASSIGNED),
+            self.sync.get_inner_map(UNASSIGNED),
+            self.sync.get_inner_map(DISTANCE),
+        )
+    }
+
+    fn compute_segments_to_acquire_or_release_internal(
+        reader: &Reader,
+        assigned_segments: HashMap<String, Value>,
+        unassigned_segments: HashMap<String, Value>,
+        distance_to_tail: HashMap<String, Value>,
+    ) -> Result<isize, SynchronizerError> {
+        ReaderGroupState::check_reader_online(&assigned_segments, reader)?;
+
+        let mut total_segments = unassigned_segments.len();
+        let mut owned_by_reader = 0;
+        for (r, v) in &assigned_segments {
+            let segments: HashMap<SegmentWithRange, Offset> =
+                deserialize_from(&v.data).expect("deserialize assigned segments");
+            total_segments += segments.len();
+            if *r == reader.to_string() {
+                owned_by_reader = segments.len();
+            }
+        }
+
+        let num_readers = assigned_segments.len();
+        let min_share = total_segments / num_readers;
+        let remainder = total_segments % num_readers;
+        let max_share = if remainder == 0 { min_share } else { min_share + 1 };
+
+        // an overloaded reader gives up the segments exceeding the maximum share.
+        if owned_by_reader > max_share {
+            return Ok(-((owned_by_reader - max_share) as isize));
+        }
+
+        // rank the readers by their distance to tail, the readers which are the least behind
+        // are entitled to the remaining segments.
+        let mut ranking = assigned_segments
+            .keys()
+            .map(|r| {
+                let distance = distance_to_tail.get(r).map_or(u64::MAX, |v| {
+                    deserialize_from(&v.data).expect("deserialize distance")
+                });
+                (distance, r.to_owned())
+            })
+            .collect::<Vec<(u64, String)>>();
+        ranking.sort();
+        let rank = ranking
+            .iter()
+            .position(|(_distance, r)| *r == reader.to_string())
+            .expect("reader should be ranked");
+        let fair_share = if rank < remainder { max_share } else { min_share };
+
+        Ok(cmp::min(
+            fair_share.saturating_sub(owned_by_reader),
+            unassigned_segments.len(),
+        ) as isize)
     }
 
-    /// Assigns an unassigned segment to a given reader
+    /// Assigns an unassigned segment to a given reader. Returns the assigned segment along
+    /// with the offset the reader should start reading from.
     pub(crate) async fn assign_segment_to_reader(
         &mut self,
         reader: &Reader,
-    ) -> Result<Option<ScopedSegment>, ReaderGroupStateError> {
+    ) -> Result<Option<(SegmentWithRange, Offset)>, ReaderGroupStateError> {
         let option = self
             .sync
             .insert(|table| ReaderGroupState::assign_segment_to_reader_internal(table, reader))
@@ -318,7 +603,16 @@ impl ReaderGroupState {
             })?;
 
         if let Some(segment_str) = option {
-            Ok(Some(ScopedSegment::from(&*segment_str)))
+            let segment = SegmentWithRange::from(&*segment_str);
+            let mut owned_segments =
+                ReaderGroupState::get_reader_positions_internal(reader, self.sync.get_inner_map(ASSIGNED))
+                    .context(SyncError {
+                        error_msg: format!("get positions of reader {:?}", reader),
+                    })?;
+            let offset = owned_segments
+                .remove(&segment)
+                .expect("assigned segment should be owned by the reader");
+            Ok(Some((segment, offset)))
         } else {
             Ok(None)
         }
@@ -354,7 +648,161 @@ impl ReaderGroupState {
         );
         table.insert_tombstone(UNASSIGNED.to_owned(), segment.to_string())?;
 
-        Ok(Some(segment.scoped_segment.to_string()))
+        Ok(Some(segment.to_string()))
+    }
+
+    /// Assigns the given segments to the reader at the given offsets. Only the segments which are
+    /// currently unassigned are assigned, the others are skipped.
+    pub(crate) async fn claim_segments(
+        &mut self,
+        reader: &Reader,
+        segments: &HashMap<SegmentWithRange, i64>,
+    ) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::claim_segments_internal(table, reader, segments))
+            .await
+            .context(SyncError {
+                error_msg: format!("assign segments {:?} to reader {:?}", segments, reader),
+            })?;
+        Ok(())
+    }
+
+    fn claim_segments_internal(
+        table: &mut Table,
+        reader: &Reader,
+        segments: &HashMap<SegmentWithRange, i64>,
+    ) -> Result<Option<String>, SynchronizerError> {
+        let mut assigned_segments = ReaderGroupState::get_reader_owned_segments_from_table(table, reader)?;
+        let unassigned_segments = ReaderGroupState::get_unassigned_segments_from_table(table);
+
+        for (segment, offset) in segments {
+            if unassigned_segments.contains_key(segment) {
+                assigned_segments.insert(segment.to_owned(), Offset::new(*offset as u64, *offset as u64));
+                table.insert_tombstone(UNASSIGNED.to_owned(), segment.to_string())?;
+            } else {
+                warn!(
+                    "segment {:?} is not unassigned, it cannot be assigned to reader {:?}",
+                    segment, reader
+                );
+            }
+        }
+        table.insert(
+            ASSIGNED.to_owned(),
+            reader.to_string(),
+            "HashMap<SegmentWithRange, Offset>".to_owned(),
+            Box::new(assigned_segments),
+        );
+        Ok(None)
+    }
+
+    /// Resets the reader group state to the given config. The unassigned segments are replaced by
+    /// the given segments, while the segments assigned to the online readers, the future segments
+    /// and the checkpoints in progress are cleared.
+    pub(crate) async fn reset(
+        &mut self,
+        config: &ReaderGroupConfigVersioned,
+        segments_to_offsets: &HashMap<SegmentWithRange, Offset>,
+    ) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::reset_internal(table, config, segments_to_offsets))
+            .await
+            .context(SyncError {
+                error_msg: format!("reset reader group state to {:?}", config),
+            })?;
+        Ok(())
+    }
+
+    fn reset_internal(
+        table: &mut Table,
+        config: &ReaderGroupConfigVersioned,
+        segments_to_offsets: &HashMap<SegmentWithRange, Offset>,
+    ) -> Result<Option<String>, SynchronizerError> {
+        // a key must not be removed and inserted again in the same update, so the unassigned
+        // segments which stay unassigned are only overwritten.
+        let segments: HashSet<String> = segments_to_offsets.keys().map(|s| s.to_string()).collect();
+        for outer_key in &[UNASSIGNED, FUTURE, CHECKPOINTS] {
+            for inner_key in table.get_inner_map(outer_key).keys() {
+                if *outer_key == UNASSIGNED && segments.contains(inner_key) {
+                    continue;
+                }
+                table.insert_tombstone((*outer_key).to_owned(), inner_key.to_owned())?;
+            }
+        }
+        for reader in table.get_inner_map(ASSIGNED).keys() {
+            table.insert(
+                ASSIGNED.to_owned(),
+                reader.to_owned(),
+                "HashMap<SegmentWithRange, Offset>".to_owned(),
+                Box::new(HashMap::<SegmentWithRange, Offset>::new()),
+            );
+        }
+        for (segment, offset) in segments_to_offsets {
+            table.insert(
+                UNASSIGNED.to_owned(),
+                segment.to_string(),
+                "Offset".to_owned(),
+                Box::new(offset.to_owned()),
+            );
+        }
+        table.insert(
+            CONFIG.to_owned(),
+            DEFAULT_INNER_KEY.to_owned(),
+            "ReaderGroupConfigVersioned".to_owned(),
+            Box::new(config.clone()),
+        );
+        Ok(None)
+    }
+
+    /// Returns the end offsets of the segments which are bounded by the ending stream cuts of the
+    /// reader group config. The segments not present are read until they are sealed.
+    pub(crate) async fn get_end_offsets(
+        &mut self,
+    ) -> Result<HashMap<ScopedSegment, i64>, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: "get end offsets",
+        })?;
+        let value = self
+            .sync
+            .get(CONFIG, DEFAULT_INNER_KEY)
+            .expect("reader group state should contain the config");
+        let config: ReaderGroupConfigVersioned = deserialize_from(&value.data).expect("deserialize config");
+        Ok(config
+            .get_ending_stream_cuts()
+            .values()
+            .flat_map(|cut| match cut {
+                StreamCutVersioned::V1(v1) => v1.get_positions(),
+                StreamCutVersioned::UNBOUNDED => HashMap::new(),
+            })
+            .collect::<HashMap<ScopedSegment, i64>>())
+    }
+
+    /// Returns true if all the segments of the reader group have been read, i.e. there are
+    /// no segments assigned to readers nor waiting to be assigned.
+    pub(crate) async fn is_end_of_data(&mut self) -> Result<bool, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: "check end of data",
+        })?;
+        Ok(ReaderGroupState::is_end_of_data_internal(
+            &self.sync.get_inner_map(ASSIGNED),
+            &self.sync.get_inner_map(UNASSIGNED),
+            &self.sync.get_inner_map(FUTURE),
+        ))
+    }
+
+    fn is_end_of_data_internal(
+        assigned: &HashMap<String, Value>,
+        unassigned: &HashMap<String, Value>,
+        future: &HashMap<String, Value>,
+    ) -> bool {
+        unassigned.is_empty()
+            && future.is_empty()
+            && assigned.values().all(|v| {
+                let segments: HashMap<SegmentWithRange, Offset> =
+                    deserialize_from(&v.data).expect("deserialize assigned segments");
+                segments.is_empty()
+            })
     }
 
     /// Returns the list of segments assigned to the requested reader.
@@ -362,7 +810,7 @@ impl ReaderGroupState {
         &mut self,
         reader: &Reader,
     ) -> Result<HashSet<ScopedSegment>, SynchronizerError> {
-        self.sync.fetch_updates().await.expect("should fetch updates");
+        self.fetch_updates().await?;
         let value =
             self.sync
                 .get(ASSIGNED, &reader.to_string())
@@ -459,13 +907,16 @@ impl ReaderGroupState {
     /// This should be called by the reader who's reading the current segment. Since a segment
     /// cannot be read by multiple readers, we can assume this won't be called by multiple processors
     /// at the same time.
+    /// Returns a SegmentNotOwned error if the segment is no longer assigned to the reader, e.g. it was
+    /// released by a reset of the reader group or reclaimed from a reader put offline, and the state
+    /// is left unchanged.
     pub(crate) async fn segment_completed(
         &mut self,
         reader: &Reader,
         segment_completed: &SegmentWithRange,
         successors_mapped_to_their_predecessors: &HashMap<SegmentWithRange, Vec<Segment>>,
     ) -> Result<(), ReaderGroupStateError> {
-        let _res_str = self
+        let res_str = self
             .sync
             .insert(|table| {
                 ReaderGroupState::segment_completed_internal(
@@ -482,6 +933,13 @@ impl ReaderGroupState {
                     segment_completed, reader
                 ),
             })?;
+        ensure!(
+            res_str.is_some(),
+            SegmentNotOwned {
+                segment: segment_completed.to_string(),
+                reader: reader.to_string(),
+            }
+        );
         Ok(())
     }
 
@@ -491,13 +949,18 @@ impl ReaderGroupState {
         segment_completed: &SegmentWithRange,
         successors_mapped_to_their_predecessors: &HashMap<SegmentWithRange, Vec<Segment>>,
     ) -> Result<Option<String>, SynchronizerError> {
+        // the segments of a reader put offline have been reclaimed by the reader group.
+        if !table.contains_key(ASSIGNED, &reader.to_string()) {
+            return Ok(None);
+        }
         let mut assigned_segments = ReaderGroupState::get_reader_owned_segments_from_table(table, reader)?;
         let mut future_segments = ReaderGroupState::get_future_segments_from_table(table);
 
-        // remove completed segment from assigned_segment list
-        assigned_segments
-            .remove(segment_completed)
-            .expect("should have assigned this segment to reader");
+        // remove completed segment from assigned_segment list, nothing is updated if the segment
+        // has been taken away from the reader in the meantime.
+        if assigned_segments.remove(segment_completed).is_none() {
+            return Ok(None);
+        }
         table.insert(
             ASSIGNED.to_owned(),
             reader.to_string(),
@@ -541,6 +1004,14 @@ impl ReaderGroupState {
             .map(|(segment, _set)| segment.to_owned())
             .collect::<Vec<SegmentWithRange>>();
 
+        // the successors are not part of the position of this reader, so the checkpoints this reader
+        // has not passed record them.
+        let ready_positions = ready_to_read
+            .iter()
+            .map(|segment| (segment.scoped_segment.clone(), 0))
+            .collect::<HashMap<ScopedSegment, i64>>();
+        ReaderGroupState::add_to_pending_checkpoints(table, reader, &ready_positions, false);
+
         for segment in ready_to_read {
             // add ready to read segments to unassigned_segments
             table.insert(
@@ -552,9 +1023,208 @@ impl ReaderGroupState {
             // remove those from the future_segments
             table.insert_tombstone(FUTURE.to_owned(), segment.to_string())?;
         }
+        Ok(Some(segment_completed.to_string()))
+    }
+
+    /// Creates a checkpoint. The checkpoint records the offsets of the currently unassigned segments
+    /// and completes once all the online readers have recorded their positions.
+    pub(crate) async fn create_checkpoint(&mut self, name: &str) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::create_checkpoint_internal(table, name))
+            .await
+            .context(SyncError {
+                error_msg: format!("create checkpoint {}", name),
+            })?;
+        Ok(())
+    }
+
+    fn create_checkpoint_internal(
+        table: &mut Table,
+        name: &str,
+    ) -> Result<Option<String>, SynchronizerError> {
+        ensure!(
+            !table.contains_key(CHECKPOINTS, name),
+            SyncUpdateError {
+                error_msg: format!("Failed to create checkpoint: checkpoint {} already exists", name)
+            }
+        );
+        let sequence = ReaderGroupState::get_checkpoints_from_table(table)
+            .values()
+            .map(|checkpoint| checkpoint.sequence + 1)
+            .max()
+            .unwrap_or(0);
+        let pending_readers = HashSet::from_iter(ReaderGroupState::get_online_readers_internal(
+            table.get_inner_map(ASSIGNED),
+        ));
+        let positions = ReaderGroupState::get_unassigned_segments_from_table(table)
+            .into_iter()
+            .map(|(segment, offset)| (segment.scoped_segment, offset.read as i64))
+            .collect::<HashMap<ScopedSegment, i64>>();
+        table.insert(
+            CHECKPOINTS.to_owned(),
+            name.to_owned(),
+            "CheckpointState".to_owned(),
+            Box::new(CheckpointState::new(sequence, pending_readers, positions)),
+        );
+        Ok(None)
+    }
+
+    /// Returns the name of the oldest checkpoint the given reader has not passed yet.
+    pub(crate) async fn get_checkpoint_for_reader(
+        &mut self,
+        reader: &Reader,
+    ) -> Result<Option<String>, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: format!("get checkpoint for reader {:?}", reader),
+        })?;
+        Ok(ReaderGroupState::get_checkpoint_for_reader_internal(
+            self.sync.get_inner_map(CHECKPOINTS),
+            reader,
+        ))
+    }
+
+    fn get_checkpoint_for_reader_internal(
+        checkpoints: HashMap<String, Value>,
+        reader: &Reader,
+    ) -> Option<String> {
+        checkpoints
+            .iter()
+            .map(|(name, v)| {
+                let checkpoint: CheckpointState = deserialize_from(&v.data).expect("deserialize checkpoint");
+                (name, checkpoint)
+            })
+            .filter(|(_name, checkpoint)| checkpoint.pending_readers.contains(reader))
+            .min_by_key(|(_name, checkpoint)| checkpoint.sequence)
+            .map(|(name, _checkpoint)| name.to_owned())
+    }
+
+    /// Records the position of the given reader in the checkpoint, the reader has passed the
+    /// checkpoint after this.
+    pub(crate) async fn checkpoint(
+        &mut self,
+        reader: &Reader,
+        name: &str,
+        positions: &HashMap<ScopedSegment, i64>,
+    ) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| ReaderGroupState::checkpoint_internal(table, reader, name, positions))
+            .await
+            .context(SyncError {
+                error_msg: format!("reader {:?} passes checkpoint {}", reader, name),
+            })?;
+        Ok(())
+    }
+
+    fn checkpoint_internal(
+        table: &mut Table,
+        reader: &Reader,
+        name: &str,
+        positions: &HashMap<ScopedSegment, i64>,
+    ) -> Result<Option<String>, SynchronizerError> {
+        let mut checkpoint = ReaderGroupState::get_checkpoints_from_table(table)
+            .remove(name)
+            .ok_or(SynchronizerError::SyncUpdateError {
+                error_msg: format!("Failed to pass checkpoint: checkpoint {} does not exist", name),
+            })?;
+        ensure!(
+            checkpoint.pending_readers.remove(reader),
+            SyncUpdateError {
+                error_msg: format!(
+                    "Failed to pass checkpoint: reader {:?} is not pending on checkpoint {}",
+                    reader, name
+                )
+            }
+        );
+        checkpoint.positions.extend(positions.clone());
+        table.insert(
+            CHECKPOINTS.to_owned(),
+            name.to_owned(),
+            "CheckpointState".to_owned(),
+            Box::new(checkpoint),
+        );
         Ok(None)
     }
 
+    /// Returns the offsets recorded by the checkpoint if all the readers have passed it, otherwise
+    /// returns None.
+    pub(crate) async fn get_completed_checkpoint(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<HashMap<ScopedSegment, i64>>, ReaderGroupStateError> {
+        self.fetch_updates().await.context(SyncError {
+            error_msg: format!("get checkpoint {}", name),
+        })?;
+        let value = self
+            .sync
+            .get(CHECKPOINTS, name)
+            .ok_or(SynchronizerError::SyncUpdateError {
+                error_msg: format!("checkpoint {} does not exist", name),
+            })
+            .context(SyncError {
+                error_msg: format!("get checkpoint {}", name),
+            })?;
+        let checkpoint: CheckpointState = deserialize_from(&value.data).expect("deserialize checkpoint");
+        if checkpoint.pending_readers.is_empty() {
+            Ok(Some(checkpoint.positions))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Removes the checkpoint from the reader group state.
+    pub(crate) async fn remove_checkpoint(&mut self, name: &str) -> Result<(), ReaderGroupStateError> {
+        let _res_str = self
+            .sync
+            .insert(|table| {
+                table.insert_tombstone(CHECKPOINTS.to_owned(), name.to_owned())?;
+                Ok(None)
+            })
+            .await
+            .context(SyncError {
+                error_msg: format!("remove checkpoint {}", name),
+            })?;
+        Ok(())
+    }
+
+    // Adds the given offsets to the checkpoints the reader has not passed yet. If pass is set the
+    // reader no longer blocks those checkpoints.
+    fn add_to_pending_checkpoints(
+        table: &mut Table,
+        reader: &Reader,
+        positions: &HashMap<ScopedSegment, i64>,
+        pass: bool,
+    ) {
+        for (name, mut checkpoint) in ReaderGroupState::get_checkpoints_from_table(table) {
+            if checkpoint.pending_readers.contains(reader) {
+                checkpoint.positions.extend(positions.clone());
+                if pass {
+                    checkpoint.pending_readers.remove(reader);
+                }
+                table.insert(
+                    CHECKPOINTS.to_owned(),
+                    name,
+                    "CheckpointState".to_owned(),
+                    Box::new(checkpoint),
+                );
+            }
+        }
+    }
+
+    fn get_checkpoints_from_table(table: &mut Table) -> HashMap<String, CheckpointState> {
+        table
+            .get_inner_map(CHECKPOINTS)
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_owned(),
+                    deserialize_from(&v.data).expect("deserialize checkpoint"),
+                )
+            })
+            .collect::<HashMap<String, CheckpointState>>()
+    }
+
     fn get_reader_owned_segments_from_table(
         table: &mut Table,
         reader: &Reader,
@@ -617,15 +1287,27 @@ pub(crate) struct Offset {
     /// But some events before this offset may not have been processed by application/caller.
     /// In case of failure, those unprocessed events may need to be read from application/caller
     /// again.
-    read: u64,
+    pub(crate) read: u64,
     /// The application/caller has processed up to this offset, this is less than or equal to the
     /// read offset.
-    processed: u64,
+    pub(crate) processed: u64,
+}
+
+#[derive(new, Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub(crate) struct CheckpointState {
+    /// The order in which the checkpoints were created, readers pass the checkpoints in this order.
+    sequence: u64,
+    /// The readers which have not passed the checkpoint yet.
+    pending_readers: HashSet<Reader>,
+    /// The offsets of the segments recorded by the checkpoint so far.
+    positions: HashMap<ScopedSegment, i64>,
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::create_mock_factory;
+    use crate::reader_group::reader_group_config::ReaderGroupConfigV1;
     use crate::table_synchronizer::{serialize, Value};
     use lazy_static::*;
     use ordered_float::OrderedFloat;
@@ -826,4 +1508,348 @@ mod test {
 
         assert_eq!(table.get_inner_map(UNASSIGNED).len(), 2);
     }
+
+    #[test]
+    fn test_compute_segments_to_acquire_or_release() {
+        let mut table = set_up();
+        let reader2 = Reader::from("test2".to_owned());
+        let mut segment1 = SEGMENT_WITH_RANGE.clone();
+        segment1.scoped_segment.segment.number = 1;
+        table.insert(
+            UNASSIGNED.to_owned(),
+            segment1.to_string(),
+            "Offset".to_owned(),
+            Box::new(Offset::new(0, 0)),
+        );
+
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        let compute = |table: &Table, reader: &Reader| {
+            ReaderGroupState::compute_segments_to_acquire_or_release_internal(
+                reader,
+                table.get_inner_map(ASSIGNED),
+                table.get_inner_map(UNASSIGNED),
+                table.get_inner_map(DISTANCE),
+            )
+            .expect("compute segments to acquire or release")
+        };
+        assert_eq!(
+            compute(&table, &READER),
+            2,
+            "single reader should acquire all segments"
+        );
+
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+
+        // a new reader joins, the first reader is overloaded and releases a segment.
+        ReaderGroupState::add_reader_internal(&mut table, &reader2).expect("add reader");
+        assert_eq!(
+            compute(&table, &READER),
+            -1,
+            "overloaded reader should release a segment"
+        );
+        assert_eq!(
+            compute(&table, &reader2),
+            0,
+            "no unassigned segment is left to acquire"
+        );
+
+        let owned = ReaderGroupState::get_reader_positions_internal(&READER, table.get_inner_map(ASSIGNED))
+            .expect("get reader positions");
+        let released = owned.keys().next().expect("get owned segment").to_owned();
+        ReaderGroupState::release_segment_internal(
+            &mut table,
+            &READER,
+            &released.scoped_segment,
+            &Offset::new(10, 10),
+        )
+        .expect("release segment");
+        assert_eq!(compute(&table, &READER), 0, "reader owns its fair share");
+        assert_eq!(
+            compute(&table, &reader2),
+            1,
+            "idle reader should acquire the released segment"
+        );
+    }
+
+    #[test]
+    fn test_reader_ranking_by_distance_to_tail() {
+        let mut table = set_up();
+        let reader2 = Reader::from("test2".to_owned());
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::add_reader_internal(&mut table, &reader2).expect("add reader");
+
+        // the reader which is the least behind is entitled to the single segment.
+        ReaderGroupState::update_distance_to_tail_internal(&mut table, &READER, 100)
+            .expect("update distance to tail");
+        ReaderGroupState::update_distance_to_tail_internal(&mut table, &reader2, 10)
+            .expect("update distance to tail");
+        let compute = |reader: &Reader| {
+            ReaderGroupState::compute_segments_to_acquire_or_release_internal(
+                reader,
+                table.get_inner_map(ASSIGNED),
+                table.get_inner_map(UNASSIGNED),
+                table.get_inner_map(DISTANCE),
+            )
+            .expect("compute segments to acquire or release")
+        };
+        assert_eq!(compute(&READER), 0);
+        assert_eq!(compute(&reader2), 1);
+    }
+
+    #[test]
+    fn test_remove_offline_readers() {
+        let mut table = set_up();
+        let reader2 = Reader::from("test2".to_owned());
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::add_reader_internal(&mut table, &reader2).expect("add reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+
+        let mut observed = HashMap::new();
+        let start = Instant::now();
+        let timeout = Duration::from_secs(5);
+        ReaderGroupState::remove_offline_readers_internal(&mut table, &mut observed, start, timeout)
+            .expect("remove offline readers");
+        assert_eq!(observed.len(), 2, "heartbeats of both readers should be observed");
+
+        // only the second reader sends heartbeats while the timeout passes.
+        ReaderGroupState::update_heartbeat_internal(&mut table, &reader2).expect("update heartbeat");
+        ReaderGroupState::remove_offline_readers_internal(
+            &mut table,
+            &mut observed,
+            start + Duration::from_secs(3),
+            timeout,
+        )
+        .expect("remove offline readers");
+        assert_eq!(
+            ReaderGroupState::get_online_readers_internal(table.get_inner_map(ASSIGNED)).len(),
+            2,
+            "readers should be online before the timeout"
+        );
+        ReaderGroupState::update_heartbeat_internal(&mut table, &reader2).expect("update heartbeat");
+        ReaderGroupState::remove_offline_readers_internal(
+            &mut table,
+            &mut observed,
+            start + Duration::from_secs(6),
+            timeout,
+        )
+        .expect("remove offline readers");
+
+        let online_readers = ReaderGroupState::get_online_readers_internal(table.get_inner_map(ASSIGNED));
+        assert_eq!(
+            online_readers,
+            vec![reader2],
+            "reader without recent heartbeat should be offline"
+        );
+        assert!(
+            table.contains_key(UNASSIGNED, &SEGMENT_WITH_RANGE.to_string()),
+            "segment owned by the offline reader should be unassigned"
+        );
+        assert!(!table.contains_key(HEARTBEAT, &READER.to_string()));
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut table = set_up();
+        let reader2 = Reader::from("test2".to_owned());
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::add_reader_internal(&mut table, &reader2).expect("add reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+
+        ReaderGroupState::create_checkpoint_internal(&mut table, "cp1").expect("create checkpoint");
+        ReaderGroupState::create_checkpoint_internal(&mut table, "cp2").expect("create checkpoint");
+        assert!(ReaderGroupState::create_checkpoint_internal(&mut table, "cp1").is_err());
+        assert_eq!(
+            ReaderGroupState::get_checkpoint_for_reader_internal(table.get_inner_map(CHECKPOINTS), &READER),
+            Some("cp1".to_owned()),
+            "readers should pass the oldest checkpoint first"
+        );
+
+        let mut positions = HashMap::new();
+        positions.insert(SEGMENT.clone(), 10);
+        ReaderGroupState::checkpoint_internal(&mut table, &READER, "cp1", &positions).expect("checkpoint");
+        assert!(ReaderGroupState::checkpoint_internal(&mut table, &READER, "cp1", &positions).is_err());
+        assert_eq!(
+            ReaderGroupState::get_checkpoint_for_reader_internal(table.get_inner_map(CHECKPOINTS), &READER),
+            Some("cp2".to_owned())
+        );
+
+        // the offline reader no longer blocks the checkpoint.
+        ReaderGroupState::remove_reader_internal(&mut table, &reader2, &HashMap::new())
+            .expect("remove reader");
+        let checkpoint = ReaderGroupState::get_checkpoints_from_table(&mut table)
+            .remove("cp1")
+            .expect("get checkpoint");
+        assert!(checkpoint.pending_readers.is_empty());
+        assert_eq!(checkpoint.positions, positions);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut table = set_up();
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+        ReaderGroupState::create_checkpoint_internal(&mut table, "cp").expect("create checkpoint");
+
+        let mut segments = HashMap::new();
+        segments.insert(SEGMENT_WITH_RANGE.clone(), Offset::new(10, 10));
+        let config = ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new());
+        ReaderGroupState::reset_internal(&mut table, &config, &segments).expect("reset");
+
+        assert_eq!(
+            ReaderGroupState::get_online_readers_internal(table.get_inner_map(ASSIGNED)),
+            vec![READER.clone()],
+            "readers should stay online after reset"
+        );
+        assert!(
+            ReaderGroupState::get_reader_owned_segments_from_table(&mut table, &READER)
+                .expect("get owned segments")
+                .is_empty()
+        );
+        assert_eq!(
+            ReaderGroupState::get_unassigned_segments_from_table(&mut table),
+            segments
+        );
+        assert!(ReaderGroupState::get_checkpoints_from_table(&mut table).is_empty());
+    }
+
+    #[test]
+    fn test_complete_segment_no_longer_assigned() {
+        let mut table = set_up();
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+
+        // the assignment is removed by a reset while the reader is still reading the segment.
+        let mut segments = HashMap::new();
+        segments.insert(SEGMENT_WITH_RANGE.clone(), Offset::new(10, 10));
+        let config = ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new());
+        ReaderGroupState::reset_internal(&mut table, &config, &segments).expect("reset");
+
+        let mut successor = SEGMENT_WITH_RANGE.clone();
+        successor.scoped_segment.segment.number = 1;
+        let mut successors_mapped_to_their_predecessors = HashMap::new();
+        successors_mapped_to_their_predecessors.insert(
+            successor,
+            vec![Segment {
+                number: 0,
+                tx_id: None,
+            }],
+        );
+        let res = ReaderGroupState::segment_completed_internal(
+            &mut table,
+            &READER,
+            &SEGMENT_WITH_RANGE,
+            &successors_mapped_to_their_predecessors,
+        )
+        .expect("complete segment");
+        assert!(res.is_none(), "segment is no longer owned by the reader");
+        assert_eq!(
+            ReaderGroupState::get_unassigned_segments_from_table(&mut table),
+            segments
+        );
+        assert!(ReaderGroupState::get_future_segments_from_table(&mut table).is_empty());
+    }
+
+    #[test]
+    fn test_complete_segment_of_offline_reader() {
+        let mut table = set_up();
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+        ReaderGroupState::remove_reader_internal(&mut table, &READER, &HashMap::new())
+            .expect("remove reader");
+
+        // the slow reader completes the segment after it was reclaimed.
+        let res = ReaderGroupState::segment_completed_internal(
+            &mut table,
+            &READER,
+            &SEGMENT_WITH_RANGE,
+            &HashMap::new(),
+        )
+        .expect("complete segment");
+        assert!(res.is_none(), "segment is no longer owned by the reader");
+        assert!(table.contains_key(UNASSIGNED, &SEGMENT_WITH_RANGE.to_string()));
+    }
+
+    #[test]
+    fn test_reset_with_unassigned_segments() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        let mut segments = HashMap::new();
+        segments.insert(SEGMENT_WITH_RANGE.clone(), Offset::new(0, 0));
+        let config = ReaderGroupConfigVersioned::V1(ReaderGroupConfigV1::new());
+        let mut state = handle
+            .block_on(ReaderGroupState::new(
+                ScopedStream::from("scope/reset"),
+                &factory,
+                config.clone(),
+                segments.clone(),
+            ))
+            .expect("create reader group state");
+
+        // the segment is still unassigned when the state is reset to it, it is updated once
+        // instead of being removed and inserted again.
+        let key = Some(SEGMENT_WITH_RANGE.to_string());
+        let version = state.sync.get_key_version(UNASSIGNED, &key);
+        handle.block_on(state.reset(&config, &segments)).expect("reset");
+        let segments = handle.block_on(state.get_segments()).expect("get segments");
+        assert_eq!(segments, HashSet::from_iter(vec![SEGMENT.clone()]));
+        assert_eq!(state.sync.get_key_version(UNASSIGNED, &key), version + 1);
+    }
+
+    #[test]
+    fn test_end_of_data() {
+        let mut table = set_up();
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        let is_end_of_data = |table: &Table| {
+            ReaderGroupState::is_end_of_data_internal(
+                &table.get_inner_map(ASSIGNED),
+                &table.get_inner_map(UNASSIGNED),
+                &table.get_inner_map(FUTURE),
+            )
+        };
+        assert!(!is_end_of_data(&table), "segment is not assigned yet");
+
+        ReaderGroupState::assign_segment_to_reader_internal(&mut table, &READER)
+            .expect("assign segment to reader");
+        assert!(!is_end_of_data(&table), "segment is being read");
+
+        ReaderGroupState::segment_completed_internal(
+            &mut table,
+            &READER,
+            &SEGMENT_WITH_RANGE,
+            &HashMap::new(),
+        )
+        .expect("complete segment");
+        assert!(is_end_of_data(&table), "all segments have been read");
+    }
+
+    #[test]
+    fn test_claim_segments() {
+        let mut table = set_up();
+        let reader2 = Reader::from("test2".to_owned());
+        ReaderGroupState::add_reader_internal(&mut table, &READER).expect("add reader");
+        ReaderGroupState::add_reader_internal(&mut table, &reader2).expect("add reader");
+
+        let mut segments = HashMap::new();
+        segments.insert(SEGMENT_WITH_RANGE.clone(), 100);
+        ReaderGroupState::claim_segments_internal(&mut table, &READER, &segments).expect("claim segments");
+        let owned = ReaderGroupState::get_reader_owned_segments_from_table(&mut table, &READER)
+            .expect("get owned segments");
+        assert_eq!(owned.get(&SEGMENT_WITH_RANGE), Some(&Offset::new(100, 100)));
+
+        // the segment is owned by the other reader.
+        ReaderGroupState::claim_segments_internal(&mut table, &reader2, &segments).expect("claim segments");
+        assert!(
+            ReaderGroupState::get_reader_owned_segments_from_table(&mut table, &reader2)
+                .expect("get owned segments")
+                .is_empty()
+        );
+    }
 }
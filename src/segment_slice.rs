@@ -9,13 +9,18 @@
 //
 
 use crate::client_factory::ClientFactory;
+use crate::error::SerdeError;
 use crate::event_reader::SegmentReadResult;
 use crate::segment_reader::AsyncSegmentReader;
 use crate::segment_reader::ReaderError::SegmentSealed;
+use crate::serializer::Serializer;
+use crate::stream::event_pointer::{EventPointerV1, EventPointerVersioned};
 use bytes::{Buf, BufMut, BytesMut};
 use pravega_rust_client_retry::retry_result::Retryable;
 use pravega_rust_client_shared::ScopedSegment;
 use pravega_wire_protocol::commands::{Command, EventCommand, TYPE_PLUS_LENGTH_SIZE};
+use std::cmp;
+use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::error::TryRecvError;
@@ -29,6 +34,7 @@ use tracing::{debug, info, warn};
 pub struct Event {
     pub offset_in_segment: i64,
     pub value: Vec<u8>,
+    pub event_pointer: EventPointerVersioned,
 }
 
 ///
@@ -38,6 +44,7 @@ pub struct Event {
 pub struct SegmentSlice {
     pub meta: SliceMetadata,
     pub(crate) slice_return_tx: Option<oneshot::Sender<SliceMetadata>>,
+    pub(crate) checkpoint: Option<String>,
 }
 
 ///
@@ -152,10 +159,30 @@ impl SliceMetadata {
     }
 
     ///
-    /// Method to verify if the Segment has pending events that can be read.
+    /// Returns the offset in the segment up to which the events have been consumed.
+    ///
+    pub(crate) fn get_consumed_offset(&self) -> i64 {
+        if self.segment_data.segment.is_empty() {
+            // no data has been received for this slice.
+            self.start_offset
+        } else {
+            self.segment_data.offset_in_segment
+        }
+    }
+
+    ///
+    /// Method to verify if the Segment has pending events that can be read, that is if the
+    /// buffered data holds at least one complete event.
     ///
     pub fn has_events(&self) -> bool {
-        self.segment_data.value.len() > TYPE_PLUS_LENGTH_SIZE as usize
+        let data = &self.segment_data.value;
+        if data.len() <= TYPE_PLUS_LENGTH_SIZE as usize {
+            return false;
+        }
+        let mut header = &data[..TYPE_PLUS_LENGTH_SIZE as usize];
+        header.get_i32();
+        let len = header.get_i32() as usize;
+        data.len() >= len + TYPE_PLUS_LENGTH_SIZE as usize
     }
 }
 
@@ -177,6 +204,7 @@ impl Default for SegmentSlice {
         SegmentSlice {
             meta: Default::default(),
             slice_return_tx: None,
+            checkpoint: None,
         }
     }
 }
@@ -202,34 +230,89 @@ impl SegmentSlice {
                 partial_data_present: false,
             },
             slice_return_tx: Some(slice_return_tx),
+            checkpoint: None,
         }
     }
 
     ///
-    /// Method to fetch data from the Segment store from a given start offset.
+    /// Create a checkpoint marker for the given checkpoint. A checkpoint marker contains no events.
+    ///
+    pub(crate) fn checkpoint_marker(checkpoint: String) -> Self {
+        SegmentSlice {
+            checkpoint: Some(checkpoint),
+            ..Default::default()
+        }
+    }
+
+    ///
+    /// Returns the name of the checkpoint if this SegmentSlice is a checkpoint marker. All the
+    /// events read by the reader before the marker precede the checkpoint.
+    ///
+    pub fn get_checkpoint_name(&self) -> Option<&str> {
+        self.checkpoint.as_deref()
+    }
+
+    ///
+    /// Returns true if this SegmentSlice is a checkpoint marker.
+    ///
+    pub fn is_checkpoint(&self) -> bool {
+        self.checkpoint.is_some()
+    }
+
+    ///
+    /// Method to fetch data from the Segment store from a given start offset up to the given end offset.
     ///
     pub(crate) async fn get_segment_data(
         segment: ScopedSegment,
         start_offset: i64,
+        end_offset: i64,
+        tx: Sender<SegmentReadResult>,
+        drop_fetch: oneshot::Receiver<()>,
+        factory: ClientFactory,
+    ) {
+        let segment_reader = factory.create_async_event_reader(segment.clone()).await;
+        SegmentSlice::read_segment_data(segment, &segment_reader, start_offset, end_offset, tx, drop_fetch)
+            .await;
+    }
+
+    async fn read_segment_data(
+        segment: ScopedSegment,
+        segment_reader: &impl AsyncSegmentReader,
+        start_offset: i64,
+        end_offset: i64,
         mut tx: Sender<SegmentReadResult>,
         mut drop_fetch: oneshot::Receiver<()>,
-        factory: ClientFactory,
     ) {
         let mut offset: i64 = start_offset;
-        let segment_reader = factory.create_async_event_reader(segment.clone()).await;
         loop {
             if let Ok(_) | Err(TryRecvError::Closed) = drop_fetch.try_recv() {
                 info!("Stop reading from the segment");
                 break;
             }
+            if offset >= end_offset {
+                info!("Reached end offset {} of segment {:?}", end_offset, segment);
+                let data = SegmentSealed {
+                    segment: segment.to_string(),
+                    can_retry: false,
+                    operation: "read segment".to_string(),
+                    error_msg: "reached the end offset of the segment".to_string(),
+                };
+                if let Err(e) = tx.send(Err(data)).await {
+                    info!("Error while sending segment data to event parser {:?} ", e);
+                }
+                break;
+            }
+            let length = cmp::min(READ_BUFFER_SIZE as i64, end_offset - offset) as i32;
             debug!(
                 "Send read request to Segment store at offset {:?} with length {:?}",
-                offset, READ_BUFFER_SIZE
+                offset, length
             );
-            let read = segment_reader.read(offset, READ_BUFFER_SIZE).await;
+            let read = segment_reader.read(offset, length).await;
             match read {
-                Ok(reply) => {
+                Ok(mut reply) => {
                     debug!("Read Response from Segment store {:?}", reply);
+                    // the data beyond the requested length may lie past the end offset.
+                    reply.data.truncate(length as usize);
                     let len = reply.data.len();
                     if len == 0 && reply.end_of_segment {
                         info!("Reached end of segment {:?} during read ", segment.clone());
@@ -313,9 +396,15 @@ impl SegmentSlice {
                     event_data.value.len()
                 );
                 //Convert to Event and send it.
+                let event_pointer = EventPointerVersioned::V1(EventPointerV1::new(
+                    ScopedSegment::from(self.meta.scoped_segment.as_str()),
+                    event_data.offset_in_segment,
+                    bytes_to_read as i32 + TYPE_PLUS_LENGTH_SIZE as i32,
+                ));
                 let event = Event {
                     offset_in_segment: event_data.offset_in_segment,
                     value: event_data.value.freeze().to_vec(),
+                    event_pointer,
                 };
                 Some(event)
             } else {
@@ -396,11 +485,53 @@ impl Iterator for SegmentSlice {
     }
 }
 
+///
+/// This represents a Segment slice whose events are deserialized to type T using the given
+/// serializer. It is returned by `TypedEventReader::acquire_segment`.
+///
+#[derive(new)]
+pub struct TypedSegmentSlice<T> {
+    slice: SegmentSlice,
+    serializer: Arc<dyn Serializer<T>>,
+}
+
+impl<T> TypedSegmentSlice<T> {
+    ///
+    /// Returns the underlying SegmentSlice.
+    ///
+    pub fn get_slice(&self) -> &SegmentSlice {
+        &self.slice
+    }
+
+    ///
+    /// Returns the underlying SegmentSlice, e.g. to release it to the EventReader.
+    ///
+    pub fn into_slice(self) -> SegmentSlice {
+        self.slice
+    }
+}
+
+///
+/// Iterator implementation of TypedSegmentSlice, an error is returned for the events which can not
+/// be deserialized.
+///
+impl<T> Iterator for TypedSegmentSlice<T> {
+    type Item = Result<T, SerdeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.slice.next()?;
+        Some(self.serializer.deserialize(&event.value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::segment_reader::ReaderError;
+    use crate::serializer::JsonSerializer;
     use bytes::{Buf, BufMut, BytesMut};
+    use pravega_wire_protocol::commands::SegmentReadCommand;
     use std::iter;
     use tokio::sync::mpsc;
     use tokio::sync::mpsc::Sender;
@@ -443,6 +574,14 @@ mod tests {
                 assert_eq!(expected_offset, d.offset_in_segment as usize);
                 assert_eq!(expected_event_len, d.value.len());
                 assert!(is_all_same(d.value.as_slice()));
+                assert_eq!(
+                    d.event_pointer,
+                    EventPointerVersioned::V1(EventPointerV1::new(
+                        ScopedSegment::from("test/test/123"),
+                        expected_offset as i64,
+                        (8 + expected_event_len) as i32,
+                    ))
+                );
                 expected_offset += 8 + expected_event_len;
                 expected_event_len += 1;
             }
@@ -478,6 +617,105 @@ mod tests {
         assert_eq!(200, expected_event_len);
     }
 
+    #[test]
+    fn test_typed_segment_slice() {
+        let serializer = JsonSerializer::<String>::new();
+        let mut segment_slice = create_segment_slice();
+        for event in &["event1", "event2"] {
+            let data = serializer.serialize(&event.to_string()).expect("serialize event");
+            segment_slice
+                .meta
+                .segment_data
+                .value
+                .put_i32(EventCommand::TYPE_CODE);
+            segment_slice.meta.segment_data.value.put_i32(data.len() as i32);
+            segment_slice.meta.segment_data.value.put(data.as_slice());
+        }
+        // an event which is not valid json.
+        segment_slice
+            .meta
+            .segment_data
+            .value
+            .put_i32(EventCommand::TYPE_CODE);
+        segment_slice.meta.segment_data.value.put_i32(1);
+        segment_slice.meta.segment_data.value.put_u8(b'{');
+
+        let events = TypedSegmentSlice::new(segment_slice, Arc::new(serializer)).collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].as_ref().expect("deserialize event"), "event1");
+        assert_eq!(events[1].as_ref().expect("deserialize event"), "event2");
+        assert!(events[2].is_err());
+    }
+
+    #[test]
+    fn test_has_events_with_partial_event() {
+        let mut segment_slice = create_segment_slice();
+        assert!(!segment_slice.meta.has_events());
+
+        // only part of a large event is buffered
+        let data = &mut segment_slice.meta.segment_data.value;
+        data.put_i32(EventCommand::TYPE_CODE);
+        data.put_i32(20 * 1024 * 1024);
+        data.put(vec![1; 1024].as_slice());
+        assert!(!segment_slice.meta.has_events());
+
+        let data = &mut segment_slice.meta.segment_data.value;
+        data.put(vec![1; 20 * 1024 * 1024 - 1024].as_slice());
+        assert!(segment_slice.meta.has_events());
+        let event = segment_slice.next().expect("read large event");
+        assert_eq!(event.value.len(), 20 * 1024 * 1024);
+        assert!(!segment_slice.meta.has_events());
+    }
+
+    #[tokio::test]
+    async fn test_read_segment_data_with_over_long_reply() {
+        let mut data = BytesMut::new();
+        for i in 1..4 {
+            data.put(event_data(i));
+        }
+        let data = data.to_vec();
+        // the end offset is after the second event.
+        let end_offset = 8 + 1 + 8 + 2;
+        let reader = OverLongSegmentReader { data: data.clone() };
+        let (tx, mut rx) = mpsc::channel(10);
+        let (_drop_tx, drop_rx) = oneshot::channel();
+        SegmentSlice::read_segment_data(
+            ScopedSegment::from("test/test/123"),
+            &reader,
+            0,
+            end_offset,
+            tx,
+            drop_rx,
+        )
+        .await;
+
+        let mut read = vec![];
+        while let Some(Ok(buf)) = rx.recv().await {
+            assert_eq!(buf.offset_in_segment, read.len() as i64);
+            read.extend_from_slice(&buf.value);
+        }
+        assert_eq!(read, data[..end_offset as usize].to_vec());
+    }
+
+    // A segment reader that returns all the data from the offset on, whatever the requested length.
+    struct OverLongSegmentReader {
+        data: Vec<u8>,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncSegmentReader for OverLongSegmentReader {
+        async fn read(&self, offset: i64, _length: i32) -> Result<SegmentReadCommand, ReaderError> {
+            Ok(SegmentReadCommand {
+                segment: "test/test/123".to_string(),
+                offset,
+                at_tail: false,
+                end_of_segment: offset as usize >= self.data.len(),
+                data: self.data[offset as usize..].to_vec(),
+                request_id: 0,
+            })
+        }
+    }
+
     // create a segment slice for testing.
     fn create_segment_slice() -> SegmentSlice {
         let segment = ScopedSegment::from("test/test/123");
@@ -491,6 +729,7 @@ mod tests {
                 partial_data_present: false,
             },
             slice_return_tx: None,
+            checkpoint: None,
         };
         segment_slice
     }
@@ -0,0 +1,289 @@
+//
+// Copyright (c) Dell Inc., or its subsidiaries. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+
+use crate::error::*;
+use serde::de::value::{Error as ValueError, SeqDeserializer};
+use serde::de::{DeserializeOwned, Deserializer, Error as _, Visitor};
+use serde::{forward_to_deserialize_any, Serialize};
+use serde_cbor::Value;
+use snafu::ResultExt;
+use std::marker::PhantomData;
+
+///
+/// Serializer converts the events of type T to the bytes written to a stream and converts the
+/// bytes read from a stream back to events. It is used by the typed writers and readers, see
+/// `TypedEventStreamWriter` and `TypedEventReader`.
+///
+pub trait Serializer<T>: Send + Sync {
+    /// Serializes the event to bytes.
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, SerdeError>;
+
+    /// Deserializes the event from bytes.
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, SerdeError>;
+}
+
+/// Serializes the events using CBOR.
+#[derive(new)]
+pub struct CborSerializer<T> {
+    #[new(default)]
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Serializer<T> for CborSerializer<T> {
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, SerdeError> {
+        serde_cbor::to_vec(value).context(Cbor {
+            msg: "serialize event".to_owned(),
+        })
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, SerdeError> {
+        serde_cbor::from_slice(bytes).context(Cbor {
+            msg: "deserialize event".to_owned(),
+        })
+    }
+}
+
+/// Serializes the events using JSON.
+#[derive(new)]
+pub struct JsonSerializer<T> {
+    #[new(default)]
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Serializer<T> for JsonSerializer<T> {
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, SerdeError> {
+        serde_json::to_vec(value).context(Json {
+            msg: "serialize event".to_owned(),
+        })
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, SerdeError> {
+        serde_json::from_slice(bytes).context(Json {
+            msg: "deserialize event".to_owned(),
+        })
+    }
+}
+
+/// Writes and reads the events as raw bytes.
+#[derive(new)]
+pub struct BytesSerializer {}
+
+impl Serializer<Vec<u8>> for BytesSerializer {
+    fn serialize(&self, value: &Vec<u8>) -> Result<Vec<u8>, SerdeError> {
+        Ok(value.clone())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Vec<u8>, SerdeError> {
+        Ok(bytes.to_vec())
+    }
+}
+
+///
+/// TableCodec converts the keys and values of a `TableMap` to the bytes stored in the table
+/// segment and back. Unlike a Serializer it is not bound to a type, since the keys and values
+/// of a table map can be of any type.
+///
+pub trait TableCodec: Send + Sync {
+    /// Encodes the key or value to bytes.
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, SerdeError>;
+
+    /// Decodes the key or value from bytes.
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, SerdeError>;
+}
+
+/// Encodes the keys and values using CBOR, it is the codec of a table map unless another
+/// one is given.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CborCodec;
+
+impl TableCodec for CborCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, SerdeError> {
+        serde_cbor::to_vec(value).context(Cbor {
+            msg: "encode table entry".to_owned(),
+        })
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, SerdeError> {
+        serde_cbor::from_slice(bytes).context(Cbor {
+            msg: "decode table entry".to_owned(),
+        })
+    }
+}
+
+/// Encodes the keys and values using JSON.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+impl TableCodec for JsonCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, SerdeError> {
+        serde_json::to_vec(value).context(Json {
+            msg: "encode table entry".to_owned(),
+        })
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, SerdeError> {
+        serde_json::from_slice(bytes).context(Json {
+            msg: "decode table entry".to_owned(),
+        })
+    }
+}
+
+/// Stores the keys and values as they are, so that tables written as raw bytes by other clients,
+/// e.g. with protobuf values, can be shared. The keys and values must be bytes or strings, such
+/// as `Vec<u8>` or `String`, and a string is stored as UTF-8.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RawCodec;
+
+impl TableCodec for RawCodec {
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, SerdeError> {
+        let msg = "encode table entry";
+        let value = serde_cbor::value::to_value(value).context(Cbor { msg: msg.to_owned() })?;
+        match value {
+            Value::Bytes(bytes) => Ok(bytes),
+            Value::Text(text) => Ok(text.into_bytes()),
+            // a Vec<u8> is serialized as a sequence of integers.
+            Value::Array(values) => values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Integer(byte) if byte >= 0 && byte <= u8::MAX as i128 => Ok(byte as u8),
+                    value => Err(SerdeError::Raw {
+                        msg: msg.to_owned(),
+                        error_msg: format!("{:?} is not a byte", value),
+                    }),
+                })
+                .collect(),
+            value => Err(SerdeError::Raw {
+                msg: msg.to_owned(),
+                error_msg: format!("{:?} is neither bytes nor a string", value),
+            }),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, SerdeError> {
+        T::deserialize(RawDeserializer(bytes)).map_err(|e| SerdeError::Raw {
+            msg: "decode table entry".to_owned(),
+            error_msg: e.to_string(),
+        })
+    }
+}
+
+// Deserializes the raw bytes into bytes, a string or a sequence of bytes.
+struct RawDeserializer<'a>(&'a [u8]);
+
+impl<'de, 'a> Deserializer<'de> for RawDeserializer<'a> {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.0.iter().copied()))
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bytes(self.0)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_byte_buf(self.0.to_vec())
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(std::str::from_utf8(self.0).map_err(ValueError::custom)?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char option unit unit_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payment {
+        id: u64,
+        account: String,
+        amount: f64,
+    }
+
+    #[test]
+    fn test_serializers() {
+        let payment = Payment {
+            id: 1,
+            account: "account".to_owned(),
+            amount: 10.5,
+        };
+        let serializers: Vec<Arc<dyn Serializer<Payment>>> =
+            vec![Arc::new(CborSerializer::new()), Arc::new(JsonSerializer::new())];
+        for serializer in serializers {
+            let bytes = serializer.serialize(&payment).expect("serialize payment");
+            let deserialized = serializer.deserialize(&bytes).expect("deserialize payment");
+            assert_eq!(deserialized, payment);
+            assert!(serializer.deserialize(&bytes[1..]).is_err());
+        }
+
+        let serializer = BytesSerializer::new();
+        let bytes = serializer.serialize(&vec![1, 2, 3]).expect("serialize bytes");
+        assert_eq!(
+            serializer.deserialize(&bytes).expect("deserialize bytes"),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_table_codecs() {
+        let payment = Payment {
+            id: 1,
+            account: "account".to_owned(),
+            amount: 10.5,
+        };
+        let bytes = CborCodec.encode(&payment).expect("encode payment");
+        assert_eq!(
+            CborCodec.decode::<Payment>(&bytes).expect("decode payment"),
+            payment
+        );
+        let bytes = JsonCodec.encode(&payment).expect("encode payment");
+        assert_eq!(
+            JsonCodec.decode::<Payment>(&bytes).expect("decode payment"),
+            payment
+        );
+
+        // the raw codec keeps bytes and strings as they are
+        let bytes = RawCodec.encode(&vec![1u8, 2, 3]).expect("encode bytes");
+        assert_eq!(bytes, vec![1, 2, 3]);
+        assert_eq!(
+            RawCodec.decode::<Vec<u8>>(&bytes).expect("decode bytes"),
+            vec![1, 2, 3]
+        );
+        let bytes = RawCodec.encode(&"key".to_owned()).expect("encode string");
+        assert_eq!(bytes, b"key".to_vec());
+        assert_eq!(RawCodec.decode::<String>(&bytes).expect("decode string"), "key");
+
+        // other types cannot be stored as raw bytes
+        assert!(RawCodec.encode(&payment).is_err());
+        assert!(RawCodec.encode(&vec![256u16]).is_err());
+        assert!(RawCodec.decode::<Payment>(&bytes).is_err());
+        assert!(RawCodec.decode::<String>(&[0xff]).is_err());
+    }
+}
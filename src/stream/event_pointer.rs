@@ -7,8 +7,13 @@
 //
 // http://www.apache.org/licenses/LICENSE-2.0
 //
+use crate::client_factory::ClientFactory;
 use crate::error::*;
-use pravega_rust_client_shared::Segment;
+use crate::segment_reader::{AsyncSegmentReader, ReaderError};
+use crate::segment_slice::Event;
+use bytes::{Buf, BufMut, BytesMut};
+use pravega_rust_client_shared::ScopedSegment;
+use pravega_wire_protocol::commands::{Command, EventCommand, TYPE_PLUS_LENGTH_SIZE};
 use serde::{Deserialize, Serialize};
 use serde_cbor::from_slice;
 use serde_cbor::to_vec;
@@ -16,44 +21,184 @@ use snafu::ResultExt;
 
 /// EventPointerVersioned enum contains all versions of EventPointer
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) enum EventPointerVersioned {
+pub enum EventPointerVersioned {
     V1(EventPointerV1),
 }
 
 impl EventPointerVersioned {
-    fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
+    /// Serializes the event pointer to a byte array.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
         let encoded = to_vec(&self).context(Cbor {
             msg: "serialize EventPointerVersioned".to_owned(),
         })?;
         Ok(encoded)
     }
 
-    fn from_bytes(input: &[u8]) -> Result<EventPointerVersioned, SerdeError> {
+    /// Deserializes the event pointer from a byte array.
+    pub fn from_bytes(input: &[u8]) -> Result<EventPointerVersioned, SerdeError> {
         let decoded: EventPointerVersioned = from_slice(&input[..]).context(Cbor {
             msg: "deserialize EventPointerVersioned".to_owned(),
         })?;
         Ok(decoded)
     }
+
+    /// Reads the event the pointer points to from the segment store.
+    pub(crate) async fn fetch_event(&self, factory: &ClientFactory) -> Result<Event, ReaderError> {
+        match self {
+            EventPointerVersioned::V1(v1) => v1.fetch_event(factory).await,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) struct EventPointerV1 {
-    segment: Segment,
+/// A pointer to an event. The pointer can be used to retrieve the event without reading the
+/// segment from the beginning, see `ClientFactory::fetch_event`.
+#[derive(new, Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct EventPointerV1 {
+    segment: ScopedSegment,
     event_start_offset: i64,
     event_length: i32,
 }
 
+impl EventPointerV1 {
+    /// gets the segment of the event
+    pub fn get_segment(&self) -> ScopedSegment {
+        self.segment.clone()
+    }
+
+    /// gets the offset of the event in the segment
+    pub fn get_event_start_offset(&self) -> i64 {
+        self.event_start_offset
+    }
+
+    /// gets the length of the event including its header
+    pub fn get_event_length(&self) -> i32 {
+        self.event_length
+    }
+
+    async fn fetch_event(&self, factory: &ClientFactory) -> Result<Event, ReaderError> {
+        let reader = factory.create_async_event_reader(self.segment.clone()).await;
+        self.read_event(&reader).await
+    }
+
+    async fn read_event(&self, reader: &impl AsyncSegmentReader) -> Result<Event, ReaderError> {
+        if self.event_length < TYPE_PLUS_LENGTH_SIZE as i32 {
+            return Err(ReaderError::OperationError {
+                segment: self.segment.to_string(),
+                can_retry: false,
+                operation: "fetch event".to_string(),
+                error_msg: format!(
+                    "event length {} is shorter than the event header",
+                    self.event_length
+                ),
+            });
+        }
+        let mut data = BytesMut::with_capacity(self.event_length as usize);
+        // the segment store may return less or more data than requested.
+        while data.len() < self.event_length as usize {
+            let offset = self.event_start_offset + data.len() as i64;
+            let remaining = self.event_length as usize - data.len();
+            let mut reply = reader.read(offset, remaining as i32).await?;
+            reply.data.truncate(remaining);
+            if reply.data.is_empty() && reply.end_of_segment {
+                return Err(ReaderError::OperationError {
+                    segment: self.segment.to_string(),
+                    can_retry: false,
+                    operation: "fetch event".to_string(),
+                    error_msg: format!("reached the end of segment at offset {}", offset),
+                });
+            }
+            data.put(reply.data.as_slice());
+        }
+        let type_code = data.get_i32();
+        let length = data.get_i32();
+        if type_code != EventCommand::TYPE_CODE || length + TYPE_PLUS_LENGTH_SIZE as i32 != self.event_length
+        {
+            return Err(ReaderError::OperationError {
+                segment: self.segment.to_string(),
+                can_retry: false,
+                operation: "fetch event".to_string(),
+                error_msg: format!("no event of length {} found", self.event_length),
+            });
+        }
+        Ok(Event {
+            offset_in_segment: self.event_start_offset,
+            value: data.to_vec(),
+            event_pointer: EventPointerVersioned::V1(self.clone()),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{create_mock_factory, create_stream};
+    use pravega_rust_client_shared::ScopedStream;
+    use pravega_wire_protocol::commands::SegmentReadCommand;
+
+    #[test]
+    fn test_fetch_event() {
+        let factory = create_mock_factory();
+        let handle = factory.get_runtime_handle();
+        handle.block_on(create_stream(&factory, "scope", "pointer"));
+        let mut writer = factory.create_event_stream_writer(ScopedStream::from("scope/pointer"));
+        handle.block_on(writer.write_event(vec![1; 10]));
+        handle.block_on(writer.write_event(vec![2; 20]));
+        handle.block_on(writer.flush()).expect("flush writer");
+
+        let segment = ScopedSegment::from("scope/pointer/0");
+        let pointer = EventPointerVersioned::V1(EventPointerV1::new(segment.clone(), 18, 28));
+        let event = handle
+            .block_on(factory.fetch_event(&pointer))
+            .expect("fetch event");
+        assert_eq!(event.value, vec![2; 20]);
+        assert_eq!(event.offset_in_segment, 18);
+        assert_eq!(event.event_pointer, pointer);
+
+        // the pointer does not match the event header
+        let pointer = EventPointerVersioned::V1(EventPointerV1::new(segment.clone(), 0, 28));
+        assert!(handle.block_on(factory.fetch_event(&pointer)).is_err());
+        // the pointer is shorter than an event header
+        let pointer = EventPointerVersioned::V1(EventPointerV1::new(segment, 0, 4));
+        assert!(handle.block_on(factory.fetch_event(&pointer)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_event_with_over_long_reply() {
+        let mut data = vec![];
+        for value in 1..3 {
+            data.extend_from_slice(&EventCommand::TYPE_CODE.to_be_bytes());
+            data.extend_from_slice(&10i32.to_be_bytes());
+            data.extend_from_slice(&[value; 10]);
+        }
+        let reader = OverLongSegmentReader { data };
+        let pointer = EventPointerV1::new(ScopedSegment::from("scope/stream/0"), 0, 18);
+        let event = pointer.read_event(&reader).await.expect("read event");
+        assert_eq!(event.value, vec![1; 10]);
+    }
+
+    // A segment reader that returns all the data from the offset on, whatever the requested length.
+    struct OverLongSegmentReader {
+        data: Vec<u8>,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncSegmentReader for OverLongSegmentReader {
+        async fn read(&self, offset: i64, _length: i32) -> Result<SegmentReadCommand, ReaderError> {
+            Ok(SegmentReadCommand {
+                segment: "scope/stream/0".to_string(),
+                offset,
+                at_tail: false,
+                end_of_segment: offset as usize >= self.data.len(),
+                data: self.data[offset as usize..].to_vec(),
+                request_id: 0,
+            })
+        }
+    }
 
     #[test]
     fn test_event_pointer_serde() {
         let v1 = EventPointerV1 {
-            segment: Segment {
-                number: 0,
-                tx_id: None,
-            },
+            segment: ScopedSegment::from("scope/stream/0"),
             event_start_offset: 0,
             event_length: 0,
         };
@@ -8,6 +8,7 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 
-pub(crate) mod event_pointer;
-pub(crate) mod position;
-pub(crate) mod stream_cut;
+pub mod event_pointer;
+pub mod position;
+pub mod stream_cut;
+pub mod watermark;
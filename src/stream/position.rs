@@ -8,7 +8,7 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 use crate::error::*;
-use pravega_rust_client_shared::{Segment, SegmentWithRange};
+use pravega_rust_client_shared::{ScopedSegment, SegmentWithRange};
 use serde::{Deserialize, Serialize};
 use serde_cbor::from_slice;
 use serde_cbor::to_vec;
@@ -17,19 +17,28 @@ use std::collections::HashMap;
 
 /// PositionedVersioned enum contains all versions of Position struct
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) enum PositionVersioned {
+pub enum PositionVersioned {
     V1(PositionV1),
 }
 
 impl PositionVersioned {
-    fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
+    /// gets the owned segments along with their key ranges and offsets
+    pub fn get_segments_with_offsets(&self) -> HashMap<SegmentWithRange, i64> {
+        match self {
+            PositionVersioned::V1(v1) => v1.get_segments_with_offsets(),
+        }
+    }
+
+    /// Serializes the position to a byte array.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
         let encoded = to_vec(&self).context(Cbor {
             msg: "serialize PositionVersioned".to_owned(),
         })?;
         Ok(encoded)
     }
 
-    fn from_bytes(input: &[u8]) -> Result<PositionVersioned, SerdeError> {
+    /// Deserializes the position from a byte array.
+    pub fn from_bytes(input: &[u8]) -> Result<PositionVersioned, SerdeError> {
         let decoded: PositionVersioned = from_slice(&input[..]).context(Cbor {
             msg: "serialize PositionVersioned".to_owned(),
         })?;
@@ -37,19 +46,21 @@ impl PositionVersioned {
     }
 }
 
+/// The position of a reader, i.e. the offsets up to which the reader has consumed the
+/// segments it owns.
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) struct PositionV1 {
-    owned_segments: HashMap<Segment, i64>,
-    segment_ranges: HashMap<Segment, SegmentWithRange>,
+pub struct PositionV1 {
+    owned_segments: HashMap<ScopedSegment, i64>,
+    segment_ranges: HashMap<ScopedSegment, SegmentWithRange>,
 }
 
 impl PositionV1 {
-    pub(crate) fn new(segments: HashMap<SegmentWithRange, i64>) -> Self {
+    pub fn new(segments: HashMap<SegmentWithRange, i64>) -> Self {
         let mut owned_segments = HashMap::with_capacity(segments.len());
         let mut segment_ranges = HashMap::with_capacity(segments.len());
         for (k, v) in segments {
-            owned_segments.insert(k.get_segment(), v);
-            segment_ranges.insert(k.get_segment(), k);
+            owned_segments.insert(k.scoped_segment.clone(), v);
+            segment_ranges.insert(k.scoped_segment.clone(), k);
         }
         PositionV1 {
             owned_segments,
@@ -57,16 +68,32 @@ impl PositionV1 {
         }
     }
 
-    pub(crate) fn get_owned_segments_with_offsets(&self) -> HashMap<Segment, i64> {
+    /// gets the owned segments with their offsets
+    pub fn get_owned_segments_with_offsets(&self) -> HashMap<ScopedSegment, i64> {
         self.owned_segments.to_owned()
     }
+
+    /// gets the owned segments along with their key ranges and offsets
+    pub fn get_segments_with_offsets(&self) -> HashMap<SegmentWithRange, i64> {
+        self.owned_segments
+            .iter()
+            .map(|(segment, offset)| {
+                let segment_with_range = self
+                    .segment_ranges
+                    .get(segment)
+                    .expect("owned segment should have a range")
+                    .to_owned();
+                (segment_with_range, *offset)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use ordered_float::OrderedFloat;
-    use pravega_rust_client_shared::{Scope, ScopedSegment, Stream};
+    use pravega_rust_client_shared::{Scope, Segment, Stream};
 
     #[test]
     fn test_position_serde() {
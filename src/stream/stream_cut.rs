@@ -8,6 +8,7 @@
 // http://www.apache.org/licenses/LICENSE-2.0
 //
 use crate::error::*;
+use base64::{decode, encode};
 use pravega_rust_client_shared::{ScopedSegment, ScopedStream};
 use serde::{Deserialize, Serialize};
 use serde_cbor::from_slice;
@@ -17,25 +18,40 @@ use std::collections::HashMap;
 
 /// StreamCutVersioned enum contains all versions of StreamCut struct
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) enum StreamCutVersioned {
+pub enum StreamCutVersioned {
     V1(StreamCutV1),
     UNBOUNDED,
 }
 
 impl StreamCutVersioned {
-    fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
+    /// Serializes the stream cut to a byte array.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerdeError> {
         let encoded = to_vec(&self).context(Cbor {
             msg: "serialize StreamCutVersioned".to_owned(),
         })?;
         Ok(encoded)
     }
 
-    fn from_bytes(input: &[u8]) -> Result<StreamCutVersioned, SerdeError> {
+    /// Deserializes the stream cut from a byte array.
+    pub fn from_bytes(input: &[u8]) -> Result<StreamCutVersioned, SerdeError> {
         let decoded: StreamCutVersioned = from_slice(&input[..]).context(Cbor {
             msg: "serialize StreamCutVersioned".to_owned(),
         })?;
         Ok(decoded)
     }
+
+    /// Serializes the stream cut to a base64 string, which can be stored as a bookmark.
+    pub fn to_base64(&self) -> Result<String, SerdeError> {
+        Ok(encode(self.to_bytes()?))
+    }
+
+    /// Deserializes the stream cut from a base64 string.
+    pub fn from_base64(input: &str) -> Result<StreamCutVersioned, SerdeError> {
+        let bytes = decode(input).context(Base64 {
+            msg: "decode StreamCutVersioned from base64".to_owned(),
+        })?;
+        StreamCutVersioned::from_bytes(&bytes)
+    }
 }
 
 /// A set of segment/offset pairs for a single stream that represent a consistent position in the
@@ -43,23 +59,23 @@ impl StreamCutVersioned {
 /// and is responsible for keyspace 0-0.5 then other segments covering the range 0.5-1.0 will also be
 /// included.)
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub(crate) struct StreamCutV1 {
+pub struct StreamCutV1 {
     stream: ScopedStream,
     positions: HashMap<ScopedSegment, i64>,
 }
 
 impl StreamCutV1 {
-    pub(crate) fn new(stream: ScopedStream, positions: HashMap<ScopedSegment, i64>) -> Self {
+    pub fn new(stream: ScopedStream, positions: HashMap<ScopedSegment, i64>) -> Self {
         StreamCutV1 { stream, positions }
     }
 
     /// gets a clone of the internal scoped stream
-    pub(crate) fn get_stream(&self) -> ScopedStream {
+    pub fn get_stream(&self) -> ScopedStream {
         self.stream.clone()
     }
 
     /// gets a clone of the internal positions
-    pub(crate) fn get_positions(&self) -> HashMap<ScopedSegment, i64> {
+    pub fn get_positions(&self) -> HashMap<ScopedSegment, i64> {
         self.positions.clone()
     }
 }
@@ -88,5 +104,10 @@ mod tests {
         let encoded = stream_cut.to_bytes().expect("encode to byte array");
         let decoded = StreamCutVersioned::from_bytes(&encoded).expect("decode from byte array");
         assert_eq!(StreamCutVersioned::V1(v1), decoded);
+
+        let encoded = stream_cut.to_base64().expect("encode to base64");
+        let decoded = StreamCutVersioned::from_base64(&encoded).expect("decode from base64");
+        assert_eq!(stream_cut, decoded);
+        assert!(StreamCutVersioned::from_base64("not base64!").is_err());
     }
 }
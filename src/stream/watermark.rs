@@ -0,0 +1,391 @@
+//
+// Copyright (c) Dell Inc., or its subsidiaries. All Rights Reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+
+use crate::client_factory::ClientFactory;
+use crate::error::*;
+use crate::segment_reader::AsyncSegmentReader;
+use bytes::{Buf, BufMut, BytesMut};
+use pravega_rust_client_shared::{ScopedSegment, ScopedStream, Segment, SegmentWithRange, Stream};
+use pravega_wire_protocol::commands::{Command, EventCommand, TYPE_PLUS_LENGTH_SIZE};
+use snafu::ResultExt;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The range of time of the events a reader is currently reading, as noted by the writers of the
+/// stream with `EventStreamWriter::note_time`. A bound is None if it is not known yet, e.g. because
+/// the writers have not noted any time or the reader is ahead of the latest watermark.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub lower_time_bound: Option<u64>,
+    pub upper_time_bound: Option<u64>,
+}
+
+impl TimeWindow {
+    /// Returns true if neither of the bounds is known.
+    pub fn is_unbounded(&self) -> bool {
+        self.lower_time_bound.is_none() && self.upper_time_bound.is_none()
+    }
+}
+
+/// A segment of a watermark stream cut along with its key range.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WatermarkSegment {
+    pub(crate) segment_id: i64,
+    pub(crate) min_key: f64,
+    pub(crate) max_key: f64,
+}
+
+impl WatermarkSegment {
+    fn overlaps(&self, segment: &SegmentWithRange) -> bool {
+        self.min_key < segment.max_key.0 && segment.min_key.0 < self.max_key
+    }
+}
+
+/// A watermark computed by the controller from the times and positions noted by the writers
+/// of a stream. The events before the stream cut have a time lower than the upper time bound,
+/// the events after the stream cut have a time greater than the lower time bound.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Watermark {
+    pub(crate) lower_time_bound: i64,
+    pub(crate) upper_time_bound: i64,
+    pub(crate) stream_cut: Vec<(WatermarkSegment, i64)>,
+}
+
+impl Watermark {
+    /// Decodes a watermark written by the controller, None is returned if the data is malformed.
+    pub(crate) fn from_bytes(input: &[u8]) -> Option<Watermark> {
+        let mut watermark = VersionedInput { buf: input }.read_revision()?;
+        let lower_time_bound = watermark.read_i64()?;
+        let upper_time_bound = watermark.read_i64()?;
+        let count = watermark.read_compact_int()?;
+        let mut stream_cut = vec![];
+        for _ in 0..count {
+            let mut segment = watermark.read_revision()?;
+            let segment = WatermarkSegment {
+                segment_id: segment.read_i64()?,
+                min_key: segment.read_f64()?,
+                max_key: segment.read_f64()?,
+            };
+            stream_cut.push((segment, watermark.read_i64()?));
+        }
+        Some(Watermark {
+            lower_time_bound,
+            upper_time_bound,
+            stream_cut,
+        })
+    }
+
+    /// Returns true if none of the given segment offsets is before the stream cut of the watermark.
+    /// A segment is compared with the segments of the stream cut that overlap its key range, a
+    /// segment of the stream cut with a lower id is a predecessor that the reader has already passed.
+    fn is_passed_by(&self, position: &HashMap<SegmentWithRange, i64>) -> bool {
+        position.iter().all(|(segment, offset)| {
+            let segment_id = segment.scoped_segment.segment.number;
+            self.stream_cut
+                .iter()
+                .filter(|(mark_segment, _)| mark_segment.overlaps(segment))
+                .all(
+                    |(mark_segment, mark_offset)| match mark_segment.segment_id.cmp(&segment_id) {
+                        Ordering::Equal => offset >= mark_offset,
+                        Ordering::Less => true,
+                        Ordering::Greater => false,
+                    },
+                )
+        })
+    }
+}
+
+// Reads values in the format of the versioned serializer used by the controller, where the fields
+// of an object are preceded by its version, its revision and the length of the revision.
+struct VersionedInput<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> VersionedInput<'a> {
+    fn read_u8(&mut self) -> Option<u8> {
+        if self.buf.remaining() < 1 {
+            return None;
+        }
+        Some(self.buf.get_u8())
+    }
+
+    fn read_i32(&mut self) -> Option<i32> {
+        if self.buf.remaining() < 4 {
+            return None;
+        }
+        Some(self.buf.get_i32())
+    }
+
+    fn read_i64(&mut self) -> Option<i64> {
+        if self.buf.remaining() < 8 {
+            return None;
+        }
+        Some(self.buf.get_i64())
+    }
+
+    fn read_f64(&mut self) -> Option<f64> {
+        if self.buf.remaining() < 8 {
+            return None;
+        }
+        Some(self.buf.get_f64())
+    }
+
+    // The two high bits of the first byte tell how many bytes follow.
+    fn read_compact_int(&mut self) -> Option<i32> {
+        let first = self.read_u8()?;
+        let mut value = i32::from(first & 0x3F);
+        for _ in 0..(first >> 6) {
+            value = (value << 8) | i32::from(self.read_u8()?);
+        }
+        Some(value)
+    }
+
+    // Reads the header of an object and returns the input of its first revision.
+    fn read_revision(&mut self) -> Option<VersionedInput<'a>> {
+        let _version = self.read_u8()?;
+        let _revision = self.read_u8()?;
+        let length = self.read_i32()?;
+        if length < 0 || self.buf.len() < length as usize {
+            return None;
+        }
+        let (revision, rest) = self.buf.split_at(length as usize);
+        self.buf = rest;
+        Some(VersionedInput { buf: revision })
+    }
+}
+
+/// Reads the watermarks the controller writes to the mark stream of a stream and computes the
+/// time window of a reader position from them.
+pub(crate) struct WatermarkReader {
+    segment: ScopedSegment,
+    read_offset: i64,
+    watermarks: Vec<Watermark>,
+}
+
+impl WatermarkReader {
+    const MARK_STREAM_PREFIX: &'static str = "_MARK";
+
+    pub(crate) fn new(stream: &ScopedStream) -> Self {
+        let mark_stream = Stream::from(format!(
+            "{}{}",
+            WatermarkReader::MARK_STREAM_PREFIX,
+            stream.stream
+        ));
+        WatermarkReader {
+            segment: ScopedSegment::new(stream.scope.clone(), mark_stream, Segment::from(0)),
+            read_offset: 0,
+            watermarks: vec![],
+        }
+    }
+
+    /// Reads the watermarks written since the last call.
+    pub(crate) async fn fetch_updates(&mut self, factory: &ClientFactory) -> Result<(), EventReaderError> {
+        let segment_info = factory
+            .create_segment_metadata_client(self.segment.clone())
+            .await
+            .get_segment_info()
+            .await
+            .map_err(|e| EventReaderError::ReaderWatermarkError {
+                error_msg: format!("failed to fetch the length of {}: {}", self.segment, e),
+            })?;
+        // the watermarks that are truncated by the controller are not needed anymore
+        if self.read_offset < segment_info.starting_offset {
+            self.read_offset = segment_info.starting_offset;
+        }
+        if self.read_offset >= segment_info.write_offset {
+            return Ok(());
+        }
+
+        let reader = factory.create_async_event_reader(self.segment.clone()).await;
+        let mut data = BytesMut::new();
+        // the segment store may return less data than requested.
+        while self.read_offset + (data.len() as i64) < segment_info.write_offset {
+            let offset = self.read_offset + data.len() as i64;
+            let reply = reader
+                .read(offset, (segment_info.write_offset - offset) as i32)
+                .await
+                .context(ReaderSegmentError {})?;
+            if reply.data.is_empty() && reply.end_of_segment {
+                break;
+            }
+            data.put(reply.data.as_slice());
+        }
+
+        // the watermarks are appended atomically, so the data read contains complete events.
+        while data.remaining() >= TYPE_PLUS_LENGTH_SIZE as usize {
+            let type_code = data.get_i32();
+            let length = data.get_i32();
+            if type_code != EventCommand::TYPE_CODE || length < 0 || length as usize > data.remaining() {
+                return Err(EventReaderError::ReaderWatermarkError {
+                    error_msg: format!(
+                        "no watermark found at offset {} of {}",
+                        self.read_offset, self.segment
+                    ),
+                });
+            }
+            let event = data.split_to(length as usize);
+            let watermark =
+                Watermark::from_bytes(&event).ok_or_else(|| EventReaderError::ReaderWatermarkError {
+                    error_msg: format!(
+                        "malformed watermark at offset {} of {}",
+                        self.read_offset, self.segment
+                    ),
+                })?;
+            self.watermarks.push(watermark);
+            self.read_offset += i64::from(TYPE_PLUS_LENGTH_SIZE) + i64::from(length);
+        }
+        Ok(())
+    }
+
+    /// Computes the time window of the given position. The lower bound comes from the latest
+    /// watermark the position has passed and the upper bound from the first one it has not.
+    pub(crate) fn compute_time_window(&mut self, position: &HashMap<SegmentWithRange, i64>) -> TimeWindow {
+        if position.is_empty() {
+            return TimeWindow::default();
+        }
+        let passed = self
+            .watermarks
+            .iter()
+            .take_while(|watermark| watermark.is_passed_by(position))
+            .count();
+        // the position only moves forward, so the watermarks before the latest passed one are not needed.
+        if passed > 1 {
+            self.watermarks.drain(..passed - 1);
+        }
+        let (lower, upper) = if passed > 0 {
+            (self.watermarks.first(), self.watermarks.get(1))
+        } else {
+            (None, self.watermarks.first())
+        };
+        TimeWindow {
+            lower_time_bound: lower.map(|watermark| watermark.lower_time_bound as u64),
+            upper_time_bound: upper.map(|watermark| watermark.upper_time_bound as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ordered_float::OrderedFloat;
+
+    // Encodes a watermark the way the controller does.
+    pub(crate) fn encode(watermark: &Watermark) -> Vec<u8> {
+        let mut fields = vec![];
+        fields.put_i64(watermark.lower_time_bound);
+        fields.put_i64(watermark.upper_time_bound);
+        fields.put_u8(watermark.stream_cut.len() as u8);
+        for (segment, offset) in &watermark.stream_cut {
+            let mut segment_fields = vec![];
+            segment_fields.put_i64(segment.segment_id);
+            segment_fields.put_f64(segment.min_key);
+            segment_fields.put_f64(segment.max_key);
+            put_revision(&mut fields, &segment_fields);
+            fields.put_i64(*offset);
+        }
+        let mut data = vec![];
+        put_revision(&mut data, &fields);
+        data
+    }
+
+    fn put_revision(buf: &mut Vec<u8>, fields: &[u8]) {
+        buf.put_u8(0);
+        buf.put_u8(0);
+        buf.put_i32(fields.len() as i32);
+        buf.put_slice(fields);
+    }
+
+    pub(crate) fn watermark(
+        lower_time_bound: i64,
+        upper_time_bound: i64,
+        cut: &[(i64, f64, f64, i64)],
+    ) -> Watermark {
+        Watermark {
+            lower_time_bound,
+            upper_time_bound,
+            stream_cut: cut
+                .iter()
+                .map(|(segment_id, min_key, max_key, offset)| {
+                    (
+                        WatermarkSegment {
+                            segment_id: *segment_id,
+                            min_key: *min_key,
+                            max_key: *max_key,
+                        },
+                        *offset,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn position(segments: &[(i64, f64, f64, i64)]) -> HashMap<SegmentWithRange, i64> {
+        segments
+            .iter()
+            .map(|(number, min_key, max_key, offset)| {
+                let segment = SegmentWithRange {
+                    scoped_segment: ScopedSegment::from(format!("scope/stream/{}", number).as_str()),
+                    min_key: OrderedFloat(*min_key),
+                    max_key: OrderedFloat(*max_key),
+                };
+                (segment, *offset)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_watermark_decoding() {
+        let mark = watermark(10, 20, &[(0, 0.0, 0.5, 100), (1, 0.5, 1.0, 200)]);
+        let decoded = Watermark::from_bytes(&encode(&mark)).expect("decode watermark");
+        assert_eq!(decoded, mark);
+
+        let encoded = encode(&mark);
+        assert!(Watermark::from_bytes(&encoded[..encoded.len() - 1]).is_none());
+
+        let mut input = VersionedInput {
+            buf: &[0x05, 0x41, 0x02, 0x81, 0x02, 0x03],
+        };
+        assert_eq!(input.read_compact_int(), Some(5));
+        assert_eq!(input.read_compact_int(), Some(0x0102));
+        assert_eq!(input.read_compact_int(), Some(0x010203));
+    }
+
+    #[test]
+    fn test_compute_time_window() {
+        let mut reader = WatermarkReader::new(&ScopedStream::from("scope/stream"));
+        reader.watermarks = vec![
+            watermark(10, 20, &[(0, 0.0, 0.5, 100), (1, 0.5, 1.0, 100)]),
+            watermark(20, 30, &[(0, 0.0, 0.5, 200), (1, 0.5, 1.0, 200)]),
+            watermark(30, 40, &[(2, 0.0, 1.0, 100)]),
+        ];
+
+        // the reader has not passed any watermark
+        let window = reader.compute_time_window(&position(&[(0, 0.0, 0.5, 50), (1, 0.5, 1.0, 150)]));
+        assert_eq!(window.lower_time_bound, None);
+        assert_eq!(window.upper_time_bound, Some(20));
+
+        // the reader owning a single segment only compares the overlapping segments
+        let window = reader.compute_time_window(&position(&[(1, 0.5, 1.0, 150)]));
+        assert_eq!(window.lower_time_bound, Some(10));
+        assert_eq!(window.upper_time_bound, Some(30));
+
+        let window = reader.compute_time_window(&position(&[(0, 0.0, 0.5, 200), (1, 0.5, 1.0, 250)]));
+        assert_eq!(window.lower_time_bound, Some(20));
+        assert_eq!(window.upper_time_bound, Some(40));
+        assert_eq!(reader.watermarks.len(), 2);
+
+        // the reader reading the successor has passed the predecessors
+        let window = reader.compute_time_window(&position(&[(2, 0.0, 1.0, 100)]));
+        assert_eq!(window.lower_time_bound, Some(30));
+        assert_eq!(window.upper_time_bound, None);
+
+        assert!(reader.compute_time_window(&HashMap::new()).is_unbounded());
+    }
+}
@@ -702,6 +702,13 @@ async fn conditionally_write(
                 debug!("KeyDoesNotExist {}, {}", operation, error_msg);
                 table_synchronizer.fetch_updates().await.expect("fetch update");
             }
+            // retrying does not help if the entries cannot be serialized.
+            Err(e @ TableError::SerializationError { .. }) => {
+                return Err(SynchronizerError::SyncTableError {
+                    operation: "insert conditionally_all".to_owned(),
+                    source: e,
+                });
+            }
             Err(e) => {
                 debug!("Error message is {}", e);
                 if retry > 0 {
@@ -772,6 +779,12 @@ async fn conditionally_remove(
                 debug!("KeyDoesNotExist {}, {}", operation, error_msg);
                 table_synchronizer.fetch_updates().await.expect("fetch update");
             }
+            Err(e @ TableError::SerializationError { .. }) => {
+                return Err(SynchronizerError::SyncTableError {
+                    operation: "remove conditionally_all".to_owned(),
+                    source: e,
+                });
+            }
             Err(e) => {
                 debug!("Error message is {}", e);
                 if retry > 0 {
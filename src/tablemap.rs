@@ -9,9 +9,10 @@
 //
 
 use crate::client_factory::ClientFactory;
-use crate::error::RawClientError;
+use crate::error::{RawClientError, SerdeError};
 use crate::get_request_id;
 use crate::raw_client::RawClient;
+use crate::serializer::{CborCodec, TableCodec};
 use async_stream::try_stream;
 use futures::stream::Stream;
 use pravega_rust_client_auth::DelegationTokenProvider;
@@ -26,19 +27,20 @@ use pravega_wire_protocol::commands::{
 };
 use pravega_wire_protocol::wire_commands::{Replies, Requests};
 use serde::Serialize;
-use serde_cbor::from_slice;
-use serde_cbor::to_vec;
 use snafu::Snafu;
 use tracing::{debug, info};
 
 pub type Version = i64;
 
-pub struct TableMap {
+/// A map backed by a table segment. The keys and values are converted to bytes by the codec of
+/// the map, which is CBOR unless another codec is given when the map is created.
+pub struct TableMap<C = CborCodec> {
     /// name of the map
     name: String,
     endpoint: PravegaNodeUri,
     factory: ClientFactory,
     delegation_token_provider: DelegationTokenProvider,
+    codec: C,
 }
 
 #[derive(Debug, Snafu)]
@@ -59,10 +61,24 @@ pub enum TableError {
     IncorrectKeyVersion { operation: String, error_msg: String },
     #[snafu(display("Error observed while performing {} due to {}", operation, error_msg,))]
     OperationError { operation: String, error_msg: String },
+    #[snafu(display("Serialization error while performing {}: {}", operation, source))]
+    SerializationError { operation: String, source: SerdeError },
 }
+
 impl TableMap {
     /// create a table map
     pub async fn new(name: String, factory: ClientFactory) -> Result<TableMap, TableError> {
+        Self::new_with_codec(name, factory, CborCodec).await
+    }
+}
+
+impl<C: TableCodec> TableMap<C> {
+    /// create a table map that converts the keys and values using the given codec
+    pub async fn new_with_codec(
+        name: String,
+        factory: ClientFactory,
+        codec: C,
+    ) -> Result<TableMap<C>, TableError> {
         let segment = ScopedSegment {
             scope: Scope::from("_tables".to_owned()),
             stream: PravegaStream::from(name),
@@ -82,6 +98,7 @@ impl TableMap {
             delegation_token_provider: factory
                 .create_delegation_token_provider(ScopedStream::from(&segment))
                 .await,
+            codec,
         };
 
         let op = "Create table segment";
@@ -140,17 +157,15 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let key = to_vec(k).expect("error during serialization.");
-        let read_result = self.get_raw_values(vec![key]).await;
-        read_result.map(|v| {
-            let (l, version) = &v[0];
-            if l.is_empty() {
-                None
-            } else {
-                let value: V = from_slice(l.as_slice()).expect("error during deserialization");
-                Some((value, *version))
-            }
-        })
+        let key = self.encode(k)?;
+        let v = self.get_raw_values(vec![key]).await?;
+        let (l, version) = &v[0];
+        if l.is_empty() {
+            Ok(None)
+        } else {
+            let value: V = self.decode(l.as_slice())?;
+            Ok(Some((value, *version)))
+        }
     }
 
     ///
@@ -169,7 +184,7 @@ impl TableMap {
 
     ///
     /// Conditionally inserts a key-value pair into the table map. The Key and Value are serialized to bytes using
-    /// cbor.
+    /// the codec of the map.
     ///
     /// The insert is performed after checking the key_version passed.
     /// Once the update is done the newer version is returned.
@@ -186,8 +201,8 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let key = to_vec(k).expect("error during serialization.");
-        let val = to_vec(v).expect("error during serialization.");
+        let key = self.encode(k)?;
+        let val = self.encode(v)?;
         self.insert_raw_values(vec![(key, val, key_version)], offset)
             .await
             .map(|versions| versions[0])
@@ -218,7 +233,7 @@ impl TableMap {
     where
         K: Serialize + serde::de::DeserializeOwned,
     {
-        let key = to_vec(k).expect("error during serialization.");
+        let key = self.encode(k)?;
         self.remove_raw_values(vec![(key, key_version)], offset).await
     }
 
@@ -232,24 +247,22 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let keys_raw: Vec<Vec<u8>> = keys
+        let keys_raw = keys
             .iter()
-            .map(|k| to_vec(*k).expect("error during serialization."))
-            .collect();
-
-        let read_result: Result<Vec<(Vec<u8>, Version)>, TableError> = self.get_raw_values(keys_raw).await;
-        read_result.map(|v| {
-            v.iter()
-                .map(|(data, version)| {
-                    if data.is_empty() {
-                        None
-                    } else {
-                        let value: V = from_slice(data.as_slice()).expect("error during deserialization");
-                        Some((value, *version))
-                    }
-                })
-                .collect()
-        })
+            .map(|k| self.encode(*k))
+            .collect::<Result<Vec<Vec<u8>>, TableError>>()?;
+
+        let v: Vec<(Vec<u8>, Version)> = self.get_raw_values(keys_raw).await?;
+        v.iter()
+            .map(|(data, version)| {
+                if data.is_empty() {
+                    Ok(None)
+                } else {
+                    let value: V = self.decode(data.as_slice())?;
+                    Ok(Some((value, *version)))
+                }
+            })
+            .collect()
     }
 
     ///
@@ -261,22 +274,16 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let r: Vec<(Vec<u8>, Vec<u8>, Version)> = kvps
+        let r = kvps
             .iter()
-            .map(|(k, v)| {
-                (
-                    to_vec(k).expect("error during serialization."),
-                    to_vec(v).expect("error during serialization."),
-                    TableKey::KEY_NO_VERSION,
-                )
-            })
-            .collect();
+            .map(|(k, v)| Ok((self.encode(k)?, self.encode(v)?, TableKey::KEY_NO_VERSION)))
+            .collect::<Result<Vec<(Vec<u8>, Vec<u8>, Version)>, TableError>>()?;
         self.insert_raw_values(r, offset).await
     }
 
     ///
     /// Conditionally inserts key-value pairs into the table map. The Key and Value are serialized to to bytes using
-    /// cbor
+    /// the codec of the map
     ///
     /// The insert is performed after checking the key_version passed, in case of a failure none of the key-value pairs
     /// are persisted.
@@ -292,16 +299,10 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let r: Vec<(Vec<u8>, Vec<u8>, Version)> = kvps
+        let r = kvps
             .iter()
-            .map(|(k, v, ver)| {
-                (
-                    to_vec(k).expect("error during serialization."),
-                    to_vec(v).expect("error during serialization."),
-                    *ver,
-                )
-            })
-            .collect();
+            .map(|(k, v, ver)| Ok((self.encode(k)?, self.encode(v)?, *ver)))
+            .collect::<Result<Vec<(Vec<u8>, Vec<u8>, Version)>, TableError>>()?;
         self.insert_raw_values(r, offset).await
     }
 
@@ -328,10 +329,10 @@ impl TableMap {
     where
         K: Serialize + serde::de::DeserializeOwned,
     {
-        let r: Vec<(Vec<u8>, Version)> = keys
+        let r = keys
             .iter()
-            .map(|(k, v)| (to_vec(k).expect("error during serialization."), *v))
-            .collect();
+            .map(|(k, v)| Ok((self.encode(k)?, *v)))
+            .collect::<Result<Vec<(Vec<u8>, Version)>, TableError>>()?;
         self.remove_raw_values(r, offset).await
     }
 
@@ -354,7 +355,7 @@ impl TableMap {
                     break;
                 } else {
                     for (key_raw, version) in keys {
-                       let key: K = from_slice(key_raw.as_slice()).expect("error during deserialization");
+                       let key: K = self.decode(key_raw.as_slice())?;
                         yield (key, version)
                     }
                     token = t;
@@ -384,8 +385,8 @@ impl TableMap {
                     break;
                 } else {
                     for (key_raw, value_raw, version) in entries {
-                        let key: K = from_slice(key_raw.as_slice()).expect("error during deserialization");
-                        let value: V = from_slice(value_raw.as_slice()).expect("error during deserialization");
+                        let key: K = self.decode(key_raw.as_slice())?;
+                        let value: V = self.decode(value_raw.as_slice())?;
                         yield (key, value, version)
                     }
                     token = t;
@@ -415,8 +416,8 @@ impl TableMap {
                     break;
                 } else {
                     for (key_raw, value_raw, version) in entries {
-                        let key: K = from_slice(key_raw.as_slice()).expect("error during deserialization");
-                        let value: V = from_slice(value_raw.as_slice()).expect("error during deserialization");
+                        let key: K = self.decode(key_raw.as_slice())?;
+                        let value: V = self.decode(value_raw.as_slice())?;
                         yield (key, value, version, last_position)
                     }
                     from_position = last_position;
@@ -439,17 +440,15 @@ impl TableMap {
     where
         K: Serialize + serde::de::DeserializeOwned,
     {
-        let res = self.read_keys_raw(max_keys_at_once, token).await;
-        res.map(|(keys, token)| {
-            let keys_de: Vec<(K, Version)> = keys
-                .iter()
-                .map(|(k, version)| {
-                    let key: K = from_slice(k.as_slice()).expect("error during deserialization");
-                    (key, *version)
-                })
-                .collect();
-            (keys_de, token)
-        })
+        let (keys, token) = self.read_keys_raw(max_keys_at_once, token).await?;
+        let keys_de = keys
+            .iter()
+            .map(|(k, version)| {
+                let key: K = self.decode(k.as_slice())?;
+                Ok((key, *version))
+            })
+            .collect::<Result<Vec<(K, Version)>, TableError>>()?;
+        Ok((keys_de, token))
     }
 
     ///
@@ -467,18 +466,9 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let res = self.read_entries_raw(max_entries_at_once, token).await;
-        res.map(|(entries, token)| {
-            let entries_de: Vec<(K, V, Version)> = entries
-                .iter()
-                .map(|(k, v, version)| {
-                    let key: K = from_slice(k.as_slice()).expect("error during deserialization");
-                    let value: V = from_slice(v.as_slice()).expect("error during deserialization");
-                    (key, value, *version)
-                })
-                .collect();
-            (entries_de, token)
-        })
+        let (entries, token) = self.read_entries_raw(max_entries_at_once, token).await?;
+        let entries_de = self.decode_entries(&entries)?;
+        Ok((entries_de, token))
     }
 
     ///
@@ -493,20 +483,47 @@ impl TableMap {
         K: Serialize + serde::de::DeserializeOwned,
         V: Serialize + serde::de::DeserializeOwned,
     {
-        let res = self
+        let (entries, token) = self
             .read_entries_raw_delta(max_entries_at_once, from_position)
-            .await;
-        res.map(|(entries, token)| {
-            let entries_de: Vec<(K, V, Version)> = entries
-                .iter()
-                .map(|(k, v, version)| {
-                    let key: K = from_slice(k.as_slice()).expect("error during deserialization");
-                    let value: V = from_slice(v.as_slice()).expect("error during deserialization");
-                    (key, value, *version)
-                })
-                .collect();
-            (entries_de, token)
-        })
+            .await?;
+        let entries_de = self.decode_entries(&entries)?;
+        Ok((entries_de, token))
+    }
+
+    fn decode_entries<K, V>(
+        &self,
+        entries: &[(Vec<u8>, Vec<u8>, Version)],
+    ) -> Result<Vec<(K, V, Version)>, TableError>
+    where
+        K: serde::de::DeserializeOwned,
+        V: serde::de::DeserializeOwned,
+    {
+        entries
+            .iter()
+            .map(|(k, v, version)| {
+                let key: K = self.decode(k.as_slice())?;
+                let value: V = self.decode(v.as_slice())?;
+                Ok((key, value, *version))
+            })
+            .collect()
+    }
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, TableError> {
+        self.codec
+            .encode(value)
+            .map_err(|e| TableError::SerializationError {
+                operation: "encode".to_owned(),
+                source: e,
+            })
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, TableError> {
+        self.codec
+            .decode(bytes)
+            .map_err(|e| TableError::SerializationError {
+                operation: "decode".to_owned(),
+                source: e,
+            })
     }
 
     ///
@@ -900,6 +917,7 @@ impl TableMap {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::serializer::{JsonCodec, RawCodec};
     use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
     use pravega_rust_client_config::ClientConfigBuilder;
     use pravega_rust_client_shared::PravegaNodeUri;
@@ -1000,15 +1018,52 @@ mod test {
         assert!(option.is_none());
     }
 
+    #[test]
+    fn test_table_map_codecs() {
+        let mut rt = Runtime::new().unwrap();
+        let factory = create_factory();
+        let raw_map = rt.block_on(factory.create_table_map_with_codec("codecs".to_string(), RawCodec));
+        let json_map = rt.block_on(factory.create_table_map_with_codec("codecs".to_string(), JsonCodec));
+
+        // raw bytes written by another client are read as they are
+        rt.block_on(raw_map.insert(&b"raw".to_vec(), &vec![1u8, 2, 3], -1))
+            .expect("insert raw bytes");
+        let option: Option<(Vec<u8>, Version)> = rt
+            .block_on(raw_map.get(&"raw".to_string()))
+            .expect("get raw bytes");
+        assert_eq!(option, Some((vec![1, 2, 3], 0)));
+
+        // the keys and values written as JSON can be read as text
+        rt.block_on(json_map.insert(&"json".to_string(), &vec![1, 2], -1))
+            .expect("insert json");
+        let option: Option<(String, Version)> = rt
+            .block_on(raw_map.get(&"\"json\"".to_string()))
+            .expect("get json as text");
+        assert_eq!(option, Some(("[1,2]".to_string(), 0)));
+        let option: Option<(Vec<i32>, Version)> =
+            rt.block_on(json_map.get(&"json".to_string())).expect("get json");
+        assert_eq!(option, Some((vec![1, 2], 0)));
+
+        // the keys and values the codec cannot convert are reported as errors
+        let result = rt.block_on(raw_map.insert(&1u64, &vec![1u8], -1));
+        assert!(matches!(result, Err(TableError::SerializationError { .. })));
+        let result: Result<Option<(u64, Version)>, TableError> =
+            rt.block_on(json_map.get(&"json".to_string()));
+        assert!(matches!(result, Err(TableError::SerializationError { .. })));
+    }
+
     // helper function
     fn create_table_map(rt: &mut Runtime) -> TableMap {
+        rt.block_on(create_factory().create_table_map("tablemap".to_string()))
+    }
+
+    fn create_factory() -> ClientFactory {
         let config = ClientConfigBuilder::default()
             .connection_type(ConnectionType::Mock(MockType::Happy))
             .mock(true)
             .controller_uri(PravegaNodeUri::from("127.0.0.2:9091"))
             .build()
             .unwrap();
-        let factory = ClientFactory::new(config);
-        rt.block_on(factory.create_table_map("tablemap".to_string()))
+        ClientFactory::new(config)
     }
 }
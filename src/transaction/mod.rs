@@ -17,10 +17,11 @@ use crate::error::*;
 use crate::get_random_f64;
 use crate::transaction::pinger::PingerHandle;
 use pravega_rust_client_shared::{
-    ScopedSegment, ScopedStream, StreamSegments, Timestamp, TransactionStatus, TxId, WriterId,
+    PingStatus, ScopedSegment, ScopedStream, StreamSegments, Timestamp, TransactionStatus, TxId, WriterId,
 };
 use snafu::ResultExt;
 use std::collections::HashMap;
+use tokio::sync::watch;
 use tracing::{debug, info_span};
 use tracing_futures::Instrument;
 use transactional_event_segment_writer::TransactionalEventSegmentWriter;
@@ -41,6 +42,7 @@ pub struct Transaction {
     inner: HashMap<ScopedSegment, TransactionalEventSegmentWriter>,
     segments: StreamSegments,
     handle: PingerHandle,
+    ping_status: Option<watch::Receiver<PingStatus>>,
     factory: ClientFactory,
 }
 
@@ -52,6 +54,7 @@ impl Transaction {
         transactions: HashMap<ScopedSegment, TransactionalEventSegmentWriter>,
         segments: StreamSegments,
         handle: PingerHandle,
+        ping_status: Option<watch::Receiver<PingStatus>>,
         factory: ClientFactory,
     ) -> Self {
         Transaction {
@@ -59,6 +62,7 @@ impl Transaction {
             inner: transactions,
             segments,
             handle,
+            ping_status,
             factory,
         }
     }
@@ -164,6 +168,23 @@ impl Transaction {
         Ok(())
     }
 
+    /// Returns a watch of the ping status of this transaction, which changes from `PingStatus::Ok`
+    /// once a ping finds the transaction committed or aborted, or once a ping fails. The watch is
+    /// closed if the pinging is stopped. None is returned if this transaction is not pinged by
+    /// this process, which is the case for a transaction fetched by its id.
+    pub fn ping_status(&self) -> Option<watch::Receiver<PingStatus>> {
+        self.ping_status.clone()
+    }
+
+    /// Stops extending the lease of this transaction, the transaction is aborted by the
+    /// controller once its lease expires unless it is committed before.
+    pub async fn stop_pinging(&mut self) -> Result<(), TransactionError> {
+        self.handle
+            .remove(self.info.txn_id)
+            .await
+            .context(TxnStreamWriterError {})
+    }
+
     /// check the current Transaction status by sending request to Pravega controller.
     pub async fn check_status(&self) -> Result<TransactionStatus, TransactionError> {
         self.factory
@@ -187,7 +208,9 @@ impl Transaction {
 mod test {
     use super::*;
     use crate::transaction::transactional_event_stream_writer::test::create_txn_stream_writer;
+    use std::time::Duration;
     use tokio::runtime::Runtime;
+    use tokio::time::timeout;
 
     #[test]
     fn test_txn_commit() {
@@ -224,4 +247,47 @@ mod test {
         let status = rt.block_on(txn.check_status()).unwrap();
         assert_eq!(status, TransactionStatus::Aborted);
     }
+
+    #[test]
+    fn test_txn_ping_status() {
+        let mut rt = Runtime::new().unwrap();
+        let mut txn_stream_writer = rt.block_on(create_txn_stream_writer());
+        let txn = rt
+            .block_on(txn_stream_writer.begin_with_lease(Duration::from_millis(100)))
+            .expect("begin a transaction");
+        let mut status = txn.ping_status().expect("transaction is pinged");
+        assert_eq!(rt.block_on(status.recv()), Some(PingStatus::Ok));
+
+        // the next ping finds the transaction aborted by someone else
+        rt.block_on(
+            txn.factory
+                .get_controller_client()
+                .abort_transaction(&txn.get_stream(), txn.get_txn_id()),
+        )
+        .expect("abort transaction");
+        let result = rt.block_on(async { timeout(Duration::from_secs(5), status.recv()).await });
+        assert_eq!(result.expect("ping status changes"), Some(PingStatus::Aborted));
+    }
+
+    #[test]
+    fn test_txn_stop_pinging() {
+        let mut rt = Runtime::new().unwrap();
+        let mut txn_stream_writer = rt.block_on(create_txn_stream_writer());
+        let mut txn = rt
+            .block_on(txn_stream_writer.begin_with_lease(Duration::from_millis(100)))
+            .expect("begin a transaction");
+        let mut status = txn.ping_status().expect("transaction is pinged");
+        assert_eq!(rt.block_on(status.recv()), Some(PingStatus::Ok));
+
+        // the watch is closed once the transaction is removed from the ping list
+        rt.block_on(txn.stop_pinging()).expect("stop pinging");
+        let result = rt.block_on(async { timeout(Duration::from_secs(5), status.recv()).await });
+        assert_eq!(result.expect("ping status is closed"), None);
+
+        // a fetched transaction is not pinged
+        let fetched = rt
+            .block_on(txn_stream_writer.get_txn(txn.get_txn_id()))
+            .expect("get transaction");
+        assert!(fetched.ping_status().is_none());
+    }
 }
@@ -10,28 +10,35 @@
 
 use crate::client_factory::ClientFactory;
 use crate::error::*;
+use pravega_controller_client::ControllerError;
+use pravega_rust_client_retry::retry_result::{RetryError, Retryable};
 use pravega_rust_client_shared::{PingStatus, ScopedStream, TxId};
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::time::Duration;
-use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
-use tokio::time::delay_for;
-use tracing::{debug, error, info};
+use tokio::sync::watch;
+use tokio::time::{delay_until, Instant};
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug)]
 enum PingerEvent {
-    Add(TxId),
+    Add(TxId, u64, watch::Sender<PingStatus>),
     Remove(TxId),
     Terminate,
 }
 
+// A transaction in the ping list together with its lease.
+struct PingedTransaction {
+    lease_millis: u64,
+    next_ping: Instant,
+    status_sender: watch::Sender<PingStatus>,
+}
+
 /// Pinger is used to ping transactions periodically. It spawns a task that runs in the background
-/// to ping transactions without blocking the current thread. The spawned task uses a loop to run
-/// the ping logic and sleeps(does not block other tasks) for a given time before next iteration.
+/// to ping transactions without blocking the current thread. The spawned task waits for the
+/// next transaction whose ping is due, each transaction is pinged with its own lease.
 pub(crate) struct Pinger {
     stream: ScopedStream,
-    txn_lease_millis: u64,
-    ping_interval_millis: u64,
     factory: ClientFactory,
     receiver: Receiver<PingerEvent>,
 }
@@ -42,8 +49,16 @@ pub(crate) struct Pinger {
 pub(crate) struct PingerHandle(Sender<PingerEvent>);
 
 impl PingerHandle {
-    pub(crate) async fn add(&mut self, txn_id: TxId) -> Result<(), TransactionalEventStreamWriterError> {
-        if let Err(e) = self.0.send(PingerEvent::Add(txn_id)).await {
+    /// Adds a transaction to the ping list, the status of the transaction is sent to the given
+    /// sender once a ping finds that it is no longer open.
+    pub(crate) async fn add(
+        &mut self,
+        txn_id: TxId,
+        lease: Duration,
+        status_sender: watch::Sender<PingStatus>,
+    ) -> Result<(), TransactionalEventStreamWriterError> {
+        let event = PingerEvent::Add(txn_id, lease.as_millis() as u64, status_sender);
+        if let Err(e) = self.0.send(event).await {
             error!("pinger failed to add transaction: {:?}", e);
             Err(TransactionalEventStreamWriterError::PingerError {
                 msg: format!("failed to add transaction due to: {:?}", e),
@@ -77,16 +92,10 @@ impl PingerHandle {
 }
 
 impl Pinger {
-    pub(crate) fn new(
-        stream: ScopedStream,
-        txn_lease_millis: u64,
-        factory: ClientFactory,
-    ) -> (Self, PingerHandle) {
+    pub(crate) fn new(stream: ScopedStream, factory: ClientFactory) -> (Self, PingerHandle) {
         let (tx, rx) = channel(100);
         let pinger = Pinger {
             stream,
-            txn_lease_millis,
-            ping_interval_millis: Pinger::get_ping_interval(txn_lease_millis),
             factory,
             receiver: rx,
         };
@@ -95,67 +104,111 @@ impl Pinger {
     }
 
     pub(crate) async fn start_ping(&mut self) {
-        // this set is used to store the transactions that are alive and
-        // needs to be pinged periodically.
-        let mut txn_list: HashSet<TxId> = HashSet::new();
-
-        // this set is used to store the transaction that are aborted or committed
-        let mut completed_txns: HashSet<TxId> = HashSet::new();
+        // the transactions that are alive and need to be pinged periodically.
+        let mut txns: HashMap<TxId, PingedTransaction> = HashMap::new();
 
         loop {
-            // try receive any incoming events
-            match self.receiver.try_recv() {
-                Ok(event) => match event {
-                    PingerEvent::Add(id) => {
-                        txn_list.insert(id);
-                    }
-                    PingerEvent::Remove(id) => {
-                        txn_list.remove(&id);
-                    }
-                    PingerEvent::Terminate => {
-                        return;
-                    }
-                },
-                Err(e) => {
-                    if e != TryRecvError::Empty {
-                        error!("pinger exit with error: {:?}", e);
+            let next_ping = txns.values().map(|txn| txn.next_ping).min();
+            let event = match next_ping {
+                Some(deadline) => {
+                    tokio::select! {
+                        event = self.receiver.recv() => Some(event),
+                        _ = delay_until(deadline) => None,
                     }
                 }
+                None => Some(self.receiver.recv().await),
+            };
+
+            match event {
+                Some(Some(PingerEvent::Add(id, lease_millis, status_sender))) => {
+                    // the lease is set when the transaction is created, the first ping extends it.
+                    let next_ping =
+                        Instant::now() + Duration::from_millis(Pinger::get_ping_interval(lease_millis));
+                    txns.insert(
+                        id,
+                        PingedTransaction {
+                            lease_millis,
+                            next_ping,
+                            status_sender,
+                        },
+                    );
+                }
+                Some(Some(PingerEvent::Remove(id))) => {
+                    txns.remove(&id);
+                }
+                Some(Some(PingerEvent::Terminate)) => return,
+                Some(None) => {
+                    debug!("all pinger handles are dropped, pinger exits");
+                    return;
+                }
+                None => self.ping_due_transactions(&mut txns).await,
             }
+        }
+    }
 
-            // remove completed transactions from the ping list
-            txn_list.retain(|i| !completed_txns.contains(i));
-            completed_txns.clear();
+    // Pings the transactions whose ping is due. The transactions that are committed or aborted,
+    // or that the controller definitively refuses to ping, are removed from the ping list and
+    // their status is sent out. A transaction that fails to be pinged due to a transient error
+    // is pinged again on its next tick.
+    async fn ping_due_transactions(&self, txns: &mut HashMap<TxId, PingedTransaction>) {
+        let now = Instant::now();
+        let due = txns
+            .iter()
+            .filter(|(_id, txn)| txn.next_ping <= now)
+            .map(|(id, _txn)| id.to_owned())
+            .collect::<Vec<_>>();
 
-            info!("start sending pings to {} transactions.", txn_list.len());
-            // for each transaction in the ping list, send ping to the server
-            for txn_id in txn_list.iter() {
-                debug!(
-                    "sending ping request for txn ID: {:?} with lease: {:?}",
-                    txn_id, self.txn_lease_millis
-                );
-                let status = self
-                    .factory
-                    .get_controller_client()
-                    .ping_transaction(
-                        &self.stream,
-                        txn_id.to_owned(),
-                        Duration::from_millis(self.txn_lease_millis),
-                    )
-                    .await
-                    .expect("ping transaction");
-
-                if let PingStatus::Ok = status {
-                    debug!("successfully pinged transaction {:?}", txn_id);
-                } else {
-                    debug!("transaction {:?} is committed/aborted", txn_id);
-                    completed_txns.insert(txn_id.to_owned());
+        info!("start sending pings to {} transactions.", due.len());
+        for txn_id in due {
+            let lease_millis = txns.get(&txn_id).expect("must have transaction").lease_millis;
+            debug!(
+                "sending ping request for txn ID: {:?} with lease: {:?}",
+                txn_id, lease_millis
+            );
+            let result = self
+                .factory
+                .get_controller_client()
+                .ping_transaction(&self.stream, txn_id, Duration::from_millis(lease_millis))
+                .await;
+
+            if let Some(status) = Pinger::get_final_status(txn_id, result) {
+                debug!("transaction {:?} is no longer pinged: {:?}", txn_id, status);
+                let txn = txns.remove(&txn_id).expect("must have transaction");
+                if txn.status_sender.broadcast(status).is_err() {
+                    debug!("ping status of transaction {:?} is not watched", txn_id);
                 }
+            } else {
+                let txn = txns.get_mut(&txn_id).expect("must have transaction");
+                txn.next_ping =
+                    Instant::now() + Duration::from_millis(Pinger::get_ping_interval(lease_millis));
             }
-            info!("sending transaction pings complete.");
+        }
+        info!("sending transaction pings complete.");
+    }
 
-            // delay for transaction lease milliseconds.
-            delay_for(Duration::from_millis(self.txn_lease_millis)).await;
+    // Returns the status of a transaction that is no longer pinged, or None if the transaction
+    // should be pinged again.
+    fn get_final_status(
+        txn_id: TxId,
+        result: Result<PingStatus, RetryError<ControllerError>>,
+    ) -> Option<PingStatus> {
+        match result {
+            Ok(PingStatus::Ok) => {
+                debug!("successfully pinged transaction {:?}", txn_id);
+                None
+            }
+            Ok(status) => Some(status),
+            Err(e) if e.error.can_retry() => {
+                warn!(
+                    "failed to ping transaction {:?} due to {:?}, retrying on the next tick",
+                    txn_id, e
+                );
+                None
+            }
+            Err(e) => {
+                error!("failed to ping transaction {:?} due to {:?}", txn_id, e);
+                Some(PingStatus::Failed)
+            }
         }
     }
 
@@ -175,6 +228,36 @@ impl Pinger {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_get_final_status() {
+        let txn_id = TxId(0);
+        let error = |can_retry| RetryError {
+            error: ControllerError::OperationError {
+                can_retry,
+                operation: "pingTransaction".to_owned(),
+                error_msg: "error".to_owned(),
+            },
+            total_delay: Duration::from_millis(0),
+            tries: 1,
+        };
+
+        assert_eq!(Pinger::get_final_status(txn_id, Ok(PingStatus::Ok)), None);
+        assert_eq!(
+            Pinger::get_final_status(txn_id, Ok(PingStatus::Committed)),
+            Some(PingStatus::Committed)
+        );
+        assert_eq!(
+            Pinger::get_final_status(txn_id, Ok(PingStatus::Aborted)),
+            Some(PingStatus::Aborted)
+        );
+        // a transient error is retried on the next tick.
+        assert_eq!(Pinger::get_final_status(txn_id, Err(error(true))), None);
+        assert_eq!(
+            Pinger::get_final_status(txn_id, Err(error(false))),
+            Some(PingStatus::Failed)
+        );
+    }
+
     #[test]
     fn test_get_ping_interval() {
         assert_eq!(Pinger::get_ping_interval(1000u64), 1000u64);
@@ -10,17 +10,18 @@
 
 use crate::client_factory::ClientFactory;
 use crate::error::*;
+use crate::get_random_u128;
 use crate::reactor::event::{Incoming, PendingEvent};
-use crate::reactor::segment_writer::SegmentWriter;
+use crate::reactor::segment_writer::{BatchPolicy, SegmentWriter};
 use pravega_rust_client_auth::DelegationTokenProvider;
+use pravega_rust_client_channel::{create_channel, ChannelReceiver};
 use pravega_rust_client_retry::retry_policy::RetryWithBackoff;
-use pravega_rust_client_shared::ScopedSegment;
+use pravega_rust_client_shared::{ScopedSegment, WriterId};
 use pravega_wire_protocol::commands::DataAppendedCommand;
 use pravega_wire_protocol::wire_commands::Replies;
 use snafu::ResultExt;
 use std::sync::Arc;
 use tokio::sync::mpsc::error::TryRecvError;
-use tokio::sync::mpsc::{channel, Receiver};
 use tokio::sync::oneshot;
 use tracing::{debug, error, warn};
 
@@ -29,7 +30,7 @@ use tracing::{debug, error, warn};
 pub(super) struct TransactionalEventSegmentWriter {
     segment: ScopedSegment,
     event_segment_writer: SegmentWriter,
-    recevier: Receiver<Incoming>,
+    recevier: ChannelReceiver<Incoming>,
     // Only need to hold onto the latest event since if any previous events failed, the last one will also fail
     outstanding: Option<oneshot::Receiver<Result<(), SegmentWriterError>>>,
 }
@@ -42,9 +43,18 @@ impl TransactionalEventSegmentWriter {
         retry_policy: RetryWithBackoff,
         delegation_token_provider: Arc<DelegationTokenProvider>,
     ) -> Self {
-        let (tx, rx) = channel(TransactionalEventSegmentWriter::CHANNEL_CAPACITY);
-        let event_segment_writer =
-            SegmentWriter::new(segment.clone(), tx, retry_policy, delegation_token_provider);
+        let (tx, rx) = create_channel(TransactionalEventSegmentWriter::CHANNEL_CAPACITY);
+        // the transactional writer only processes server replies, so its events never linger.
+        // every writer uses a fresh writer id, a transaction resumed after a restart does not
+        // share the event numbers of the writer that wrote to it before.
+        let event_segment_writer = SegmentWriter::new(
+            WriterId::from(get_random_u128()),
+            segment.clone(),
+            tx,
+            retry_policy,
+            delegation_token_provider,
+            BatchPolicy::default(),
+        );
         TransactionalEventSegmentWriter {
             segment,
             event_segment_writer,
@@ -71,7 +81,7 @@ impl TransactionalEventSegmentWriter {
         let (oneshot_tx, oneshot_rx) = oneshot::channel();
         if let Some(pending_event) = PendingEvent::with_header(None, event, oneshot_tx) {
             self.event_segment_writer
-                .write(pending_event)
+                .write(pending_event, factory)
                 .await
                 .context(WriterError {})?;
         }
@@ -97,7 +107,7 @@ impl TransactionalEventSegmentWriter {
         &mut self,
         factory: &ClientFactory,
     ) -> Result<(), TransactionalEventSegmentWriterError> {
-        if let Some(event) = self.recevier.recv().await {
+        if let Some((event, _size)) = self.recevier.recv().await {
             self.process_server_reply(event, factory).await?;
             Ok(())
         } else {
@@ -113,7 +123,7 @@ impl TransactionalEventSegmentWriter {
     ) -> Result<(), TransactionalEventSegmentWriterError> {
         loop {
             match self.recevier.try_recv() {
-                Ok(event) => self.process_server_reply(event, factory).await?,
+                Ok((event, _size)) => self.process_server_reply(event, factory).await?,
                 // No reply from the server yet, just return ok.
                 Err(TryRecvError::Empty) => return Ok(()),
                 Err(e) => return Err(TransactionalEventSegmentWriterError::MpscError { source: e }),
@@ -167,7 +177,7 @@ impl TransactionalEventSegmentWriter {
         );
 
         self.event_segment_writer.ack(cmd.event_number);
-        if let Err(e) = self.event_segment_writer.write_pending_events().await {
+        if let Err(e) = self.event_segment_writer.write_pending_events(factory).await {
             warn!(
                 "writer {:?} failed to flush data to segment {:?} due to {:?}, reconnecting",
                 self.event_segment_writer.id,
@@ -14,11 +14,14 @@ use crate::transaction::pinger::{Pinger, PingerHandle};
 use crate::transaction::transactional_event_segment_writer::TransactionalEventSegmentWriter;
 use crate::transaction::{Transaction, TransactionInfo};
 use pravega_rust_client_auth::DelegationTokenProvider;
-use pravega_rust_client_shared::{ScopedStream, StreamSegments, TransactionStatus, TxId, WriterId};
+use pravega_rust_client_shared::{
+    PingStatus, ScopedStream, StreamSegments, TransactionStatus, TxId, WriterId,
+};
 use snafu::ResultExt;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
 use tracing::{info, info_span};
 use tracing_futures::Instrument;
 
@@ -73,11 +76,7 @@ pub struct TransactionalEventStreamWriter {
 impl TransactionalEventStreamWriter {
     // use ClientFactory to initialize a TransactionalEventStreamWriter.
     pub(crate) async fn new(stream: ScopedStream, writer_id: WriterId, factory: ClientFactory) -> Self {
-        let (mut pinger, pinger_handle) = Pinger::new(
-            stream.clone(),
-            factory.get_config().transaction_timeout_time,
-            factory.clone(),
-        );
+        let (mut pinger, pinger_handle) = Pinger::new(stream.clone(), factory.clone());
         let delegation_token_provider =
             Arc::new(factory.create_delegation_token_provider(stream.clone()).await);
         let runtime_handle = factory.get_runtime_handle();
@@ -92,22 +91,83 @@ impl TransactionalEventStreamWriter {
         }
     }
 
-    /// This method opens a transaction by sending a request to Pravega controller.
+    /// This method opens a transaction by sending a request to Pravega controller. The lease of
+    /// the transaction is the transaction timeout time of the client config.
     pub async fn begin(&mut self) -> Result<Transaction, TransactionalEventStreamWriterError> {
+        let lease = Duration::from_millis(self.factory.get_config().transaction_timeout_time);
+        self.begin_with_lease(lease).await
+    }
+
+    /// This method opens a transaction with the given lease. The transaction is pinged with this
+    /// lease until it is committed, aborted or its pinging is stopped, so a long running
+    /// transaction can use a larger lease than a short lived one.
+    pub async fn begin_with_lease(
+        &mut self,
+        lease: Duration,
+    ) -> Result<Transaction, TransactionalEventStreamWriterError> {
         let txn_segments = self
             .factory
             .get_controller_client()
-            .create_transaction(
-                &self.stream,
-                Duration::from_millis(self.factory.get_config().transaction_timeout_time),
-            )
+            .create_transaction(&self.stream, lease)
             .await
             .map_err(|e| e.error)
             .context(TxnStreamControllerError {})?;
         info!("Transaction {} created", txn_segments.tx_id);
-        let txn_id = txn_segments.tx_id;
+        self.open_txn(txn_segments.tx_id, txn_segments.stream_segments, lease)
+            .await
+    }
+
+    /// This method resumes a transaction that was opened before, typically by a writer that ran
+    /// in a process which crashed after persisting the transaction id. If the transaction is
+    /// still open it is pinged again using the transaction timeout time of the client config
+    /// as the lease, and events can be written to it before it is committed or aborted.
+    /// Otherwise a closed transaction is returned, see `get_txn`.
+    pub async fn resume_txn(
+        &mut self,
+        txn_id: TxId,
+    ) -> Result<Transaction, TransactionalEventStreamWriterError> {
+        let lease = Duration::from_millis(self.factory.get_config().transaction_timeout_time);
+        self.resume_txn_with_lease(txn_id, lease).await
+    }
+
+    /// Same as `resume_txn` but pings the transaction with the given lease.
+    pub async fn resume_txn_with_lease(
+        &mut self,
+        txn_id: TxId,
+        lease: Duration,
+    ) -> Result<Transaction, TransactionalEventStreamWriterError> {
+        // the lease may be about to expire, so it is extended right away.
+        let status = self
+            .factory
+            .get_controller_client()
+            .ping_transaction(&self.stream, txn_id, lease)
+            .await
+            .map_err(|e| e.error)
+            .context(TxnStreamControllerError {})?;
+        if status != PingStatus::Ok {
+            info!("Transaction {} is no longer open: {:?}", txn_id, status);
+            return self.get_txn(txn_id).await;
+        }
+        let segments = self
+            .factory
+            .get_controller_client()
+            .get_epoch_segments(&self.stream, txn_id.get_epoch())
+            .await
+            .map_err(|e| e.error)
+            .context(TxnStreamControllerError {})?;
+        info!("Transaction {} resumed", txn_id);
+        self.open_txn(txn_id, segments, lease).await
+    }
+
+    // Creates the writers of an open transaction and adds the transaction to the ping list.
+    async fn open_txn(
+        &mut self,
+        txn_id: TxId,
+        segments: StreamSegments,
+        lease: Duration,
+    ) -> Result<Transaction, TransactionalEventStreamWriterError> {
         let mut transactions = HashMap::new();
-        for s in txn_segments.stream_segments.get_segments() {
+        for s in segments.get_segments() {
             let mut txn_segment = s.clone();
             txn_segment.segment.tx_id = Some(txn_id);
             let mut writer = TransactionalEventSegmentWriter::new(
@@ -118,12 +178,14 @@ impl TransactionalEventStreamWriter {
             writer.initialize(&self.factory).await;
             transactions.insert(s, writer);
         }
-        self.pinger_handle.add(txn_id).await?;
+        let (status_sender, status_receiver) = watch::channel(PingStatus::Ok);
+        self.pinger_handle.add(txn_id, lease, status_sender).await?;
         Ok(Transaction::new(
             TransactionInfo::new(txn_id, self.writer_id, self.stream.clone(), false),
             transactions,
-            txn_segments.stream_segments,
+            segments,
             self.pinger_handle.clone(),
+            Some(status_receiver),
             self.factory.clone(),
         ))
     }
@@ -146,6 +208,7 @@ impl TransactionalEventStreamWriter {
                 HashMap::new(),
                 StreamSegments::new(BTreeMap::new()),
                 self.pinger_handle.clone(),
+                None,
                 self.factory.clone(),
             ));
         }
@@ -170,6 +233,7 @@ impl TransactionalEventStreamWriter {
             transactions,
             segments,
             self.pinger_handle.clone(),
+            None,
             self.factory.clone(),
         ))
     }
@@ -178,10 +242,11 @@ impl TransactionalEventStreamWriter {
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
-    use crate::create_stream;
+    use crate::{create_stream, read_segment};
     use pravega_rust_client_config::connection_type::{ConnectionType, MockType};
     use pravega_rust_client_config::ClientConfigBuilder;
-    use pravega_rust_client_shared::{PravegaNodeUri, ScopedSegment};
+    use pravega_rust_client_shared::{PravegaNodeUri, ScopedSegment, Timestamp};
+    use pravega_wire_protocol::commands::{Command, EventCommand};
     use tokio::runtime::Runtime;
 
     #[test]
@@ -195,6 +260,48 @@ pub(crate) mod test {
         assert_eq!(transaction.get_txn_id(), fetched_transaction.get_txn_id());
     }
 
+    #[test]
+    fn test_resume_txn() {
+        let mut rt = Runtime::new().unwrap();
+        let mut txn_stream_writer = rt.block_on(create_txn_stream_writer());
+        let mut transaction = rt.block_on(txn_stream_writer.begin()).expect("open transaction");
+        rt.block_on(transaction.write_event(None, vec![1; 1024]))
+            .expect("write event");
+        let txn_id = transaction.get_txn_id();
+        drop(transaction);
+
+        // an open transaction can be written and committed once resumed
+        let mut resumed = rt
+            .block_on(txn_stream_writer.resume_txn(txn_id))
+            .expect("resume transaction");
+        assert!(resumed.ping_status().is_some());
+        rt.block_on(resumed.write_event(None, vec![2; 1024]))
+            .expect("write event");
+        rt.block_on(resumed.commit(Timestamp(0)))
+            .expect("commit transaction");
+        let status = rt.block_on(resumed.check_status()).expect("check status");
+        assert_eq!(status, TransactionStatus::Committed);
+
+        // the transaction segment that is merged on commit holds the events written before and
+        // after the resume, the mock controller does not merge it into the stream segment.
+        let mut txn_segment = ScopedSegment::from("scope/stream/0");
+        txn_segment.segment.tx_id = Some(txn_id);
+        let data = rt.block_on(read_segment(&txn_stream_writer.factory, &txn_segment));
+        let mut expected = vec![];
+        for i in 1..=2 {
+            let event = EventCommand { data: vec![i; 1024] };
+            expected.extend(event.write_fields().expect("encode event"));
+        }
+        assert_eq!(data, expected);
+
+        // a committed transaction is resumed as a closed transaction
+        let mut resumed = rt
+            .block_on(txn_stream_writer.resume_txn(txn_id))
+            .expect("resume transaction");
+        assert!(resumed.ping_status().is_none());
+        assert!(rt.block_on(resumed.commit(Timestamp(0))).is_err());
+    }
+
     // helper function
     pub(crate) async fn create_txn_stream_writer() -> TransactionalEventStreamWriter {
         let txn_segment = ScopedSegment::from("scope/stream/0");
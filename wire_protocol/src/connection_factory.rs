@@ -143,10 +143,13 @@ impl ConnectionFactory for TokioConnectionFactory {
 
 type TableSegmentIndex = HashMap<String, HashMap<TableKey, TableValue>>;
 type TableSegment = HashMap<String, Vec<(TableKey, TableValue)>>;
+type WriterEventNumbers = HashMap<(u128, String), i64>;
+type SegmentData = HashMap<String, Vec<u8>>;
 
 struct MockConnectionFactory {
     segments: Arc<Mutex<HashMap<String, SegmentInfo>>>,
-    writers: Arc<Mutex<HashMap<u128, String>>>,
+    segment_data: Arc<Mutex<SegmentData>>,
+    writers: Arc<Mutex<WriterEventNumbers>>,
     table_segment_index: Arc<Mutex<TableSegmentIndex>>,
     table_segment: Arc<Mutex<TableSegment>>,
     mock_type: MockType,
@@ -156,6 +159,7 @@ impl MockConnectionFactory {
     pub fn new(mock_type: MockType) -> Self {
         MockConnectionFactory {
             segments: Arc::new(Mutex::new(HashMap::new())),
+            segment_data: Arc::new(Mutex::new(HashMap::new())),
             writers: Arc::new(Mutex::new(HashMap::new())),
             table_segment_index: Arc::new(Mutex::new(HashMap::new())),
             table_segment: Arc::new(Mutex::new(HashMap::new())),
@@ -173,6 +177,7 @@ impl ConnectionFactory for MockConnectionFactory {
         let mock = MockConnection::new(
             endpoint,
             self.segments.clone(),
+            self.segment_data.clone(),
             self.writers.clone(),
             self.table_segment_index.clone(),
             self.table_segment.clone(),
@@ -10,9 +10,10 @@
 
 extern crate byteorder;
 use crate::commands::{
-    AppendSetupCommand, DataAppendedCommand, SegmentAlreadyExistsCommand, SegmentCreatedCommand,
-    SegmentIsSealedCommand, SegmentIsTruncatedCommand, SegmentReadCommand, SegmentSealedCommand,
-    SegmentTruncatedCommand, StreamSegmentInfoCommand, TableEntries, TableEntriesDeltaReadCommand,
+    AppendSetupCommand, Command, ConditionalCheckFailedCommand, DataAppendedCommand, NoSuchSegmentCommand,
+    SegmentAlreadyExistsCommand, SegmentCreatedCommand, SegmentDeletedCommand, SegmentIsSealedCommand,
+    SegmentIsTruncatedCommand, SegmentReadCommand, SegmentSealedCommand, SegmentTruncatedCommand,
+    SegmentsMergedCommand, StreamSegmentInfoCommand, TableEntries, TableEntriesDeltaReadCommand,
     TableEntriesUpdatedCommand, TableKey, TableKeyBadVersionCommand, TableKeyDoesNotExistCommand,
     TableKeysRemovedCommand, TableReadCommand, TableValue, WrongHostCommand,
 };
@@ -22,10 +23,12 @@ use crate::wire_commands::{Decode, Encode, Replies, Requests};
 use async_trait::async_trait;
 use pravega_rust_client_config::connection_type::MockType;
 use pravega_rust_client_shared::{PravegaNodeUri, ScopedSegment, SegmentInfo};
+use snafu::ResultExt;
 use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
+use std::io;
 use std::sync::Arc;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::Mutex;
@@ -33,6 +36,8 @@ use uuid::Uuid;
 
 type TableSegmentIndex = HashMap<String, HashMap<TableKey, TableValue>>;
 type TableSegment = HashMap<String, Vec<(TableKey, TableValue)>>;
+type WriterEventNumbers = HashMap<(u128, String), i64>;
+type SegmentData = HashMap<String, Vec<u8>>;
 
 pub struct MockConnection {
     id: Uuid,
@@ -44,8 +49,12 @@ pub struct MockConnection {
     buffer_offset: usize,
     // maps from segment to segment info
     segments: Arc<Mutex<HashMap<String, SegmentInfo>>>,
-    // maps from writerId to segment
-    writers: Arc<Mutex<HashMap<u128, String>>>,
+    // maps from segment to the data appended to it
+    segment_data: Arc<Mutex<SegmentData>>,
+    // maps from writerId and segment to the last event number appended
+    writers: Arc<Mutex<WriterEventNumbers>>,
+    // maps from writerId to the segment it appends to on this connection
+    appending: HashMap<u128, String>,
     // table segment index
     table_segment_index: Arc<Mutex<TableSegmentIndex>>,
     // table segment
@@ -56,7 +65,8 @@ impl MockConnection {
     pub fn new(
         endpoint: PravegaNodeUri,
         segments: Arc<Mutex<HashMap<String, SegmentInfo>>>,
-        writers: Arc<Mutex<HashMap<u128, String>>>,
+        segment_data: Arc<Mutex<SegmentData>>,
+        writers: Arc<Mutex<WriterEventNumbers>>,
         table_segment_index: Arc<Mutex<TableSegmentIndex>>,
         table_segment: Arc<Mutex<TableSegment>>,
         mock_type: MockType,
@@ -71,7 +81,9 @@ impl MockConnection {
             buffer: vec![],
             buffer_offset: 0,
             segments,
+            segment_data,
             writers,
+            appending: HashMap::new(),
             table_segment_index,
             table_segment,
         }
@@ -82,16 +94,19 @@ impl MockConnection {
 impl Connection for MockConnection {
     async fn send_async(&mut self, payload: &[u8]) -> Result<(), ConnectionError> {
         let mut segments_guard = self.segments.lock().await;
+        let mut segment_data_guard = self.segment_data.lock().await;
         let mut writers_guard = self.writers.lock().await;
         let mut table_segment_index_guard = self.table_segment_index.lock().await;
         let mut table_segment_guard = self.table_segment.lock().await;
         match self.mock_type {
-            MockType::Happy => {
+            MockType::Happy | MockType::ConnectionLostAfterAppend => {
                 send_happy(
                     self.sender.as_mut().expect("get sender"),
                     payload,
                     &mut *segments_guard,
+                    &mut *segment_data_guard,
                     &mut *writers_guard,
+                    &mut self.appending,
                     &mut *table_segment_index_guard,
                     &mut *table_segment_guard,
                 )
@@ -128,6 +143,8 @@ impl Connection for MockConnection {
     fn split(&mut self) -> (Box<dyn ConnectionReadHalf>, Box<dyn ConnectionWriteHalf>) {
         let reader = Box::new(MockReadingConnection {
             id: self.id,
+            mock_type: self.mock_type,
+            endpoint: self.endpoint.clone(),
             receiver: self
                 .receiver
                 .take()
@@ -140,7 +157,9 @@ impl Connection for MockConnection {
             mock_type: self.mock_type,
             sender: self.sender.take().expect("split mock connection and get sender"),
             segments: self.segments.clone(),
+            segment_data: self.segment_data.clone(),
             writers: self.writers.clone(),
+            appending: self.appending.clone(),
             table_segment_index: self.table_segment_index.clone(),
             table_segment: self.table_segment.clone(),
         }) as Box<dyn ConnectionWriteHalf>;
@@ -171,6 +190,8 @@ impl Debug for MockConnection {
 
 pub struct MockReadingConnection {
     id: Uuid,
+    mock_type: MockType,
+    endpoint: PravegaNodeUri,
     receiver: UnboundedReceiver<Replies>,
     buffer: Vec<u8>,
     index: usize,
@@ -183,8 +204,12 @@ pub struct MockWritingConnection {
     sender: UnboundedSender<Replies>,
     // maps from segment to segment info
     segments: Arc<Mutex<HashMap<String, SegmentInfo>>>,
-    // maps from writerId to segment
-    writers: Arc<Mutex<HashMap<u128, String>>>,
+    // maps from segment to the data appended to it
+    segment_data: Arc<Mutex<SegmentData>>,
+    // maps from writerId and segment to the last event number appended
+    writers: Arc<Mutex<WriterEventNumbers>>,
+    // maps from writerId to the segment it appends to on this connection
+    appending: HashMap<u128, String>,
     // table segment index
     table_segment_index: Arc<Mutex<TableSegmentIndex>>,
     // table segment
@@ -195,7 +220,15 @@ pub struct MockWritingConnection {
 impl ConnectionReadHalf for MockReadingConnection {
     async fn read_async(&mut self, buf: &mut [u8]) -> Result<(), ConnectionError> {
         if self.index == self.buffer.len() {
-            let reply: Replies = self.receiver.recv().await.expect("read");
+            let reply: Replies = match self.receiver.recv().await {
+                Some(reply) => reply,
+                None if self.mock_type == MockType::ConnectionLostAfterAppend => {
+                    return Err(io::Error::from(io::ErrorKind::ConnectionAborted)).context(ReadData {
+                        endpoint: self.endpoint.clone(),
+                    });
+                }
+                None => panic!("read"),
+            };
             self.buffer = reply.write_fields().expect("serialize reply");
             self.index = 0;
         }
@@ -214,16 +247,26 @@ impl ConnectionReadHalf for MockReadingConnection {
 impl ConnectionWriteHalf for MockWritingConnection {
     async fn send_async(&mut self, payload: &[u8]) -> Result<(), ConnectionError> {
         let mut segments_guard = self.segments.lock().await;
+        let mut segment_data_guard = self.segment_data.lock().await;
         let mut writers_guard = self.writers.lock().await;
         let mut table_segment_index_guard = self.table_segment_index.lock().await;
         let mut table_segment_guard = self.table_segment.lock().await;
         match self.mock_type {
-            MockType::Happy => {
+            MockType::Happy | MockType::ConnectionLostAfterAppend => {
+                // the reply of a conditional append is lost together with the connection.
+                let (lost_sender, _lost_receiver) = unbounded_channel();
+                if self.mock_type == MockType::ConnectionLostAfterAppend
+                    && matches!(Requests::read_from(payload), Ok(Requests::ConditionalAppend(_)))
+                {
+                    self.sender = lost_sender;
+                }
                 send_happy(
                     &mut self.sender,
                     payload,
                     &mut *segments_guard,
+                    &mut *segment_data_guard,
                     &mut *writers_guard,
+                    &mut self.appending,
                     &mut *table_segment_index_guard,
                     &mut *table_segment_guard,
                 )
@@ -244,7 +287,9 @@ async fn send_happy(
     sender: &mut UnboundedSender<Replies>,
     payload: &[u8],
     segments: &mut HashMap<String, SegmentInfo>,
-    writers: &mut HashMap<u128, String>,
+    segment_data: &mut SegmentData,
+    writers: &mut WriterEventNumbers,
+    appending: &mut HashMap<u128, String>,
     table_segment_index: &mut HashMap<String, HashMap<TableKey, TableValue>>,
     table_segment: &mut HashMap<String, Vec<(TableKey, TableValue)>>,
 ) -> Result<(), ConnectionError> {
@@ -263,17 +308,21 @@ async fn send_happy(
                 is_sealed: false,
                 last_modified_time: 0,
             });
-            writers.insert(cmd.writer_id, cmd.segment.to_string());
+            appending.insert(cmd.writer_id, cmd.segment.to_string());
+            // i64::MIN when there is no previous event of this writer in this segment
+            let last_event_number = *writers
+                .get(&(cmd.writer_id, cmd.segment.to_string()))
+                .unwrap_or(&i64::MIN);
             let reply = Replies::AppendSetup(AppendSetupCommand {
                 request_id: cmd.request_id,
                 segment: cmd.segment,
                 writer_id: cmd.writer_id,
-                last_event_number: i64::MIN, // when there is no previous event in this segment
+                last_event_number,
             });
             sender.send(reply).expect("send reply");
         }
         Requests::AppendBlockEnd(cmd) => {
-            let segment = writers.get(&cmd.writer_id).expect("writer hasn't been set up");
+            let segment = appending.get(&cmd.writer_id).expect("writer hasn't been set up");
             let segment_info = segments.get_mut(segment).expect("segment is not created");
             if segment_info.is_sealed {
                 let reply = Replies::SegmentIsSealed(SegmentIsSealedCommand {
@@ -286,13 +335,121 @@ async fn send_happy(
                 return Ok(());
             }
             segment_info.write_offset += cmd.data.len() as i64;
+            segment_data
+                .entry(segment.to_string())
+                .or_default()
+                .extend_from_slice(&cmd.data);
+            writers.insert((cmd.writer_id, segment.to_string()), cmd.last_event_number);
 
             let reply = Replies::DataAppended(DataAppendedCommand {
                 writer_id: cmd.writer_id,
                 event_number: cmd.last_event_number,
                 previous_event_number: 0, //not used in event stream writer
                 request_id: cmd.request_id,
-                current_segment_write_offset: 0, //not used in event stream writer
+                current_segment_write_offset: segment_info.write_offset,
+            });
+            sender.send(reply).expect("send reply");
+        }
+        Requests::ConditionalAppend(cmd) => {
+            let segment = appending.get(&cmd.writer_id).expect("writer hasn't been set up");
+            let segment_info = segments.get_mut(segment).expect("segment is not created");
+            let reply = if segment_info.is_sealed {
+                Replies::SegmentIsSealed(SegmentIsSealedCommand {
+                    request_id: cmd.request_id,
+                    segment: segment.to_string(),
+                    server_stack_trace: "".to_string(),
+                    offset: 0,
+                })
+            } else if segment_info.write_offset != cmd.expected_offset {
+                Replies::ConditionalCheckFailed(ConditionalCheckFailedCommand {
+                    writer_id: cmd.writer_id,
+                    event_number: cmd.event_number,
+                    request_id: cmd.request_id,
+                })
+            } else {
+                // the event is appended together with its header
+                let event = cmd.event.write_fields().expect("encode event");
+                segment_info.write_offset += event.len() as i64;
+                segment_data.entry(segment.to_string()).or_default().extend(event);
+                let previous_event_number = writers
+                    .insert((cmd.writer_id, segment.to_string()), cmd.event_number)
+                    .unwrap_or(i64::MIN);
+                Replies::DataAppended(DataAppendedCommand {
+                    writer_id: cmd.writer_id,
+                    event_number: cmd.event_number,
+                    previous_event_number,
+                    request_id: cmd.request_id,
+                    current_segment_write_offset: segment_info.write_offset,
+                })
+            };
+            sender.send(reply).expect("send reply");
+        }
+        Requests::CreateSegment(cmd) => {
+            let reply = if segments.contains_key(&cmd.segment) {
+                Replies::SegmentAlreadyExists(SegmentAlreadyExistsCommand {
+                    request_id: cmd.request_id,
+                    segment: cmd.segment,
+                    server_stack_trace: "".to_string(),
+                })
+            } else {
+                segments.insert(
+                    cmd.segment.to_string(),
+                    SegmentInfo {
+                        segment: ScopedSegment::from(&*cmd.segment),
+                        starting_offset: 0,
+                        write_offset: 0,
+                        is_sealed: false,
+                        last_modified_time: 0,
+                    },
+                );
+                Replies::SegmentCreated(SegmentCreatedCommand {
+                    request_id: cmd.request_id,
+                    segment: cmd.segment,
+                })
+            };
+            sender.send(reply).expect("send reply");
+        }
+        Requests::MergeSegments(cmd) => {
+            let source_length = segments.get(&cmd.source).map(|source| source.write_offset);
+            let target = segments.get_mut(&cmd.target).expect("segment is not created");
+            let reply = match source_length {
+                None => Replies::NoSuchSegment(NoSuchSegmentCommand {
+                    request_id: cmd.request_id,
+                    segment: cmd.source,
+                    server_stack_trace: "".to_string(),
+                    offset: 0,
+                }),
+                Some(_) if target.is_sealed => Replies::SegmentIsSealed(SegmentIsSealedCommand {
+                    request_id: cmd.request_id,
+                    segment: cmd.target,
+                    server_stack_trace: "".to_string(),
+                    offset: 0,
+                }),
+                Some(source_length) => {
+                    target.write_offset += source_length;
+                    let new_target_write_offset = target.write_offset;
+                    segments.remove(&cmd.source);
+                    let source_data = segment_data.remove(&cmd.source).unwrap_or_default();
+                    segment_data
+                        .entry(cmd.target.clone())
+                        .or_default()
+                        .extend(source_data);
+                    Replies::SegmentsMerged(SegmentsMergedCommand {
+                        request_id: cmd.request_id,
+                        target: cmd.target,
+                        source: cmd.source,
+                        new_target_write_offset,
+                    })
+                }
+            };
+            sender.send(reply).expect("send reply");
+        }
+        Requests::DeleteSegment(cmd) => {
+            segments.remove(&cmd.segment);
+            segment_data.remove(&cmd.segment);
+            let reply = Replies::SegmentDeleted(SegmentDeletedCommand {
+                request_id: cmd.request_id,
+                segment: cmd.segment,
             });
             sender.send(reply).expect("send reply");
         }
@@ -335,7 +492,6 @@ async fn send_happy(
         }
         Requests::ReadSegment(cmd) => {
             let segment_info = segments.get(&cmd.segment).expect("segment is not created");
-            assert!(segment_info.write_offset >= cmd.offset);
 
             let reply = if cmd.offset < segment_info.starting_offset {
                 Replies::SegmentIsTruncated(SegmentIsTruncatedCommand {
@@ -346,16 +502,21 @@ async fn send_happy(
                     offset: cmd.offset,
                 })
             } else {
-                let read_length = cmp::min(
-                    segment_info.write_offset - cmd.offset,
-                    cmd.suggested_length as i64,
+                // a read beyond the tail finds no data, like a read that waits at the tail.
+                let read_length = cmp::max(
+                    cmp::min(
+                        segment_info.write_offset - cmd.offset,
+                        cmd.suggested_length as i64,
+                    ),
+                    0,
                 );
                 Replies::SegmentRead(SegmentReadCommand {
                     segment: cmd.segment.to_string(),
                     offset: cmd.offset,
                     at_tail: false,
-                    end_of_segment: false,
-                    data: vec![1; read_length as usize],
+                    // there is nothing more to read from a sealed segment.
+                    end_of_segment: segment_info.is_sealed && read_length == 0,
+                    data: read_data(segment_data.get(&cmd.segment), cmd.offset, read_length),
                     request_id: cmd.request_id,
                 })
             };
@@ -384,12 +545,11 @@ async fn send_happy(
                 .get_mut(&cmd.segment)
                 .expect("get table segment index");
             let segment = table_segment.get_mut(&cmd.segment).expect("get table segment");
-            let mut versions = vec![];
-            for (k, v) in cmd.table_entries.entries {
+            // the updates are applied atomically, check all the key versions before applying any.
+            for (k, _v) in &cmd.table_entries.entries {
                 let old_version = index
-                    .get_key_value(&k)
+                    .get_key_value(k)
                     .map_or_else(|| -1, |(key, _value)| key.key_version);
-                versions.push(old_version + 1);
 
                 // -1 is key not exist, i64 min_value is unconditionally insert
                 if k.key_version != old_version && k.key_version != i64::min_value() {
@@ -401,6 +561,13 @@ async fn send_happy(
                     sender.send(reply).expect("send reply");
                     return Ok(());
                 }
+            }
+            let mut versions = vec![];
+            for (k, v) in cmd.table_entries.entries {
+                let old_version = index
+                    .get_key_value(&k)
+                    .map_or_else(|| -1, |(key, _value)| key.key_version);
+                versions.push(old_version + 1);
                 index.remove(&k); // delete the old key, or no-op if key doesn't exist
                 let new_key = TableKey {
                     payload: k.payload,
@@ -500,6 +667,15 @@ async fn send_happy(
     Ok(())
 }
 
+// Returns the data appended to the segment in the given range, or ones if it is not known.
+fn read_data(data: Option<&Vec<u8>>, offset: i64, length: i64) -> Vec<u8> {
+    let (start, end) = (offset as usize, (offset + length) as usize);
+    match data {
+        Some(data) if end <= data.len() => data[start..end].to_vec(),
+        _ => vec![1; length as usize],
+    }
+}
+
 async fn send_sealed(sender: &mut UnboundedSender<Replies>, payload: &[u8]) -> Result<(), ConnectionError> {
     let request: Requests = Requests::read_from(payload).expect("mock connection decode request");
     match request {
@@ -619,6 +795,7 @@ mod test {
             Arc::new(Mutex::new(HashMap::new())),
             Arc::new(Mutex::new(HashMap::new())),
             Arc::new(Mutex::new(HashMap::new())),
+            Arc::new(Mutex::new(HashMap::new())),
             MockType::Happy,
         );
         let request = Requests::Hello(HelloCommand {